@@ -1,5 +1,3 @@
-use std::convert::TryInto;
-
 use api_client::{
     error::*,
     request::{
@@ -12,12 +10,13 @@ use serde::{
     Deserialize,
     Serialize,
 };
+
+use crate::types::api::LastMatch;
+
 /// Query Parameters for [Get Last match](super::get_last_match)
 ///
 /// [`last_match`](https://aoe2.net/#api)
 // TODO
-use serde_json::Value as JsonValue;
-
 #[derive(PartialEq,
            typed_builder::TypedBuilder,
            Deserialize,
@@ -48,7 +47,7 @@ impl<'a> GetLastMatchRequest<'a> {
 }
 
 impl<'a> Request for GetLastMatchRequest<'a> {
-    type Response = Option<JsonValue>;
+    type Response = LastMatch;
 
     const PATH: &'static str = "player/lastmatch";
     const ROOT: &'static str = crate::AOE2_NET_URL;
@@ -59,39 +58,11 @@ impl<'a> RequestGet for GetLastMatchRequest<'a> {
         request: Option<Self>,
         uri: &http::Uri,
         response: http::Response<Vec<u8>>)
-        -> Result<Response<Self, Option<JsonValue>>, ApiRequestGetError>
+        -> Result<Response<Self, LastMatch>, ApiRequestGetError>
         where Self: Sized,
     {
-        let text = std::str::from_utf8(&response.body()).map_err(|e| {
-                       ApiRequestGetError::Utf8Error(response.body().clone(),
-                                                     e,
-                                                     uri.clone())
-                   })?;
-
-        if let Ok(ApiRequestError { error,
-                                    status,
-                                    message, }) =
-            serde_json::from_str::<ApiRequestError>(&text)
-        {
-            return Err(ApiRequestGetError::Error {
-                error,
-                status: status
-                    .try_into()
-                    .unwrap_or(http::StatusCode::BAD_REQUEST),
-                message,
-                uri: uri.clone(),
-            });
-        }
-        let response: JsonValue = serde_json::from_str(&text).map_err(|e| {
-                                      ApiRequestGetError::DeserializeError(
-                text.to_string(),
-                e,
-                uri.clone(),
-            )
-                                  })?;
-        Ok(Response { data: response.into(),
+        Ok(Response { data: super::parse_data(uri, &response)?,
                       pagination: None,
-                      // pagination: response.pagination.cursor,
                       request })
     }
 }
@@ -1,5 +1,8 @@
 //! Aoe2.net API endpoints
 //! Use together with the `api-client`
+//!
+//! Every request deserializes into the typed datastructures of
+//! [`crate::types::api`], wrap it into [`Raw`] to get the plain JSON instead.
 
 pub mod last_match;
 pub mod leaderboard;
@@ -11,3 +14,88 @@ pub mod rating;
 pub mod rating_history;
 pub mod stats_players;
 pub mod strings;
+
+use std::convert::TryInto;
+
+use api_client::{
+    error::*,
+    request::{
+        Request,
+        RequestGet,
+    },
+    response::Response,
+};
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use serde_json::Value as JsonValue;
+
+/// Wrapper around a request of this crate, that returns the response of
+/// aoe2.net as plain JSON instead of the typed datastructure
+#[derive(PartialEq, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Raw<R>(pub R);
+
+impl<R: Request> Request for Raw<R> {
+    type Response = Option<JsonValue>;
+
+    const PATH: &'static str = R::PATH;
+    const ROOT: &'static str = R::ROOT;
+}
+
+impl<R: Request> RequestGet for Raw<R> {
+    fn parse_response(
+        request: Option<Self>,
+        uri: &http::Uri,
+        response: http::Response<Vec<u8>>)
+        -> Result<Response<Self, Option<JsonValue>>, ApiRequestGetError>
+        where Self: Sized,
+    {
+        let data: JsonValue = parse_data(uri, &response)?;
+
+        Ok(Response { data: data.into(),
+                      pagination: None,
+                      request })
+    }
+}
+
+/// Parse the body of an aoe2.net `response` into `T`
+///
+/// # Errors
+/// Returns an error if the body is not valid UTF-8, is an error message of
+/// aoe2.net or can't be deserialized into `T`
+pub(crate) fn parse_data<T>(uri: &http::Uri,
+                            response: &http::Response<Vec<u8>>)
+                            -> Result<T, ApiRequestGetError>
+    where T: DeserializeOwned,
+{
+    let text = std::str::from_utf8(response.body()).map_err(|e| {
+                   ApiRequestGetError::Utf8Error(response.body().clone(),
+                                                 e,
+                                                 uri.clone())
+               })?;
+
+    if let Ok(ApiRequestError { error,
+                                status,
+                                message, }) =
+        serde_json::from_str::<ApiRequestError>(text)
+    {
+        return Err(ApiRequestGetError::Error {
+            error,
+            status: status
+                .try_into()
+                .unwrap_or(http::StatusCode::BAD_REQUEST),
+            message,
+            uri: uri.clone(),
+        });
+    }
+
+    serde_json::from_str(text).map_err(|e| {
+                                  ApiRequestGetError::DeserializeError(
+            text.to_string(),
+            e,
+            uri.clone(),
+        )
+                              })
+}
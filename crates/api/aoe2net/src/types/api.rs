@@ -1,11 +1,19 @@
-//! Datastructures of the aoe2net API (incomplete)
+//! Datastructures of the aoe2net API
+//!
+//! The typed responses tolerate unknown fields, missing fields and `null`
+//! values, as aoe2.net leaves out or nulls a lot of them depending on the
+//! match and the endpoint.
 
 use serde::{
+    de::DeserializeOwned,
     Deserialize,
+    Deserializer,
     Serialize,
 };
 use serde_json::Value as JsonValue;
 
+use super::helper::Aoe2netStringObj;
+
 /// Convenience datastructure for the `RatingHistory` endpoint to
 /// easily parse the data into our struct
 #[derive(Clone,
@@ -19,11 +27,17 @@ use serde_json::Value as JsonValue;
            Serialize,
            Deserialize)]
 pub struct RatingHistory {
+    #[serde(default, deserialize_with = "null_as_default")]
     pub drops: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub num_losses: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub num_wins: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub rating: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub streak: i64,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub timestamp: i64,
 }
 
@@ -56,3 +70,251 @@ pub struct Player {
     pub wins: JsonValue,
     pub won: JsonValue,
 }
+
+/// Response of the `player/lastmatch` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LastMatch {
+    pub profile_id: Option<i64>,
+    pub steam_id: Option<String>,
+    pub name: Option<String>,
+    pub country: Option<String>,
+    pub last_match: Option<Match>,
+}
+
+/// A match of the `match`, `matches` and `player/matches` endpoints and the
+/// `last_match` of the `player/lastmatch` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Match {
+    #[serde(deserialize_with = "string_or_number")]
+    pub match_id: Option<String>,
+    #[serde(deserialize_with = "string_or_number")]
+    pub lobby_id: Option<String>,
+    pub match_uuid: Option<String>,
+    pub version: Option<String>,
+    pub name: Option<String>,
+    pub num_players: Option<i64>,
+    pub num_slots: Option<i64>,
+    pub average_rating: Option<i64>,
+    pub cheats: Option<bool>,
+    pub full_tech_tree: Option<bool>,
+    pub ending_age: Option<i64>,
+    pub expansion: Option<String>,
+    pub game_type: Option<i64>,
+    pub has_custom_content: Option<bool>,
+    pub has_password: Option<bool>,
+    pub lock_speed: Option<bool>,
+    pub lock_teams: Option<bool>,
+    pub map_size: Option<i64>,
+    pub map_type: Option<i64>,
+    pub pop: Option<i64>,
+    pub ranked: Option<bool>,
+    pub leaderboard_id: Option<i64>,
+    pub rating_type: Option<i64>,
+    pub resources: Option<i64>,
+    pub rms: Option<String>,
+    pub scenario: Option<String>,
+    pub server: Option<String>,
+    pub shared_exploration: Option<bool>,
+    pub speed: Option<i64>,
+    pub starting_age: Option<i64>,
+    pub team_together: Option<bool>,
+    pub team_positions: Option<bool>,
+    pub treaty_length: Option<i64>,
+    pub turbo: Option<bool>,
+    pub victory: Option<i64>,
+    pub victory_time: Option<i64>,
+    pub visibility: Option<i64>,
+    pub opened: Option<i64>,
+    pub started: Option<i64>,
+    pub finished: Option<i64>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub players: Vec<MatchPlayer>,
+}
+
+/// An open lobby of the `lobbies` endpoint, which has the same layout as a
+/// [`Match`] that didn't start yet
+pub type Lobby = Match;
+
+/// A player or an open slot of a [`Match`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchPlayer {
+    pub profile_id: Option<i64>,
+    pub steam_id: Option<String>,
+    pub name: Option<String>,
+    pub clan: Option<String>,
+    pub country: Option<String>,
+    pub slot: Option<i64>,
+    pub slot_type: Option<i64>,
+    pub rating: Option<i64>,
+    #[serde(deserialize_with = "number_or_string")]
+    pub rating_change: Option<i64>,
+    pub games: Option<i64>,
+    pub wins: Option<i64>,
+    pub streak: Option<i64>,
+    pub drops: Option<i64>,
+    pub color: Option<i64>,
+    pub team: Option<i64>,
+    pub civ: Option<i64>,
+    pub won: Option<bool>,
+}
+
+/// Response of the `leaderboard` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Leaderboard {
+    pub total: Option<i64>,
+    pub leaderboard_id: Option<i64>,
+    pub start: Option<i64>,
+    pub count: Option<i64>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+/// A player on a [`Leaderboard`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeaderboardEntry {
+    pub profile_id: Option<i64>,
+    pub rank: Option<i64>,
+    pub rating: Option<i64>,
+    pub steam_id: Option<String>,
+    pub icon: Option<String>,
+    pub name: Option<String>,
+    pub clan: Option<String>,
+    pub country: Option<String>,
+    pub previous_rating: Option<i64>,
+    pub highest_rating: Option<i64>,
+    pub streak: Option<i64>,
+    pub lowest_streak: Option<i64>,
+    pub highest_streak: Option<i64>,
+    pub games: Option<i64>,
+    pub wins: Option<i64>,
+    pub losses: Option<i64>,
+    pub drops: Option<i64>,
+    pub last_match: Option<i64>,
+    pub last_match_time: Option<i64>,
+}
+
+/// Response of the `strings` endpoint, the translations of the IDs used in
+/// the other responses
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiStrings {
+    pub language: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub age: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub civ: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub game_type: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub leaderboard: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub map_size: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub map_type: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub rating_type: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub resources: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub speed: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub victory: Vec<Aoe2netStringObj>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub visibility: Vec<Aoe2netStringObj>,
+}
+
+/// Response of the `stats/players` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    pub app_id: Option<i64>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub player_stats: Vec<PlayerStatsEntry>,
+}
+
+/// Amount of players at a point in time of [`PlayerStats`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStatsEntry {
+    pub time: Option<i64>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub num_players: NumPlayers,
+}
+
+/// Amount of players by activity of a [`PlayerStatsEntry`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NumPlayers {
+    pub steam: Option<i64>,
+    pub multiplayer: Option<i64>,
+    pub looking: Option<i64>,
+    pub in_game: Option<i64>,
+    pub multiplayer_1h: Option<i64>,
+    pub multiplayer_24h: Option<i64>,
+}
+
+/// Deserialize `null` as `<T as Default>::default()`
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where D: Deserializer<'de>,
+          T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Deserialize an ID that aoe2.net sends as a string or as a number into a
+/// `String`
+fn string_or_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where D: Deserializer<'de>,
+{
+    match JsonValue::deserialize(deserializer)? {
+        JsonValue::Null => Ok(None),
+        JsonValue::String(string) => Ok(Some(string)),
+        other => Ok(Some(other.to_string())),
+    }
+}
+
+/// Deserialize a number that aoe2.net sends as a number or as a string, e.g.
+/// `"-13"`, values that are not a number become `None`
+fn number_or_string<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where D: Deserializer<'de>,
+          T: DeserializeOwned + std::str::FromStr,
+{
+    match JsonValue::deserialize(deserializer)? {
+        JsonValue::String(string) => Ok(string.trim().parse().ok()),
+        other => Ok(serde_json::from_value(other).ok()),
+    }
+}
+
+#[test]
+fn match_tolerates_nulls_unknown_fields_and_string_numbers() {
+    let last_match: LastMatch = serde_json::from_value(serde_json::json!({
+        "profile_id": 196240,
+        "steam_id": null,
+        "unknown_field": {"nested": true},
+        "last_match": {
+            "match_id": 75984064,
+            "players": [
+                {"profile_id": 196240, "rating_change": "-13", "won": null},
+                {"profile_id": null, "rating_change": null}
+            ],
+            "finished": null
+        }
+    }))
+    .unwrap();
+
+    let last_match = last_match.last_match.unwrap();
+    assert_eq!(last_match.match_id.as_deref(), Some("75984064"));
+    assert_eq!(last_match.finished, None);
+    assert_eq!(last_match.players[0].rating_change, Some(-13));
+    assert_eq!(last_match.players[1], MatchPlayer::default());
+
+    let leaderboard: Leaderboard =
+        serde_json::from_value(serde_json::json!({"leaderboard": null}))
+            .unwrap();
+    assert!(leaderboard.leaderboard.is_empty());
+}
@@ -0,0 +1,86 @@
+use api_client::{
+    error::*,
+    request::{
+        Request,
+        RequestGet,
+    },
+    response::Response,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::types::api::{
+    CivilizationStats,
+    Page,
+};
+
+/// Query Parameters for [Get Civilization Stats](super::civilizations)
+///
+/// [`civilizations`](https://api.aoestats.io/de/civilizations/)
+#[derive(PartialEq,
+           typed_builder::TypedBuilder,
+           Deserialize,
+           Serialize,
+           Clone,
+           Debug)]
+#[non_exhaustive]
+pub struct GetCivilizationStatsRequest<'a> {
+    /// Number of the civilization, see `Definitions::civs`
+    #[builder(default = None, setter(into))]
+    pub civ_num: Option<i64>,
+    /// Number of the ranked ladder, see `Definitions::ladder`
+    #[builder(default = None, setter(into))]
+    #[serde(rename = "filter__ladder_val")]
+    pub ladder: Option<i64>,
+    /// Rating range, see `Definitions::elo`
+    #[builder(default = None, setter(into))]
+    #[serde(rename = "filter__elo_val")]
+    pub elo: Option<&'a str>,
+    /// Patch, see `Definitions::patch`
+    #[builder(default = None, setter(into))]
+    #[serde(rename = "filter__patch_val")]
+    pub patch: Option<&'a str>,
+    #[builder(default = None, setter(into))]
+    pub limit: Option<i32>,
+    #[builder(default = None, setter(into))]
+    pub offset: Option<i32>,
+}
+
+impl<'a> GetCivilizationStatsRequest<'a> {
+    pub fn new(civ_num: Option<i64>,
+               ladder: Option<i64>,
+               elo: Option<&'a str>,
+               patch: Option<&'a str>)
+               -> GetCivilizationStatsRequest<'a> {
+        GetCivilizationStatsRequest::builder().civ_num(civ_num)
+                                              .ladder(ladder)
+                                              .elo(elo)
+                                              .patch(patch)
+                                              .build()
+    }
+}
+
+impl<'a> Request for GetCivilizationStatsRequest<'a> {
+    type Response = Page<CivilizationStats>;
+
+    const PATH: &'static str = "de/civilizations/";
+    const ROOT: &'static str = crate::AOESTATS_URL;
+}
+
+impl<'a> RequestGet for GetCivilizationStatsRequest<'a> {
+    fn parse_response(
+        request: Option<Self>,
+        uri: &http::Uri,
+        response: http::Response<Vec<u8>>)
+        -> Result<Response<Self, Page<CivilizationStats>>, ApiRequestGetError>
+        where Self: Sized,
+    {
+        let data = super::parse_data(uri, &response)?;
+
+        Ok(Response { data,
+                      pagination: None,
+                      request })
+    }
+}
@@ -0,0 +1,57 @@
+use api_client::{
+    error::*,
+    request::{
+        Request,
+        RequestGet,
+    },
+    response::Response,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::types::api::Definitions;
+
+/// Query Parameters for [Get Definitions](super::defs)
+///
+/// [`defs`](https://api.aoestats.io/de/defs)
+#[derive(PartialEq,
+           typed_builder::TypedBuilder,
+           Deserialize,
+           Serialize,
+           Clone,
+           Debug,
+           Default)]
+#[non_exhaustive]
+pub struct GetDefinitionsRequest {}
+
+impl GetDefinitionsRequest {
+    #[must_use]
+    pub fn new() -> GetDefinitionsRequest {
+        GetDefinitionsRequest::builder().build()
+    }
+}
+
+impl Request for GetDefinitionsRequest {
+    type Response = Definitions;
+
+    const PATH: &'static str = "de/defs";
+    const ROOT: &'static str = crate::AOESTATS_URL;
+}
+
+impl RequestGet for GetDefinitionsRequest {
+    fn parse_response(
+        request: Option<Self>,
+        uri: &http::Uri,
+        response: http::Response<Vec<u8>>)
+        -> Result<Response<Self, Definitions>, ApiRequestGetError>
+        where Self: Sized,
+    {
+        let data = super::parse_data(uri, &response)?;
+
+        Ok(Response { data,
+                      pagination: None,
+                      request })
+    }
+}
@@ -0,0 +1,86 @@
+use api_client::{
+    error::*,
+    request::{
+        Request,
+        RequestGet,
+    },
+    response::Response,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::types::api::{
+    MapStats,
+    Page,
+};
+
+/// Query Parameters for [Get Map Stats](super::maps)
+///
+/// [`maps`](https://api.aoestats.io/de/maps/)
+#[derive(PartialEq,
+           typed_builder::TypedBuilder,
+           Deserialize,
+           Serialize,
+           Clone,
+           Debug)]
+#[non_exhaustive]
+pub struct GetMapStatsRequest<'a> {
+    /// Number of the map, see `Definitions::maps`
+    #[builder(default = None, setter(into))]
+    pub map_num: Option<i64>,
+    /// Number of the ranked ladder, see `Definitions::ladder`
+    #[builder(default = None, setter(into))]
+    #[serde(rename = "filter__ladder_val")]
+    pub ladder: Option<i64>,
+    /// Rating range, see `Definitions::elo`
+    #[builder(default = None, setter(into))]
+    #[serde(rename = "filter__elo_val")]
+    pub elo: Option<&'a str>,
+    /// Patch, see `Definitions::patch`
+    #[builder(default = None, setter(into))]
+    #[serde(rename = "filter__patch_val")]
+    pub patch: Option<&'a str>,
+    #[builder(default = None, setter(into))]
+    pub limit: Option<i32>,
+    #[builder(default = None, setter(into))]
+    pub offset: Option<i32>,
+}
+
+impl<'a> GetMapStatsRequest<'a> {
+    pub fn new(map_num: Option<i64>,
+               ladder: Option<i64>,
+               elo: Option<&'a str>,
+               patch: Option<&'a str>)
+               -> GetMapStatsRequest<'a> {
+        GetMapStatsRequest::builder().map_num(map_num)
+                                     .ladder(ladder)
+                                     .elo(elo)
+                                     .patch(patch)
+                                     .build()
+    }
+}
+
+impl<'a> Request for GetMapStatsRequest<'a> {
+    type Response = Page<MapStats>;
+
+    const PATH: &'static str = "de/maps/";
+    const ROOT: &'static str = crate::AOESTATS_URL;
+}
+
+impl<'a> RequestGet for GetMapStatsRequest<'a> {
+    fn parse_response(
+        request: Option<Self>,
+        uri: &http::Uri,
+        response: http::Response<Vec<u8>>)
+        -> Result<Response<Self, Page<MapStats>>, ApiRequestGetError>
+        where Self: Sized,
+    {
+        let data = super::parse_data(uri, &response)?;
+
+        Ok(Response { data,
+                      pagination: None,
+                      request })
+    }
+}
@@ -0,0 +1,116 @@
+//! Aoestats.io API endpoints
+//! Use together with the `api-client`
+//!
+//! Every request deserializes into the typed datastructures of
+//! [`crate::types::api`], wrap it into [`Raw`] to get the plain JSON instead.
+
+pub mod civilizations;
+pub mod defs;
+pub mod maps;
+
+use api_client::{
+    error::*,
+    request::{
+        Request,
+        RequestGet,
+    },
+    response::Response,
+};
+use serde::{
+    de::DeserializeOwned,
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value as JsonValue;
+
+/// Wrapper around a request of this crate, that returns the response of
+/// aoestats.io as plain JSON instead of the typed datastructure
+#[derive(PartialEq, Serialize, Clone, Debug)]
+#[serde(transparent)]
+pub struct Raw<R>(pub R);
+
+impl<R: Request> Request for Raw<R> {
+    type Response = Option<JsonValue>;
+
+    const PATH: &'static str = R::PATH;
+    const ROOT: &'static str = R::ROOT;
+}
+
+impl<R: Request> RequestGet for Raw<R> {
+    fn parse_response(
+        request: Option<Self>,
+        uri: &http::Uri,
+        response: http::Response<Vec<u8>>)
+        -> Result<Response<Self, Option<JsonValue>>, ApiRequestGetError>
+        where Self: Sized,
+    {
+        let data: JsonValue = parse_data(uri, &response)?;
+
+        Ok(Response { data: data.into(),
+                      pagination: None,
+                      request })
+    }
+}
+
+/// Error message aoestats.io responds with instead of the data
+#[derive(Deserialize)]
+struct ErrorDetail {
+    detail: String,
+}
+
+/// Parse the body of an aoestats.io `response` into `T`
+///
+/// # Errors
+/// Returns an error if the status is not successful, the body is not valid
+/// UTF-8 or can't be deserialized into `T`
+pub(crate) fn parse_data<T>(uri: &http::Uri,
+                            response: &http::Response<Vec<u8>>)
+                            -> Result<T, ApiRequestGetError>
+    where T: DeserializeOwned,
+{
+    let text = std::str::from_utf8(response.body()).map_err(|e| {
+                   ApiRequestGetError::Utf8Error(response.body().clone(),
+                                                 e,
+                                                 uri.clone())
+               })?;
+
+    if !response.status().is_success() {
+        return Err(ApiRequestGetError::Error {
+            error: response.status()
+                           .canonical_reason()
+                           .unwrap_or_default()
+                           .to_string(),
+            status: response.status(),
+            message: serde_json::from_str::<ErrorDetail>(text)
+                .map_or_else(|_| text.to_string(), |error| error.detail),
+            uri: uri.clone(),
+        });
+    }
+
+    serde_json::from_str(text).map_err(|e| {
+                                  ApiRequestGetError::DeserializeError(
+                                      text.to_string(),
+                                      e,
+                                      uri.clone(),
+                                  )
+                              })
+}
+
+#[test]
+fn error_details_are_parsed() {
+    let uri = http::Uri::from_static("https://api.aoestats.io/de/defs");
+    let response = http::Response::builder()
+        .status(http::StatusCode::TOO_MANY_REQUESTS)
+        .body(br#"{"detail": "Request was throttled."}"#.to_vec())
+        .unwrap();
+
+    match parse_data::<JsonValue>(&uri, &response) {
+        Err(ApiRequestGetError::Error { status,
+                                        message,
+                                        .. }) => {
+            assert_eq!(status, http::StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(message, "Request was throttled.");
+        },
+        other => panic!("Expected an error, got {:?}", other),
+    }
+}
@@ -0,0 +1,9 @@
+//! Rust API crate for aoestats.io
+//!
+//! Check the [api-documentation](https://api.aoestats.io/de/) for more
+//! information.
+
+pub mod endpoints;
+pub mod types;
+
+pub const AOESTATS_URL: &str = "https://api.aoestats.io/";
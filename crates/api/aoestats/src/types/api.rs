@@ -0,0 +1,187 @@
+//! Datastructures of the aoestats API
+//!
+//! The typed responses tolerate unknown and missing fields, as the stats of
+//! aoestats.io are only partially documented.
+
+use std::collections::HashMap;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value as JsonValue;
+
+/// Response of the `defs` endpoint, which defines the magic numbers and
+/// strings used throughout the API
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Definitions {
+    /// Numbers of the civilizations keyed by their name
+    pub civs: HashMap<String, i64>,
+    /// Numbers of the maps keyed by their name
+    pub maps: HashMap<String, i64>,
+    /// Breakdown of the match ratings, inclusive on the lower bound and
+    /// exclusive on the upper bound
+    pub elo: HashMap<String, String>,
+    /// Numbers of the ranked ladders keyed by their name
+    pub ladder: HashMap<String, i64>,
+    /// Patches the stats were calculated on
+    pub patch: HashMap<String, String>,
+    /// Buckets used to calculate the timeseries graphs
+    pub timeseries: HashMap<String, String>,
+    /// Descriptions of the series stored in the `series` of the stats
+    pub series: HashMap<String, String>,
+}
+
+impl Definitions {
+    /// Number of the civilization with the `name`, ignoring case
+    #[must_use]
+    pub fn civ_num(&self,
+                   name: &str)
+                   -> Option<i64> {
+        num_by_name(&self.civs, name)
+    }
+
+    /// Number of the map with the `name`, ignoring case
+    #[must_use]
+    pub fn map_num(&self,
+                   name: &str)
+                   -> Option<i64> {
+        num_by_name(&self.maps, name)
+    }
+
+    /// Number of the ranked ladder with the `name`, ignoring case
+    #[must_use]
+    pub fn ladder_num(&self,
+                      name: &str)
+                      -> Option<i64> {
+        num_by_name(&self.ladder, name)
+    }
+
+    /// Name of the series with the `description`, ignoring case
+    #[must_use]
+    pub fn series_name(&self,
+                       description: &str)
+                       -> Option<&str> {
+        self.series
+            .iter()
+            .find(|(_, desc)| desc.eq_ignore_ascii_case(description))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Number in the `definitions` keyed by the `name`, ignoring case
+fn num_by_name(definitions: &HashMap<String, i64>,
+               name: &str)
+               -> Option<i64> {
+    definitions.iter()
+               .find(|(key, _)| key.eq_ignore_ascii_case(name))
+               .map(|(_, num)| *num)
+}
+
+/// A page of results of the list endpoints
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Page<T> {
+    /// Amount of results of all pages
+    pub count: i64,
+    /// URL of the next page
+    pub next: Option<String>,
+    /// URL of the previous page
+    pub previous: Option<String>,
+    /// Results on this page
+    pub results: Vec<T>,
+}
+
+/// Combination of ladder, patch and elo the stats were calculated for, `None`
+/// means the stats are not restricted to one of them
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatsFilter {
+    /// Rating range, e.g. `1250-1650`
+    pub elo_val: Option<String>,
+    /// Number of the ranked ladder
+    pub ladder_val: Option<i64>,
+    /// Patch, e.g. `37906`
+    pub patch_val: Option<String>,
+    pub combined: bool,
+}
+
+/// Stats of a civilization for a [`StatsFilter`], which include its win rate
+/// against each other civilization and on each map in the `series`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CivilizationStats {
+    /// Unique token to retrieve these stats with
+    pub token: Option<String>,
+    pub filter: StatsFilter,
+    pub civ_num: i64,
+    pub num_played: i64,
+    pub num_won: i64,
+    pub win_rate: f64,
+    pub play_rate: f64,
+    pub avg_game_length: Option<String>,
+    /// JSON blobs keyed by the name of the series, see
+    /// [`Definitions::series`]
+    pub series: HashMap<String, JsonValue>,
+}
+
+/// Stats of a map for a [`StatsFilter`], which include the win rate of each
+/// civilization on it in the `series`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapStats {
+    /// Unique token to retrieve these stats with
+    pub token: Option<String>,
+    pub filter: StatsFilter,
+    pub map_num: i64,
+    pub num_played: i64,
+    pub num_won: i64,
+    pub win_rate: f64,
+    pub play_rate: f64,
+    pub avg_game_length: Option<String>,
+    /// JSON blobs keyed by the name of the series, see
+    /// [`Definitions::series`]
+    pub series: HashMap<String, JsonValue>,
+}
+
+#[test]
+fn ensure_civilization_stats_from_sample() {
+    let sample = r#"
+{
+  "count": 1,
+  "next": null,
+  "previous": null,
+  "results": [
+    {
+      "token": "a1b2",
+      "filter": {"elo_val": null, "ladder_val": 3, "patch_val": "37906",
+                 "combined": false},
+      "civ_num": 18,
+      "num_played": 2000,
+      "num_won": 1040,
+      "win_rate": 0.52,
+      "play_rate": 0.04,
+      "avg_game_length": "00:32:10",
+      "series": {"win_rate_by_map": {"9": 0.55}}
+    }
+  ]
+}"#;
+
+    let page: Page<CivilizationStats> = serde_json::from_str(sample).unwrap();
+    assert_eq!(page.results[0].filter.ladder_val, Some(3));
+    assert_eq!(page.results[0].series["win_rate_by_map"]["9"], 0.55);
+
+    let definitions: Definitions = serde_json::from_str(
+        r#"{"civs": {"Aztecs": 0}, "maps": {"Arabia": 9},
+            "ladder": {"1v1 Random Map": 3},
+            "series": {"win_rate_by_map": "Win rate on each map"},
+            "unknown": 1}"#,
+    )
+    .unwrap();
+    assert_eq!(definitions.civ_num("aztecs"), Some(0));
+    assert_eq!(definitions.map_num("arabia"), Some(9));
+    assert_eq!(definitions.ladder_num("1v1 random map"), Some(3));
+    assert_eq!(definitions.series_name("win rate on each map"),
+               Some("win_rate_by_map"));
+}
@@ -0,0 +1,11 @@
+//! Error types (incomplete)
+
+use displaydoc::Display;
+use thiserror::Error;
+
+/// Error thrown from aoestats.io API endpoints
+#[derive(Debug, Error, Display)]
+pub enum ApiError {
+    /// Generic Error thrown by the API
+    Generic(String),
+}
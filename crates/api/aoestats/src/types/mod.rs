@@ -0,0 +1,4 @@
+//! Types and Errors used in this crate
+
+pub mod api;
+pub mod error;
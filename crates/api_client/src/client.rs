@@ -52,7 +52,8 @@ impl<'a> Client<'a> for ReqwestClient {
                 // This should not fail, we just created the response.
                 .expect("expected to get headers mut when building response");
             std::mem::swap(headers, response.headers_mut());
-            let result = result.version(response.version());
+            let result = result.version(response.version())
+                               .status(response.status());
             Ok(result
                 .body(response.bytes().await?.as_ref().to_vec())
                 .expect("mismatch reqwest -> http conversion should not fail"))
@@ -29,8 +29,15 @@ pub trait Request: serde::Serialize {
     }
     /// Returns full URI for the request, including query parameters.
     fn get_uri(&self) -> Result<http::Uri, InvalidUri> {
-        http::Uri::from_str(&format!("{}{}?{}",
-                                     <Self as Request>::ROOT,
+        self.get_uri_with_root(<Self as Request>::ROOT)
+    }
+    /// Returns full URI for the request on a different API root, including
+    /// query parameters.
+    fn get_uri_with_root(&self,
+                         root: &str)
+                         -> Result<http::Uri, InvalidUri> {
+        http::Uri::from_str(&format!("{}/{}?{}",
+                                     root.trim_end_matches('/'),
                                      <Self as Request>::PATH,
                                      self.query()?)).map_err(Into::into)
     }
@@ -51,7 +58,16 @@ pub trait RequestGet: Request {
     fn create_request(&self)
                       -> Result<http::Request<Vec<u8>>, CreateRequestError>
     {
-        let uri = self.get_uri()?;
+        self.create_request_with_root(<Self as Request>::ROOT)
+    }
+
+    /// Create a [`http::Request`] from this [`Request`] on a different API
+    /// root in your client
+    fn create_request_with_root(
+        &self,
+        root: &str)
+        -> Result<http::Request<Vec<u8>>, CreateRequestError> {
+        let uri = self.get_uri_with_root(root)?;
 
         http::Request::builder().method(http::Method::GET)
                                 .uri(uri)
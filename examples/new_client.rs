@@ -29,5 +29,5 @@ async fn main() {
 
     let response = client.req_get(req).await.unwrap();
 
-    println!("{:?}", response.data.unwrap());
+    println!("{:?}", response.data);
 }
@@ -24,6 +24,7 @@ use tokio::sync::Mutex;
 use transparencies_backend_rs::{
     domain::{
         api_handler::client::A2NClient,
+        data_processing::pro_matches::spawn_pro_match_discovery,
         types::InMemoryDb,
     },
     persistence::in_memory_db::data_preloading::get_static_data_inside_thread,
@@ -84,10 +85,14 @@ async fn main() -> Result<(), Report> {
 
     get_static_data_inside_thread(in_memory_db_clone,
                                   github_root,
-                                  aoe2_net_root).await;
+                                  aoe2_net_root.clone()).await;
 
     let a2n_client = A2NClient::with_client(client);
 
+    spawn_pro_match_discovery(a2n_client.clone(),
+                              aoe2_net_root,
+                              in_memory_db.clone());
+
     let api = filters::transparencies(a2n_client, in_memory_db.clone());
 
     let routes = api.with(warp::log("transparencies"));
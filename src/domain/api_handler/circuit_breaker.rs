@@ -0,0 +1,201 @@
+//! Circuit breaker for requests towards external APIs
+//!
+//! After a configurable amount of consecutive failures towards an API root
+//! the circuit opens and requests towards that root fail immediately instead
+//! of waiting for a timeout. After the open duration has passed, a single
+//! probe request is let through and its result decides whether the circuit
+//! closes again or stays open.
+
+use std::{
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use dashmap::DashMap;
+use tracing::{
+    info,
+    warn,
+};
+
+use crate::{
+    domain::api_handler::normalize_root,
+    CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+    CIRCUIT_BREAKER_OPEN_DURATION,
+};
+
+/// State of the circuit for a single API root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CircuitState {
+    /// Requests are let through, contains the amount of consecutive failures
+    Closed(u32),
+    /// Requests fail immediately until the given point in time
+    Open(Instant),
+    /// A single probe request has been let through to check if the upstream
+    /// has recovered, other requests fail immediately. If the probe doesn't
+    /// report back until the given point in time, another one is let through
+    HalfOpen(Instant),
+}
+
+impl Default for CircuitState {
+    fn default() -> Self {
+        CircuitState::Closed(0)
+    }
+}
+
+/// Circuit breaker with a circuit per API root
+///
+/// Clones share the same circuits, so all clones of a client see the same
+/// state of the upstream.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    /// State of the circuit for each API root
+    circuits: Arc<DashMap<String, CircuitState>>,
+    /// Amount of consecutive failures after which the circuit opens
+    failure_threshold: u32,
+    /// Duration the circuit stays open before requests are let through again
+    open_duration: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(*CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+                  *CIRCUIT_BREAKER_OPEN_DURATION)
+    }
+}
+
+impl CircuitBreaker {
+    /// Create a new [`CircuitBreaker`]
+    #[must_use]
+    pub fn new(failure_threshold: u32,
+               open_duration: Duration)
+               -> Self {
+        Self { circuits: Arc::new(DashMap::new()),
+               failure_threshold,
+               open_duration }
+    }
+
+    /// Returns `true` if requests towards `root` are allowed
+    pub fn allow_request(&self,
+                         root: &str)
+                         -> bool {
+        let mut state =
+            self.circuits.entry(normalize_root(root).to_string()).or_default();
+
+        let now = Instant::now();
+
+        match *state {
+            CircuitState::Open(until) | CircuitState::HalfOpen(until)
+                if now < until =>
+            {
+                false
+            }
+            CircuitState::Open(_) | CircuitState::HalfOpen(_) => {
+                info!("Circuit for {} is half-open, checking if upstream has \
+                       recovered",
+                      root);
+                *state = CircuitState::HalfOpen(now + self.open_duration);
+                true
+            }
+            CircuitState::Closed(_) => true,
+        }
+    }
+
+    /// Returns `true` if the circuit for `root` currently rejects requests,
+    /// which is while it is open and while a probe request is checking if the
+    /// upstream has recovered
+    #[must_use]
+    pub fn is_open(&self,
+                   root: &str)
+                   -> bool {
+        let now = Instant::now();
+
+        self.circuits
+            .get(normalize_root(root))
+            .is_some_and(|state| {
+                matches!(*state,
+                         CircuitState::Open(until) |
+                         CircuitState::HalfOpen(until) if now < until)
+            })
+    }
+
+    /// Record a successful request towards `root`, closes the circuit
+    pub fn record_success(&self,
+                          root: &str) {
+        self.circuits
+            .insert(normalize_root(root).to_string(), CircuitState::Closed(0));
+    }
+
+    /// Record a failed request towards `root`, opens the circuit when the
+    /// failure threshold is reached
+    pub fn record_failure(&self,
+                          root: &str) {
+        let mut state =
+            self.circuits.entry(normalize_root(root).to_string()).or_default();
+
+        let failures = match *state {
+            CircuitState::Closed(failures) => failures + 1,
+            // A failure while checking for recovery opens the circuit again
+            CircuitState::HalfOpen(_) => self.failure_threshold,
+            CircuitState::Open(_) => return,
+        };
+
+        if failures >= self.failure_threshold {
+            warn!("Circuit for {} opened after {} consecutive failures",
+                  root, failures);
+            *state = CircuitState::Open(Instant::now() + self.open_duration);
+        }
+        else {
+            *state = CircuitState::Closed(failures);
+        }
+    }
+}
+
+#[test]
+fn circuit_opens_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+
+    breaker.record_failure("https://aoe2.net/api/");
+    assert!(breaker.allow_request("https://aoe2.net/api"));
+
+    breaker.record_failure("https://aoe2.net/api");
+    assert!(breaker.is_open("https://aoe2.net/api"));
+    assert!(!breaker.allow_request("https://aoe2.net/api"));
+}
+
+#[test]
+fn circuit_closes_after_recovery() {
+    let breaker = CircuitBreaker::new(1, Duration::from_secs(0));
+
+    breaker.record_failure("https://aoe2.net/api");
+    // Open duration has already passed, so it's half-open now
+    assert!(breaker.allow_request("https://aoe2.net/api"));
+
+    breaker.record_success("https://aoe2.net/api");
+    assert!(!breaker.is_open("https://aoe2.net/api"));
+}
+
+#[test]
+fn half_open_circuit_lets_a_single_probe_through() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+
+    breaker.record_failure("https://aoe2.net/api");
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(!breaker.is_open("https://aoe2.net/api"));
+
+    assert!(breaker.allow_request("https://aoe2.net/api"));
+    // Other requests are rejected while the probe is in flight
+    assert!(breaker.is_open("https://aoe2.net/api"));
+    assert!(!breaker.allow_request("https://aoe2.net/api"));
+
+    // The probe never reported back, so another one is let through
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.allow_request("https://aoe2.net/api"));
+    assert!(!breaker.allow_request("https://aoe2.net/api"));
+
+    breaker.record_success("https://aoe2.net/api");
+    assert!(breaker.allow_request("https://aoe2.net/api"));
+    assert!(breaker.allow_request("https://aoe2.net/api"));
+}
@@ -12,6 +12,10 @@ use api_client::{
 };
 use derive_getters::Getters;
 use http::StatusCode;
+use tracing::{
+    trace,
+    warn,
+};
 use url::Url;
 
 use crate::{
@@ -27,9 +31,28 @@ use crate::{
             GithubFileRequest,
         },
     },
+    domain::api_handler::{
+        circuit_breaker::CircuitBreaker,
+        rate_limiter::{
+            RateLimit,
+            RateLimiter,
+        },
+        retry::{
+            is_retryable_error,
+            is_retryable_status,
+            retry_after,
+            RetryPolicy,
+        },
+    },
+    persistence::in_memory_db::ttl_cache::{
+        CacheStats,
+        TtlCache,
+    },
+    AOE2NET_CACHE_TTLS,
     APP_USER_AGENT,
     CLIENT_CONNECTION_TIMEOUT,
     CLIENT_REQUEST_TIMEOUT,
+    RESPONSE_CACHE_CAPACITY,
 };
 
 /// Datastructure storing our different [`ApiClient`]s
@@ -41,12 +64,26 @@ pub struct ApiClient<'a, C>
     pub aoe2net: A2NClient<'a, C>,
 }
 
+/// Cache for raw response bodies of the aoe2net API, keyed by the full request
+/// `URI` (API root, endpoint and serialized query)
+pub type ResponseCache = TtlCache<String, Vec<u8>>;
+
 #[derive(Clone, Debug)]
 pub struct A2NClient<'a, C>
     where C: Client<'a>,
 {
     client: C,
-    _pd: std::marker::PhantomData<&'a ()>, // TODO: Implement rate limiter...
+    /// API root to use instead of the one defined by the requests
+    root: Option<Url>,
+    /// Response cache shared between all clones of this client
+    cache: ResponseCache,
+    /// Rate limiter shared between all clones of this client
+    rate_limiter: RateLimiter,
+    /// Policy for retrying failed requests
+    retry_policy: RetryPolicy,
+    /// Circuit breaker shared between all clones of this client
+    circuit_breaker: CircuitBreaker,
+    _pd: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a, C> Default for A2NClient<'a, C> where C: Client<'a> + Default,
@@ -77,10 +114,77 @@ impl<'a, C: Client<'a>> A2NClient<'a, C> {
     /// Create a new client with an existing client
     #[must_use]
     pub fn with_client(client: C) -> A2NClient<'a, C> {
+        let cache = ResponseCache::with_capacity(*RESPONSE_CACHE_CAPACITY);
+
         A2NClient { client,
+                    root: None,
+                    cache,
+                    rate_limiter: RateLimiter::new(),
+                    retry_policy: RetryPolicy::default(),
+                    circuit_breaker: CircuitBreaker::default(),
                     _pd: std::marker::PhantomData::default() }
     }
 
+    /// Use a different API root for all requests, e.g. for mocking
+    #[must_use]
+    pub fn with_root(mut self,
+                     root: Url)
+                     -> A2NClient<'a, C> {
+        self.root = Some(root);
+        self
+    }
+
+    /// Configure the [`RateLimit`] for requests towards an API root
+    #[must_use]
+    pub fn with_rate_limit(self,
+                           root: &Url,
+                           limit: RateLimit)
+                           -> A2NClient<'a, C> {
+        self.rate_limiter.configure(root.as_str(), limit);
+        self
+    }
+
+    /// Use a different [`RetryPolicy`] for failed requests
+    #[must_use]
+    pub fn with_retry_policy(mut self,
+                             retry_policy: RetryPolicy)
+                             -> A2NClient<'a, C> {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Use a different [`CircuitBreaker`] for the upstream
+    #[must_use]
+    pub fn with_circuit_breaker(mut self,
+                                circuit_breaker: CircuitBreaker)
+                                -> A2NClient<'a, C> {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    /// Returns `true` if the circuit breaker for the API `root` is open or
+    /// half-open, which means requests towards it fail immediately
+    #[must_use]
+    pub fn is_circuit_open(&self,
+                           root: &Url)
+                           -> bool {
+        self.circuit_breaker.is_open(root.as_str())
+    }
+
+    /// Returns the API root requests of type `R` are sent to, the root set
+    /// with [`A2NClient::with_root`] or else the one defined by `R`
+    #[must_use]
+    pub fn root_for<R>(&self) -> &str
+        where R: Request, {
+        self.root.as_ref().map_or(R::ROOT, Url::as_str)
+    }
+
+    /// Returns the hit/miss counters of the response cache
+    #[must_use]
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
     /// Create a new [`HelixClient`] with a default
     /// [`HttpClient`][`crate::HttpClient`]
     #[must_use]
@@ -99,6 +203,20 @@ impl<'a, C: Client<'a>> A2NClient<'a, C> {
 
     /// Request on a valid [`RequestGet`] endpoint
     ///
+    /// Requests towards the API root are rate limited, if the limit is reached
+    /// the request is queued until it is allowed.
+    ///
+    /// Responses of endpoints that have a `time-to-live` in
+    /// [`static@crate::AOE2NET_CACHE_TTLS`] are answered from the response
+    /// cache as long as they are not expired.
+    ///
+    /// Connection errors, timeouts, `429` and `5xx` responses are retried
+    /// according to the [`RetryPolicy`] of this client.
+    ///
+    /// If the retries are exhausted, the failure gets recorded by the
+    /// [`CircuitBreaker`]. While the circuit for the API root is open, requests
+    /// fail immediately.
+    ///
     ///  # Errors
     // TODO
     pub async fn req_get<R, D>(
@@ -108,13 +226,100 @@ impl<'a, C: Client<'a>> A2NClient<'a, C> {
         where R: Request<Response = D> + Request + RequestGet,
               D: serde::de::DeserializeOwned + PartialEq,
     {
-        let req = request.create_request()?;
+        let root = self.root_for::<R>();
+        let req = request.create_request_with_root(root)?;
         let uri = req.uri().clone();
-        let response = self.client
-                           .req(req)
-                           .await
-                           .map_err(ClientRequestError::RequestError)?;
-        <R>::parse_response(Some(request), &uri, response).map_err(Into::into)
+
+        let key = uri.to_string();
+        let ttl = AOE2NET_CACHE_TTLS.get(R::PATH).map(|ttl| *ttl);
+
+        if ttl.is_some() {
+            if let Some(body) = self.cache.get(&key) {
+                trace!("Response cache hit for {}", key);
+                return <R>::parse_response(Some(request),
+                                           &uri,
+                                           http::Response::new(body))
+                    .map_err(Into::into);
+            }
+        }
+
+        if !self.circuit_breaker.allow_request(root) {
+            return Err(ClientRequestError::Custom(
+                format!("Circuit breaker for {root} is open").into(),
+            ));
+        }
+
+        let mut req = Some(req);
+        let mut attempt = 0;
+
+        let response = loop {
+            // `http::Request` is not `Clone`, so we need to recreate it for
+            // each retry
+            let req = match req.take() {
+                Some(req) => req,
+                None => request.create_request_with_root(root)?,
+            };
+
+            self.rate_limiter.acquire(root).await;
+
+            let delay = match self.client.req(req).await {
+                Ok(response) if is_retryable_status(response.status()) &&
+                                self.retry_policy.should_retry(attempt) =>
+                {
+                    let retry_after = retry_after(response.headers());
+
+                    // The upstream wants us to wait longer than we are
+                    // willing to, so we give up right away
+                    let Some(delay) =
+                        self.retry_policy.delay(attempt, retry_after)
+                    else {
+                        self.circuit_breaker.record_failure(root);
+                        break response;
+                    };
+
+                    delay
+                }
+                Ok(response) => {
+                    if is_retryable_status(response.status()) {
+                        self.circuit_breaker.record_failure(root);
+                    }
+                    else {
+                        self.circuit_breaker.record_success(root);
+                    }
+                    break response;
+                }
+                Err(err) if is_retryable_error(&err) &&
+                            self.retry_policy.should_retry(attempt) =>
+                {
+                    self.retry_policy.backoff(attempt)
+                }
+                Err(err) => {
+                    if is_retryable_error(&err) {
+                        self.circuit_breaker.record_failure(root);
+                    }
+                    return Err(ClientRequestError::RequestError(err));
+                }
+            };
+
+            attempt += 1;
+            warn!("Request to {} failed, retry {} of {} in {:?}",
+                  uri,
+                  attempt,
+                  self.retry_policy.max_retries,
+                  delay);
+            tokio::time::sleep(delay).await;
+        };
+
+        let body = (ttl.is_some() && response.status().is_success())
+            .then(|| response.body().clone());
+        let parsed = <R>::parse_response(Some(request), &uri, response)?;
+
+        // Only successfully parsed responses get cached
+        if let (Some(ttl), Some(body)) = (ttl, body) {
+            self.cache.insert(key, body, ttl);
+        }
+
+        Ok(parsed)
     }
 
     // /// Request on a valid [`RequestPost`] endpoint
@@ -312,12 +517,48 @@ impl ApiRequest {
     /// see [`reqwest::Error`]
     pub async fn execute<R>(&self) -> Result<R, ApiRequestError>
         where R: for<'de> serde::Deserialize<'de>, {
-        let response =
-            self.client()
-                .get(&format!("{}/{}", &self.root().as_str(), &self.endpoint()))
-                .query(&self.query())
-                .send()
-                .await?;
+        let mut attempt = 0;
+
+        let response = loop {
+            let delay = match self.client()
+                                  .get(format!("{}/{}",
+                                               self.root().as_str(),
+                                               self.endpoint()))
+                                  .query(&self.query())
+                                  .send()
+                                  .await
+            {
+                Ok(response) if is_retryable_status(response.status()) &&
+                                self.retry_policy().should_retry(attempt) =>
+                {
+                    let retry_after = retry_after(response.headers());
+
+                    let Some(delay) =
+                        self.retry_policy().delay(attempt, retry_after)
+                    else {
+                        break response;
+                    };
+
+                    delay
+                }
+                Ok(response) => break response,
+                Err(err) if is_retryable_error(&err) &&
+                            self.retry_policy().should_retry(attempt) =>
+                {
+                    self.retry_policy().backoff(attempt)
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            attempt += 1;
+            warn!("Request to {}/{} failed, retry {} of {} in {:?}",
+                  self.root(),
+                  self.endpoint(),
+                  attempt,
+                  self.retry_policy().max_retries,
+                  delay);
+            tokio::time::sleep(delay).await;
+        };
 
         match response.status() {
             StatusCode::OK => Ok(response.json().await?),
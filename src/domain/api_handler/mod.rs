@@ -1,3 +1,12 @@
 //! Everything around the API client implementations
 
+pub mod circuit_breaker;
 pub mod client;
+pub mod rate_limiter;
+pub mod retry;
+
+/// API roots with and without a trailing slash are treated as the same root,
+/// e.g. by the [`rate_limiter`] and the [`circuit_breaker`]
+pub(crate) fn normalize_root(root: &str) -> &str {
+    root.trim_end_matches('/')
+}
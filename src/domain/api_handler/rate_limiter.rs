@@ -0,0 +1,200 @@
+//! Client-side rate limiting of our requests towards external APIs
+//!
+//! Each API root gets its own token bucket. Requests that find an empty
+//! bucket are queued until a token has been refilled instead of failing.
+
+use std::{
+    sync::Arc,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use dashmap::DashMap;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::{
+    domain::api_handler::normalize_root,
+    RATE_LIMIT_BURST,
+    RATE_LIMIT_REFILL_INTERVAL,
+};
+
+/// Configuration of a token bucket
+///
+/// Use [`RateLimit::new`] to create one, which rejects limits that would
+/// never let a request through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum amount of tokens, which is the amount of requests that can be
+    /// made in a burst
+    burst: u32,
+    /// Interval in which a single token gets refilled
+    refill_interval: Duration,
+}
+
+/// Error for a [`RateLimit`] that can't be used for a token bucket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(thiserror::Error, displaydoc::Display)]
+pub enum InvalidRateLimit {
+    /// The burst of a rate limit needs to allow at least one request
+    ZeroBurst,
+    /// The refill interval of a rate limit needs to be longer than zero
+    ZeroRefillInterval,
+}
+
+impl RateLimit {
+    /// Create a new [`RateLimit`]
+    ///
+    /// # Errors
+    /// Returns an error if `burst` or `refill_interval` are zero, as the token
+    /// bucket would either never allow a request or never throttle
+    pub fn new(burst: u32,
+               refill_interval: Duration)
+               -> Result<Self, InvalidRateLimit> {
+        if burst == 0 {
+            return Err(InvalidRateLimit::ZeroBurst);
+        }
+
+        if refill_interval.is_zero() {
+            return Err(InvalidRateLimit::ZeroRefillInterval);
+        }
+
+        Ok(Self { burst,
+                  refill_interval })
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self { burst: *RATE_LIMIT_BURST,
+               refill_interval: *RATE_LIMIT_REFILL_INTERVAL }
+    }
+}
+
+/// A token bucket for a single API root
+#[derive(Debug)]
+struct TokenBucket {
+    /// Configuration of this bucket
+    limit: RateLimit,
+    /// Currently available tokens
+    tokens: f64,
+    /// Last time the tokens have been refilled
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a new and full [`TokenBucket`]
+    fn with_limit(limit: RateLimit) -> Self {
+        Self { limit,
+               tokens: f64::from(limit.burst),
+               last_refill: Instant::now() }
+    }
+
+    /// Refill the tokens for the time passed since the last refill
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill);
+
+        self.tokens = (self.tokens +
+                       elapsed.as_secs_f64() /
+                       self.limit.refill_interval.as_secs_f64())
+                                                .min(f64::from(self.limit.burst));
+        self.last_refill = now;
+    }
+
+    /// Take a token if one is available, otherwise return the time to wait
+    /// until the next token is available
+    fn try_take(&mut self) -> Result<(), Duration> {
+        self.refill();
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        }
+        else {
+            Err(self.limit
+                    .refill_interval
+                    .mul_f64(1.0 - self.tokens))
+        }
+    }
+}
+
+/// Rate limiter with a token bucket per API root
+///
+/// Clones share the same buckets, so all clones of a client are limited
+/// together.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    /// Token buckets for each API root, the [`Mutex`] is queueing the waiting
+    /// requests. Roots without a configured bucket use the
+    /// [`RateLimit::default`]
+    buckets: Arc<DashMap<String, Arc<Mutex<TokenBucket>>>>,
+}
+
+impl RateLimiter {
+    /// Create a new [`RateLimiter`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configure the [`RateLimit`] for an API root
+    pub fn configure(&self,
+                     root: &str,
+                     limit: RateLimit) {
+        self.buckets.insert(normalize_root(root).to_string(),
+                            Arc::new(Mutex::new(TokenBucket::with_limit(limit))));
+    }
+
+    /// Wait until a request towards `root` is allowed
+    pub async fn acquire(&self,
+                         root: &str) {
+        let root = normalize_root(root);
+        let bucket = self.buckets
+                         .entry(root.to_string())
+                         .or_insert_with(|| {
+                             Arc::new(Mutex::new(TokenBucket::with_limit(
+                                 RateLimit::default(),
+                             )))
+                         })
+                         .clone();
+
+        // Holding the lock while sleeping queues all other requests towards
+        // the same root in order
+        let mut guard = bucket.lock().await;
+
+        while let Err(wait) = guard.try_take() {
+            info!("Rate limit reached for {}, throttling request for {:?}",
+                  root,
+                  wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn rate_limiter_queues_requests() {
+    let limiter = RateLimiter::new();
+    limiter.configure("https://aoe2.net/api",
+                      RateLimit::new(2, Duration::from_millis(50)).unwrap());
+
+    let start = Instant::now();
+
+    for _ in 0..4 {
+        limiter.acquire("https://aoe2.net/api/").await;
+    }
+
+    // Two requests are made in a burst, the other two have to wait for a
+    // refill each
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn rate_limit_rejects_zero_values() {
+    assert_eq!(RateLimit::new(0, Duration::from_millis(50)),
+               Err(InvalidRateLimit::ZeroBurst));
+    assert_eq!(RateLimit::new(2, Duration::from_secs(0)),
+               Err(InvalidRateLimit::ZeroRefillInterval));
+}
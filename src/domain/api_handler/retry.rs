@@ -0,0 +1,178 @@
+//! Retrying of idempotent `GET` requests on transient upstream failures
+//!
+//! Requests are retried on connection errors, timeouts, `429 Too Many
+//! Requests` and `5xx` responses with a jittered exponential backoff. A
+//! `Retry-After` header sent by the upstream takes precedence over the
+//! backoff, if it asks for a longer wait than our `max_delay` we give up
+//! instead of retrying too early.
+
+use std::{
+    error::Error,
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+
+use http::{
+    header::RETRY_AFTER,
+    HeaderMap,
+    StatusCode,
+};
+use rand::Rng;
+
+use crate::{
+    RETRY_BASE_DELAY,
+    RETRY_MAX_DELAY,
+    RETRY_MAX_RETRIES,
+};
+
+/// Configuration of how often and how long to wait between retries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum amount of retries after the initial request, `0` disables
+    /// retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, gets doubled for each further retry
+    pub base_delay: Duration,
+    /// Upper bound for the delay between two requests, a longer
+    /// `Retry-After` means we don't retry at all
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_retries: *RETRY_MAX_RETRIES,
+               base_delay: *RETRY_BASE_DELAY,
+               max_delay: *RETRY_MAX_DELAY }
+    }
+}
+
+impl RetryPolicy {
+    /// A [`RetryPolicy`] that never retries
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self { max_retries: 0,
+               ..Self::default() }
+    }
+
+    /// Returns `true` if another retry is allowed after `attempt` retries
+    #[must_use]
+    pub fn should_retry(&self,
+                        attempt: u32)
+                        -> bool {
+        attempt < self.max_retries
+    }
+
+    /// Delay before the next retry after `attempt` retries, a `retry_after`
+    /// given by the upstream takes precedence over the backoff
+    ///
+    /// Returns `None` if the upstream asks us to wait longer than the
+    /// `max_delay`, so the request should not be retried.
+    #[must_use]
+    pub fn delay(&self,
+                 attempt: u32,
+                 retry_after: Option<Duration>)
+                 -> Option<Duration> {
+        match retry_after {
+            Some(retry_after) if retry_after > self.max_delay => None,
+            Some(retry_after) => Some(retry_after),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Exponential backoff with jitter, randomly between half and the full
+    /// exponential delay to not retry in lockstep with other requests
+    #[must_use]
+    pub fn backoff(&self,
+               attempt: u32) -> Duration {
+        let exponential = self.base_delay
+                              .checked_mul(2_u32.saturating_pow(attempt))
+                              .unwrap_or(self.max_delay)
+                              .min(self.max_delay);
+
+        exponential.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// Returns `true` if a response with that `status` is worth retrying
+#[must_use]
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Returns `true` if the error of an HTTP client is a connection error or a
+/// timeout
+#[must_use]
+pub fn is_retryable_error(err: &(dyn Error + 'static)) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+       .is_some_and(|err| err.is_connect() || err.is_timeout())
+}
+
+/// Parses the `Retry-After` header in its `delay-seconds` or `HTTP-date`
+/// form, a date in the past means no delay
+#[must_use]
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let val = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match val.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            httpdate::parse_http_date(val).ok().map(|date| {
+                date.duration_since(SystemTime::now()).unwrap_or_default()
+            })
+        },
+    }
+}
+
+#[test]
+fn backoff_grows_and_is_capped() {
+    let policy = RetryPolicy { max_retries: 5,
+                               base_delay: Duration::from_millis(100),
+                               max_delay: Duration::from_secs(1) };
+
+    let first = policy.delay(0, None).unwrap();
+    assert!(first >= Duration::from_millis(50) &&
+            first <= Duration::from_millis(100));
+
+    let third = policy.delay(2, None).unwrap();
+    assert!(third >= Duration::from_millis(200) &&
+            third <= Duration::from_millis(400));
+
+    assert!(policy.delay(10, None).unwrap() <= Duration::from_secs(1));
+}
+
+#[test]
+fn retry_after_is_honored_or_given_up_on() {
+    let policy = RetryPolicy { max_retries: 5,
+                               base_delay: Duration::from_millis(100),
+                               max_delay: Duration::from_secs(5) };
+
+    assert_eq!(policy.delay(0, Some(Duration::from_secs(2))),
+               Some(Duration::from_secs(2)));
+    assert_eq!(policy.delay(0, Some(Duration::from_secs(30))), None);
+}
+
+#[test]
+fn retry_after_is_parsed_from_seconds() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+
+    headers.insert(RETRY_AFTER, "2".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+}
+
+#[test]
+fn retry_after_is_parsed_from_http_date() {
+    let mut headers = HeaderMap::new();
+
+    let in_a_minute = SystemTime::now() + Duration::from_secs(60);
+    headers.insert(RETRY_AFTER,
+                   httpdate::fmt_http_date(in_a_minute).parse().unwrap());
+    let delay = retry_after(&headers).unwrap();
+    assert!(delay > Duration::from_secs(58) && delay <= Duration::from_secs(60));
+
+    headers.insert(RETRY_AFTER,
+                   "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(0)));
+}
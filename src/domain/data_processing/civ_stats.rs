@@ -0,0 +1,455 @@
+//! Win rates of the civilizations of a match from aoestats.io for the
+//! `matchinfo` endpoint
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    result,
+    sync::Arc,
+};
+
+use aoestats::{
+    endpoints::{
+        civilizations::GetCivilizationStatsRequest,
+        defs::GetDefinitionsRequest,
+        Raw,
+    },
+    types::api::{
+        CivilizationStats,
+        Definitions,
+        Page,
+    },
+};
+use api_client::{
+    error::{
+        ApiRequestGetError,
+        ClientRequestError,
+    },
+    request::{
+        Request,
+        RequestGet,
+    },
+};
+use futures::future;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::response_data,
+        types::{
+            api::{
+                CivMapWinRate,
+                CivMatchup,
+                CivStats,
+            },
+            error::{
+                ApiRequestError,
+                ResponderError,
+            },
+            match_data::MatchData,
+            InMemoryDb,
+        },
+        util,
+    },
+    AOESTATS_CACHE_TTL,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// The only game aoestats.io has stats for
+pub(crate) const CIV_STATS_GAME: &str = "aoe2de";
+
+/// Description of the series of the civilization stats with the win rate on
+/// each map, keyed by the number of the map
+const MAP_WIN_RATES_SERIES: &str = "win rate on each map";
+/// Description of the series of the civilization stats with the win rate
+/// against each other civilization, keyed by the number of the civilization
+const CIV_WIN_RATES_SERIES: &str = "win rate vs each other civ";
+
+/// Civilization of a player of the match with its stats on aoestats.io
+#[derive(Debug, Clone, Default)]
+struct PlayerCiv {
+    profile_id: String,
+    /// Name of the civilization in English
+    civilisation: String,
+    /// Number of the civilization on aoestats.io
+    civ_num: Option<i64>,
+    stats: Option<CivilizationStats>,
+}
+
+/// [`CivStats`] of the players of a match of Age of Empires II: Definitive
+/// Edition
+///
+/// aoestats.io numbers civilizations, maps and ladders differently than
+/// aoe2.net, so they are matched by their English name in `english_strings`
+/// to the [`Definitions`]. The series of the stats are looked up by their
+/// description. The `client` requests aoestats.io, unless it has a root set,
+/// e.g. for mocking.
+///
+/// # Errors
+/// Returns an error if the definitions or the stats of a civilization couldn't
+/// be requested
+pub(crate) async fn civ_stats(match_data: &MatchData,
+                              english_strings: &JsonValue,
+                              client: &A2NClient<'static, reqwest::Client>,
+                              in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                              -> Result<CivStats> {
+    let definitions: Definitions =
+        cached_request(GetDefinitionsRequest::new(), client, in_memory_db)
+            .await?;
+
+    let ladder = match_data.leaderboard_id
+                           .and_then(|id| usize::try_from(id).ok())
+                           .and_then(|id| {
+                               util::lookup_translation(english_strings,
+                                                        "leaderboard",
+                                                        id)
+                           })
+                           .and_then(|ladder| definitions.ladder_num(&ladder));
+    let map_num = match_data.map_type
+                            .and_then(|map_type| {
+                                util::lookup_translation(english_strings,
+                                                         "map_type",
+                                                         map_type)
+                            })
+                            .and_then(|map| definitions.map_num(&map));
+
+    let mut players =
+        match_data.players
+                  .iter()
+                  .map(|player| {
+                      let civilisation =
+                          player.civ
+                                .and_then(|civ| {
+                                    util::lookup_translation(english_strings,
+                                                             "civ",
+                                                             civ)
+                                })
+                                .unwrap_or_default();
+
+                      PlayerCiv { profile_id: player.profile_id.clone(),
+                                  civ_num: definitions.civ_num(&civilisation),
+                                  civilisation,
+                                  stats: None }
+                  })
+                  .collect::<Vec<_>>();
+
+    // Players with the same civilization share its stats
+    let mut civ_nums = players.iter()
+                              .filter_map(|player| player.civ_num)
+                              .collect::<Vec<_>>();
+    civ_nums.sort_unstable();
+    civ_nums.dedup();
+
+    let pages = future::join_all(civ_nums.iter().map(|civ_num| {
+                    let request =
+                        GetCivilizationStatsRequest::builder().civ_num(*civ_num)
+                                                              .ladder(ladder)
+                                                              .build();
+
+                    cached_request(request, client, in_memory_db)
+                }))
+                .await;
+
+    let mut stats_of_civs = HashMap::with_capacity(civ_nums.len());
+
+    for (civ_num, page) in civ_nums.into_iter().zip(pages) {
+        let page: Page<CivilizationStats> = page?;
+        stats_of_civs.insert(civ_num, overall_stats(page));
+    }
+
+    for player in &mut players {
+        player.stats =
+            player.civ_num.and_then(|civ_num| stats_of_civs[&civ_num].clone());
+    }
+
+    Ok(civ_stats_from(&players, map_num, &definitions))
+}
+
+/// Response of aoestats.io to the `request`, which is cached in the
+/// [`InMemoryDb`] for [`static@crate::AOESTATS_CACHE_TTL`]
+///
+/// The response is cached by the `URI` on the API root the `client` requests,
+/// so responses of a mocked root aren't mixed up with the ones of aoestats.io.
+///
+/// # Errors
+/// Returns an error if the request failed or the response couldn't be
+/// deserialized
+async fn cached_request<R>(request: R,
+                           client: &A2NClient<'static, reqwest::Client>,
+                           in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                           -> Result<R::Response>
+    where R: Request + RequestGet,
+          R::Response: DeserializeOwned,
+{
+    let key = request.get_uri_with_root(client.root_for::<R>())
+                     .map_err(|err| {
+                         ApiRequestError::from(ClientRequestError::from(
+                             ApiRequestGetError::from(err),
+                         ))
+                     })?
+                     .to_string();
+
+    let responses = in_memory_db.lock().await.aoestats_responses.clone();
+
+    if let Some(response) = responses.get(&key) {
+        debug!("Serving cached aoestats response for {}", key);
+        return Ok(serde_json::from_value(response)?);
+    }
+
+    let response = response_data(client.req_get(Raw(request)).await)?;
+    let parsed = serde_json::from_value(response.clone())?;
+
+    responses.insert(key, response, *AOESTATS_CACHE_TTL);
+
+    Ok(parsed)
+}
+
+/// Stats of a civilization over all ratings on the most recent patch
+fn overall_stats(page: Page<CivilizationStats>) -> Option<CivilizationStats> {
+    page.results.into_iter().max_by_key(|stats| {
+                                let patch = stats.filter
+                                                 .patch_val
+                                                 .as_deref()
+                                                 .and_then(|patch| {
+                                                     patch.parse::<u64>().ok()
+                                                 });
+
+                                (stats.filter.elo_val.is_none(), patch)
+                            })
+}
+
+/// Win rate in percent for `num` in the `series` of the `stats`, `None` if
+/// one of them is unknown
+#[allow(clippy::cast_possible_truncation)]
+fn series_win_rate(stats: Option<&CivilizationStats>,
+                   series: Option<&str>,
+                   num: Option<i64>)
+                   -> Option<f32> {
+    stats?.series
+          .get(series?)?
+          .get(num?.to_string())?
+          .as_f64()
+          .map(|win_rate| (win_rate * 100.0) as f32)
+}
+
+/// Build the [`CivStats`] of the `players` on the map with `map_num`, the
+/// matchup is only added for 1v1s
+fn civ_stats_from(players: &[PlayerCiv],
+                  map_num: Option<i64>,
+                  definitions: &Definitions)
+                  -> CivStats {
+    let map_series = definitions.series_name(MAP_WIN_RATES_SERIES);
+    let civ_series = definitions.series_name(CIV_WIN_RATES_SERIES);
+
+    let players_win_rates =
+        players.iter()
+               .map(|player| {
+                   CivMapWinRate { profile_id: player.profile_id.clone(),
+                                   civilisation: player.civilisation.clone(),
+                                   win_rate:
+                                       series_win_rate(player.stats.as_ref(),
+                                                       map_series,
+                                                       map_num) }
+               })
+               .collect();
+
+    let matchup = match players {
+        [first, second] => {
+            Some(CivMatchup { civilisation: first.civilisation.clone(),
+                              opponent_civilisation: second.civilisation
+                                                           .clone(),
+                              win_rate:
+                                  series_win_rate(first.stats.as_ref(),
+                                                  civ_series,
+                                                  second.civ_num) })
+        },
+        _ => None,
+    };
+
+    CivStats { players: players_win_rates,
+               matchup }
+}
+
+#[test]
+fn civ_stats_are_taken_from_the_series() {
+    let stats = |civ_num: i64, series: JsonValue| {
+        serde_json::from_value::<CivilizationStats>(serde_json::json!({
+            "filter": {"elo_val": null, "ladder_val": 3, "patch_val": "37906"},
+            "civ_num": civ_num,
+            "series": series
+        }))
+        .unwrap()
+    };
+
+    let player = |profile_id: &str, civilisation: &str, civ_num, stats| {
+        PlayerCiv { profile_id: profile_id.to_string(),
+                    civilisation: civilisation.to_string(),
+                    civ_num: Some(civ_num),
+                    stats }
+    };
+
+    let definitions: Definitions = serde_json::from_value(serde_json::json!({
+        "series": {"map_wr": "Win rate on each map",
+                   "civ_wr": "Win rate vs each other civ"}
+    }))
+    .unwrap();
+
+    let players =
+        vec![player("196240",
+                    "Mayans",
+                    19,
+                    Some(stats(19,
+                               serde_json::json!({
+                                   "map_wr": {"9": 0.55},
+                                   "civ_wr": {"5": 0.5125}
+                               })))),
+             player("199325", "Franks", 5, None)];
+
+    let civ_stats = civ_stats_from(&players, Some(9), &definitions);
+
+    assert_eq!(civ_stats.players[0].win_rate, Some(55.0));
+    assert_eq!(civ_stats.players[1].win_rate, None);
+
+    let matchup = civ_stats.matchup.unwrap();
+    assert_eq!((matchup.civilisation.as_str(),
+                matchup.opponent_civilisation.as_str()),
+               ("Mayans", "Franks"));
+    assert_eq!(matchup.win_rate, Some(51.25));
+
+    assert_eq!(civ_stats_from(&players[..1], Some(9), &definitions).matchup,
+               None);
+
+    // Without the series in the definitions there are no win rates
+    let civ_stats = civ_stats_from(&players, Some(9), &Definitions::default());
+    assert_eq!(civ_stats.players[0].win_rate, None);
+    assert_eq!(civ_stats.matchup.unwrap().win_rate, None);
+}
+
+#[tokio::test]
+async fn civ_stats_are_cached_in_the_in_memory_db() {
+    use wiremock::{
+        matchers::{
+            method,
+            path,
+            query_param,
+        },
+        Mock,
+        MockServer,
+        ResponseTemplate,
+    };
+
+    use crate::domain::types::match_data::MatchPlayerData;
+
+    let mock_server = MockServer::start().await;
+
+    // aoestats.io numbers civilizations, maps and ladders differently than
+    // aoe2.net
+    Mock::given(method("GET"))
+        .and(path("/de/defs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(
+            serde_json::json!({
+                "civs": {"Mayans": 19, "Franks": 5},
+                "maps": {"Arabia": 9},
+                "ladder": {"1v1 Random Map": 2},
+                "series": {"map_wr": "Win rate on each map",
+                           "civ_wr": "Win rate vs each other civ"}
+            }),
+        ))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    for (civ_num, series) in
+        &[("19", serde_json::json!({"map_wr": {"9": 0.55},
+                                    "civ_wr": {"5": 0.5125}})),
+          ("5", serde_json::json!({"map_wr": {"9": 0.48}}))]
+    {
+        Mock::given(method("GET"))
+            .and(path("/de/civilizations/"))
+            .and(query_param("civ_num", *civ_num))
+            .and(query_param("filter__ladder_val", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"results": [{"filter": {"elo_val": null},
+                                                "series": series}]}),
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let english_strings = serde_json::json!({
+        "civ": [{"id": 20, "string": "Mayans"}, {"id": 9, "string": "Franks"}],
+        "map_type": [{"id": 29, "string": "Arabia"}],
+        "leaderboard": [{"id": 3, "string": "1v1 Random Map"}]
+    });
+
+    let player = |profile_id: &str, civ| {
+        MatchPlayerData { profile_id: profile_id.to_string(),
+                          civ: Some(civ),
+                          ..MatchPlayerData::default() }
+    };
+
+    let match_data = MatchData { leaderboard_id: Some(3),
+                                 map_type: Some(29),
+                                 players: vec![player("196240", 20),
+                                               player("199325", 9)],
+                                 ..MatchData::default() };
+
+    let root = url::Url::parse(&mock_server.uri()).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    // Different clients, so the response cache of the client can't be the
+    // reason for a single upstream request
+    for _ in 0..2 {
+        let client = A2NClient::with_client(reqwest::Client::default())
+            .with_root(root.clone());
+
+        let civ_stats =
+            civ_stats(&match_data, &english_strings, &client, &in_memory_db)
+                .await
+                .unwrap();
+
+        assert_eq!(civ_stats.players
+                            .iter()
+                            .map(|player| player.win_rate)
+                            .collect::<Vec<_>>(),
+                   vec![Some(55.0), Some(48.0)]);
+        assert_eq!(civ_stats.matchup.unwrap().win_rate, Some(51.25));
+    }
+
+    // The responses of the mocked root are not served for aoestats.io
+    let responses = in_memory_db.lock().await.aoestats_responses.clone();
+    let definitions = GetDefinitionsRequest::new();
+
+    assert!(responses.get(&definitions.get_uri_with_root(root.as_str())
+                                      .unwrap()
+                                      .to_string())
+                     .is_some());
+    assert!(responses.get(&definitions.get_uri().unwrap().to_string())
+                     .is_none());
+}
+
+#[test]
+fn overall_stats_prefer_all_ratings_on_the_latest_patch() {
+    let stats = |elo_val: Option<&str>, patch_val: &str, num_played: i64| {
+        serde_json::json!({
+            "filter": {"elo_val": elo_val, "patch_val": patch_val},
+            "num_played": num_played
+        })
+    };
+
+    let page: Page<CivilizationStats> = serde_json::from_value(
+        serde_json::json!({"results": [stats(Some("1650+"), "37906", 1),
+                                       stats(None, "37650", 2),
+                                       stats(None, "37906", 3)]}),
+    )
+    .unwrap();
+
+    assert_eq!(overall_stats(page).unwrap().num_played, 3);
+    assert_eq!(overall_stats(Page::default()), None);
+}
@@ -0,0 +1,195 @@
+//! Building the head-to-head record of the players of a 1v1 for the
+//! `matchinfo` endpoint
+
+use std::{
+    collections::HashSet,
+    result,
+    sync::Arc,
+};
+
+use aoe2net::types::api::Match;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_history::{
+            match_history_entries,
+            performance_by,
+            request_match_history,
+        },
+        types::{
+            api::{
+                HeadToHead,
+                HeadToHeadPlayer,
+                HeadToHeadResult,
+            },
+            error::ResponderError,
+            HeadToHeadKey,
+            InMemoryDb,
+        },
+    },
+    HEAD_TO_HEAD_CACHE_TTL,
+    HEAD_TO_HEAD_MATCH_COUNT,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Amount of most recent shared matches in a [`HeadToHead`]
+const LAST_RESULTS_COUNT: usize = 5;
+/// Amount of most played maps in a [`HeadToHead`]
+const MAPS_COUNT: usize = 3;
+
+/// Head-to-head record of the player pair in `key`, which is cached for
+/// [`static@crate::HEAD_TO_HEAD_CACHE_TTL`]
+///
+/// The `client` needs to have its root set already.
+///
+/// # Errors
+/// Returns an error if the match history of one of the players couldn't be
+/// requested
+pub(crate) async fn head_to_head(key: HeadToHeadKey,
+                                 client: &A2NClient<'static,
+                                                    reqwest::Client>,
+                                 in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                                 -> Result<HeadToHead> {
+    let head_to_heads = in_memory_db.lock().await.head_to_heads.clone();
+
+    if let Some(head_to_head) = head_to_heads.get(&key) {
+        debug!("Serving cached head-to-head for {:?}", key.profile_ids);
+        return Ok(head_to_head);
+    }
+
+    let (first, second) = &key.profile_ids;
+
+    let (first_history, second_history) =
+        futures::join!(request_match_history(client,
+                                             &key.game,
+                                             first,
+                                             *HEAD_TO_HEAD_MATCH_COUNT),
+                       request_match_history(client,
+                                             &key.game,
+                                             second,
+                                             *HEAD_TO_HEAD_MATCH_COUNT));
+
+    let translation = in_memory_db.lock()
+                                  .await
+                                  .language_strings(&key.game, &key.language)
+                                  .unwrap_or(JsonValue::Null);
+
+    let head_to_head = head_to_head_from_histories(&key.profile_ids,
+                                                   &first_history?,
+                                                   &second_history?,
+                                                   &translation);
+
+    head_to_heads.insert(key, head_to_head.clone(), *HEAD_TO_HEAD_CACHE_TTL);
+
+    Ok(head_to_head)
+}
+
+/// Build the [`HeadToHead`] of the `profile_ids` from the matches that are
+/// part of both match histories and where the players were opponents
+#[allow(clippy::cast_possible_truncation)]
+fn head_to_head_from_histories(profile_ids: &(String, String),
+                               first_history: &[Match],
+                               second_history: &[Match],
+                               translation: &JsonValue)
+                               -> HeadToHead {
+    let (first, second) = profile_ids;
+
+    let second_match_ids = second_history.iter()
+                                         .filter_map(|match_data| {
+                                             match_data.match_id.as_deref()
+                                         })
+                                         .collect::<HashSet<_>>();
+
+    // Matches from the perspective of the first player
+    let shared = match_history_entries(first, first_history, translation)
+        .into_iter()
+        .filter(|entry| {
+            second_match_ids.contains(entry.match_id.as_str()) &&
+            entry.opponents
+                 .iter()
+                 .any(|opponent| &opponent.profile_id == second)
+        })
+        .collect::<Vec<_>>();
+
+    let wins = |won: bool| {
+        shared.iter().filter(|entry| entry.won == Some(won)).count() as u32
+    };
+
+    let last_results =
+        shared.iter()
+              .take(LAST_RESULTS_COUNT)
+              .map(|entry| HeadToHeadResult {
+                  match_id: entry.match_id.clone(),
+                  started: entry.started.clone(),
+                  map: entry.map.clone(),
+                  winner: entry.won.map(|won| {
+                                       if won { first } else { second }.clone()
+                                   }),
+              })
+              .collect();
+
+    let mut maps = performance_by(&shared, |entry| entry.map.clone());
+    maps.truncate(MAPS_COUNT);
+
+    HeadToHead { players: vec![HeadToHeadPlayer { profile_id: first.clone(),
+                                                  wins: wins(true) },
+                               HeadToHeadPlayer { profile_id: second.clone(),
+                                                  wins: wins(false) },],
+                 games: shared.len() as u32,
+                 last_results,
+                 maps }
+}
+
+#[test]
+fn head_to_head_only_counts_shared_matches_as_opponents() {
+    let match_data = |match_id: &str, map_type: u64, won: bool, team: u64| {
+        serde_json::from_value::<Match>(serde_json::json!({
+            "match_id": match_id,
+            "map_type": map_type,
+            "started": 1_614_000_000,
+            "players": [
+                {"profile_id": 196240, "civ": 29, "team": 1, "won": won},
+                {"profile_id": 199325, "name": "Hera", "civ": 24,
+                 "team": team, "won": !won}
+            ]
+        }))
+        .unwrap()
+    };
+
+    let first_history = (1..=7).map(|id| match_data(&id.to_string(),
+                                                    9,
+                                                    id % 3 != 0,
+                                                    2))
+                               .chain(vec![match_data("8", 29, false, 2),
+                                           // Teammates in a team game
+                                           match_data("9", 29, true, 1),
+                                           // Not in the history of the second
+                                           // player
+                                           match_data("10", 29, true, 2)])
+                               .collect::<Vec<_>>();
+
+    let second_history = (1..=9).map(|id| {
+                                    match_data(&id.to_string(), 9, true, 2)
+                                })
+                                .collect::<Vec<_>>();
+
+    let head_to_head =
+        head_to_head_from_histories(&("196240".to_string(),
+                                      "199325".to_string()),
+                                    &first_history,
+                                    &second_history,
+                                    &JsonValue::Null);
+
+    assert_eq!(head_to_head.games, 8);
+    assert_eq!((head_to_head.players[0].wins, head_to_head.players[1].wins),
+               (5, 3));
+    assert_eq!(head_to_head.last_results.len(), LAST_RESULTS_COUNT);
+    assert_eq!(head_to_head.last_results[2].winner.as_deref(), Some("199325"));
+    assert_eq!((head_to_head.maps[0].name.as_str(), head_to_head.maps[0].games),
+               ("9", 7));
+}
@@ -0,0 +1,191 @@
+//! Enriching a page of an aoe2.net leaderboard with the `aoc-reference-data`
+//! for the `leaderboard/{leaderboard_id}` endpoint
+
+use std::{
+    convert::TryFrom,
+    result,
+    sync::Arc,
+};
+
+use aoe2net::{
+    endpoints::leaderboard::GetLeaderboardRequest,
+    types::api as aoe2net_api,
+};
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::typed_response_data,
+        types::{
+            aoc_ref::RefDataLists,
+            api::{
+                LeaderboardEntry,
+                LeaderboardRequest,
+                LeaderboardResponse,
+                PlayerTeam,
+            },
+            error::ResponderError,
+            InMemoryDb,
+        },
+        util,
+    },
+    STANDARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Entry point for processing part of `leaderboard/{leaderboard_id}` endpoint
+///
+/// The pages of aoe2.net are cached by the response cache of the `client`.
+///
+/// # Errors
+/// Returns an error if the leaderboard couldn't be requested or parsed
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn build_leaderboard(par: LeaderboardRequest,
+                               client: A2NClient<'static, reqwest::Client>,
+                               root: Url,
+                               in_memory_db: Arc<Mutex<InMemoryDb>>)
+                               -> Result<LeaderboardResponse> {
+    let client = client.with_root(root);
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    let language = par.language.clone().unwrap_or_else(|| {
+                                           (*STANDARD.get(&"language")
+                                                     .unwrap())
+                                           .to_string()
+                                       });
+
+    let req = GetLeaderboardRequest::builder()
+        .game(game.as_str())
+        .leaderboard_id(par.leaderboard_id)
+        .start(par.start)
+        .count(par.count)
+        .build();
+
+    let page = typed_response_data(client.req_get(req).await)?;
+
+    let db = in_memory_db.lock().await;
+
+    let leaderboard_name =
+        db.language_strings(&game, &language).and_then(|translation| {
+            usize::try_from(par.leaderboard_id)
+                .ok()
+                .and_then(|id| {
+                    util::lookup_translation(&translation, "leaderboard", id)
+                })
+        });
+
+    Ok(LeaderboardResponse { leaderboard_id: par.leaderboard_id,
+                             leaderboard_name,
+                             total: page.total,
+                             entries: enrich_entries(page.leaderboard,
+                                                     &db.github_file_content,
+                                                     &game) })
+}
+
+/// Convert the `rows` of aoe2.net into [`LeaderboardEntry`]s, players of the
+/// `aoc-reference-data` for that `game` get their alias, team and country from
+/// it
+#[allow(clippy::cast_precision_loss)]
+fn enrich_entries(rows: Vec<aoe2net_api::LeaderboardEntry>,
+                  ref_data: &RefDataLists,
+                  game: &str)
+                  -> Vec<LeaderboardEntry> {
+    rows.into_iter()
+        .map(|row| {
+            let profile_id =
+                row.profile_id.map(|id| id.to_string()).unwrap_or_default();
+            let alias =
+                ref_data.lookup_player_alias_for_profile_id(game, &profile_id);
+
+            let team = alias.as_ref().and_then(|alias| {
+                                         ref_data.lookup_team_for_player_name(
+                                             &alias.name,
+                                         )
+                                     });
+
+            let country = alias.as_ref()
+                               .map(|alias| alias.country.clone())
+                               .or_else(|| {
+                                   row.country
+                                      .as_deref()
+                                      .map(str::to_lowercase)
+                               });
+
+            let wins = row.wins.unwrap_or_default();
+            let losses = row.losses.unwrap_or_default();
+            let games = wins + losses;
+
+            LeaderboardEntry {
+                rank: row.rank.unwrap_or_default(),
+                profile_id,
+                name: row.name.unwrap_or_default(),
+                team: team.map(PlayerTeam::from),
+                country,
+                alias: alias.map(|alias| alias.name),
+                rating: row.rating.unwrap_or_default(),
+                highest_rating: row.highest_rating,
+                streak: row.streak.unwrap_or_default(),
+                wins,
+                losses,
+                win_rate: if games == 0 {
+                    None
+                }
+                else {
+                    Some(wins as f32 / games as f32 * 100_f32)
+                },
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn leaderboard_entries_are_enriched_with_reference_data() {
+    use crate::domain::types::testing::ref_data_lists;
+
+    let ref_data = ref_data_lists(
+        serde_json::json!([{
+            "name": "TheViper",
+            "country": "no",
+            "platforms": { "de": ["196240"] }
+        }]),
+        serde_json::json!([{
+            "name": "GamerLegion",
+            "abbreviation": "GL",
+            "players": ["TheViper"]
+        }]),
+    );
+
+    let page: aoe2net_api::Leaderboard =
+        serde_json::from_value(serde_json::json!({
+            "total": 40000,
+            "leaderboard": [
+                {"profile_id": 196240, "rank": 1, "name": "GL.TheViper",
+                 "country": "NO", "rating": 2600, "highest_rating": 2700,
+                 "streak": 3, "wins": 30, "losses": 10},
+                {"profile_id": 1, "rank": 2, "name": "Unknown",
+                 "country": "DE", "rating": 2500, "streak": -1, "wins": 0,
+                 "losses": 0}
+            ]
+        }))
+        .unwrap();
+
+    let entries = enrich_entries(page.leaderboard, &ref_data, "aoe2de");
+
+    assert_eq!(entries[0].alias.as_deref(), Some("TheViper"));
+    assert_eq!(entries[0].team.as_ref().unwrap().abbreviation.as_deref(),
+               Some("GL"));
+    assert_eq!(entries[0].win_rate, Some(75.0));
+    assert_eq!((entries[1].alias.as_ref(), entries[1].country.as_deref()),
+               (None, Some("de")));
+    assert_eq!(entries[1].win_rate, None);
+}
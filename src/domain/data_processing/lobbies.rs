@@ -0,0 +1,190 @@
+//! Translating and filtering the open lobbies of aoe2.net for the `lobbies`
+//! endpoint
+
+use std::{
+    result,
+    sync::Arc,
+};
+
+use aoe2net::{
+    endpoints::lobbies::GetLobbiesRequest,
+    types::api as aoe2net_api,
+};
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::typed_response_data,
+        types::{
+            aoc_ref::RefDataLists,
+            api::{
+                LobbiesRequest,
+                LobbiesResponse,
+                Lobby,
+                LobbyPlayer,
+                Server,
+            },
+            error::ResponderError,
+            InMemoryDb,
+        },
+        util,
+    },
+    STANDARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Entry point for processing part of `lobbies` endpoint
+///
+/// # Errors
+/// Returns an error if the lobbies couldn't be requested
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn build_lobbies(par: LobbiesRequest,
+                           client: A2NClient<'static, reqwest::Client>,
+                           root: Url,
+                           in_memory_db: Arc<Mutex<InMemoryDb>>)
+                           -> Result<LobbiesResponse> {
+    let client = client.with_root(root);
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    let language = par.language.clone().unwrap_or_else(|| {
+                                           (*STANDARD.get(&"language")
+                                                     .unwrap())
+                                           .to_string()
+                                       });
+
+    let req = GetLobbiesRequest::builder().game(game.as_str()).build();
+
+    let lobbies = typed_response_data(client.req_get(req).await)?;
+
+    let db = in_memory_db.lock().await;
+
+    let translation = db.language_strings(&game, &language)
+                        .unwrap_or(JsonValue::Null);
+
+    let lobbies = lobbies.iter()
+                         .map(|lobby| {
+                             translate_lobby(lobby,
+                                             &translation,
+                                             &db.github_file_content,
+                                             &game)
+                         })
+                         .filter(|lobby| par.matches(lobby))
+                         .collect::<Vec<_>>();
+
+    debug!("Found {} lobbies for {:?}", lobbies.len(), par);
+
+    Ok(LobbiesResponse { lobbies })
+}
+
+/// Convert a `lobby` of aoe2.net into a [`Lobby`] with names translated by the
+/// `translation` and players enriched with the `aoc-reference-data` of the
+/// `game`
+fn translate_lobby(lobby: &aoe2net_api::Lobby,
+                   translation: &JsonValue,
+                   ref_data: &RefDataLists,
+                   game: &str)
+                   -> Lobby {
+    let translate = |first: &str, id: Option<i64>| {
+        util::translate_id(translation, first, id)
+    };
+
+    let players = lobby.players
+                       .iter()
+                       // Open slots are listed without a player
+                       .filter_map(|player| {
+                           let profile_id = player.profile_id?.to_string();
+                           let alias = ref_data
+                               .lookup_player_alias_for_profile_id(game,
+                                                                   &profile_id);
+                           let country = player.country
+                                               .as_deref()
+                                               .map(str::to_lowercase);
+
+                           Some(LobbyPlayer {
+                               name: player.name.clone().unwrap_or_default(),
+                               country: alias.as_ref()
+                                             .map(|alias| alias.country.clone())
+                                             .or(country),
+                               alias: alias.map(|alias| alias.name),
+                               rating: player.rating,
+                               profile_id,
+                           })
+                       })
+                       .collect();
+
+    Lobby { match_id: lobby.match_id.clone().unwrap_or_default(),
+            name: lobby.name.clone().unwrap_or_default(),
+            map: translate("map_type", lobby.map_type),
+            game_type: translate("game_type", lobby.game_type),
+            rating_type: translate("rating_type", lobby.rating_type),
+            server: lobby.server
+                         .as_deref()
+                         .map_or(Server::NotFound, Server::from),
+            average_rating: lobby.average_rating,
+            num_players: lobby.num_players.unwrap_or_default(),
+            num_slots: lobby.num_slots.unwrap_or_default(),
+            opened: lobby.opened.map(util::iso_timestamp),
+            players }
+}
+
+#[test]
+fn lobby_is_translated_and_enriched() {
+    use crate::domain::types::testing::ref_data_lists;
+
+    let ref_data = ref_data_lists(
+        serde_json::json!([{
+            "name": "Hera",
+            "country": "ca",
+            "platforms": { "de": ["199325"] }
+        }]),
+        serde_json::json!([]),
+    );
+
+    let translation = serde_json::json!({
+        "map_type": [{"id": 9, "string": "Arabia"}],
+        "game_type": [{"id": 0, "string": "Random Map"}],
+        "rating_type": [{"id": 2, "string": "1v1 Random Map"}]
+    });
+
+    let lobby: aoe2net_api::Lobby = serde_json::from_value(serde_json::json!({
+        "match_id": "66",
+        "name": "1v1 arabia",
+        "map_type": 9,
+        "game_type": 0,
+        "rating_type": 2,
+        "server": "westeurope",
+        "average_rating": 2400,
+        "num_players": 1,
+        "num_slots": 2,
+        "opened": 1_614_000_000,
+        "players": [
+            {"profile_id": 199325, "name": "[aM] Hera", "country": "CA",
+             "rating": 2400},
+            {"profile_id": null, "name": null}
+        ]
+    }))
+    .unwrap();
+
+    let lobby = translate_lobby(&lobby, &translation, &ref_data, "aoe2de");
+
+    assert_eq!((lobby.map.as_str(),
+                lobby.game_type.as_str(),
+                lobby.rating_type.as_str()),
+               ("Arabia", "Random Map", "1v1 Random Map"));
+    assert_eq!(lobby.server, Server::WesternEurope);
+    assert_eq!(lobby.opened.as_deref(), Some("2021-02-22T13:20:00Z"));
+    assert_eq!(lobby.players.len(), 1);
+    assert_eq!(lobby.players[0].alias.as_deref(), Some("Hera"));
+}
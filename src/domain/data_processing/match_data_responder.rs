@@ -3,580 +3,57 @@
 //! in many places
 
 use std::{
+    collections::HashMap,
     fs,
     io::BufWriter,
-    path::PathBuf,
     result,
     sync::Arc,
 };
 
-use aoe2net::{
-    endpoints::{
-        last_match::GetLastMatchRequest,
-        leaderboard::GetLeaderboardRequest,
-        r#match::GetMatchRequest,
-        rating::GetRatingRequest,
-        rating_history::GetRatingHistoryRequest,
-    },
-    types::{
-        api::Player as aoe2net_Player,
-        helper::{
-            Aoe2netRequestType,
-            Aoe2netStringObj,
-            RecoveredRating,
-        },
-    },
+use api_client::{
+    error::ClientRequestError,
+    request::Request,
+    response::Response,
 };
 use ron::ser::{
     to_writer_pretty,
     PrettyConfig,
 };
-use serde_json::{
-    json,
-    Value as JsonValue,
-};
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
 use tokio::sync::Mutex;
 use tracing::{
     debug,
-    trace,
+    warn,
 };
-use url::Url;
 
 use crate::{
     domain::{
-        api_handler::client::A2NClient,
+        provider::MatchDataProvider,
         types::{
             api::{
+                IdType,
                 MatchInfoRequest,
-                Rating,
-                Server,
             },
-            error::ResponderError,
-            File,
-            FileFormat,
+            error::{
+                ApiRequestError,
+                ResponderError,
+            },
+            match_data::Translations,
             InMemoryDb,
             MatchDataResponses,
         },
-        util,
     },
     STANDARD,
 };
 
 type Result<T> = result::Result<T, ResponderError>;
 
-impl MatchDataResponses {
-    /// Return `String` for `leaderboard_id` for future requests
-    ///
-    /// # Errors
-    /// Will return an error if the `leaderboard_id` could not be found
-    pub fn get_leaderboard_id_from_request(&mut self,
-                                           req_type: Aoe2netRequestType)
-                                           -> Result<String> {
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                self.aoe2net.player_last_match.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "Leaderboard ID in last_match response".to_string(),
-                        ))
-                    },
-                    |val| Ok(val["last_match"]["leaderboard_id"].to_string()),
-                )
-            }
-            Aoe2netRequestType::MatchId => {
-                self.aoe2net.match_id.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "Leaderboard ID in match_id response".to_string(),
-                        ))
-                    },
-                    |val| Ok(val["leaderboard_id"].to_string()),
-                )
-            }
-            _ => Err(ResponderError::InvalidReqType(req_type.to_string())),
-        }
-    }
-
-    /// Parses all the players into a `type T`
-    /// from the `last_match` response for convenience
-    ///
-    /// # Errors
-    /// Will return an error if either the `players array` can not be found
-    /// or will `panic!` if the deserialisation failed or the parsing of the
-    /// `Player` struct failed
-    pub fn parse_players_into<T>(&self,
-                                 req_type: Aoe2netRequestType)
-                                 -> Result<T>
-        where T: for<'de> serde::Deserialize<'de>,
-    {
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                self.aoe2net.player_last_match.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "last_match players array".to_string(),
-                        ))
-                    },
-                    |val| {
-                        Ok(serde_json::from_str::<T>(&serde_json::to_string(
-                            &val["last_match"]["players"],
-                        )?)?)
-                    },
-                )
-            }
-            Aoe2netRequestType::MatchId => {
-                self.aoe2net.match_id.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "match_id players array".to_string(),
-                        ))
-                    },
-                    |val| {
-                        Ok(serde_json::from_str::<T>(&serde_json::to_string(
-                            &val["players"],
-                        )?)?)
-                    },
-                )
-            }
-            _ => Err(ResponderError::InvalidReqType(req_type.to_string())),
-        }
-    }
-
-    /// Returns the number of players from the `last_match` response
-    ///
-    /// # Errors
-    /// Will return an error if the `last_match` could not be found
-    pub fn get_player_amount(&self) -> Result<String> {
-        self.aoe2net.player_last_match.as_ref().map_or_else(
-            || Err(ResponderError::NotFound("number of players".to_string())),
-            |val| Ok(val["last_match"]["num_players"].to_string()),
-        )
-    }
-
-    /// Returns the finishing time of a match
-    /// We use that to see if a match is currently running (`Null`)
-    /// or already finished
-    ///
-    /// # Errors
-    /// Will return an error if the `last_match` could not be found
-    pub fn get_finished_time(&self,
-                             req_type: Aoe2netRequestType)
-                             -> Result<String> {
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                self.aoe2net.player_last_match.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "last_match finished time".to_string(),
-                        ))
-                    },
-                    |val| Ok(val["last_match"]["finished"].to_string()),
-                )
-            }
-            Aoe2netRequestType::MatchId => {
-                self.aoe2net.match_id.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "match_id finished time".to_string(),
-                        ))
-                    },
-                    |val| Ok(val["finished"].to_string()),
-                )
-            }
-            _ => Err(ResponderError::InvalidReqType(req_type.to_string())),
-        }
-    }
-
-    /// Returns the rating type id for a match
-    /// We use that to check if a game is rated/unrated
-    /// or in which ladder to look for the Rating
-    ///
-    /// # Errors
-    /// Will return an error if the `last_match` could not be found
-    pub fn get_id_for_rating_type(&self,
-                                  req_type: Aoe2netRequestType)
-                                  -> Result<usize> {
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                self.aoe2net.player_last_match.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "last match rating type".to_string(),
-                        ))
-                    },
-                    |val| {
-                        Ok(val["last_match"]["rating_type"]
-                            .to_string()
-                            .parse::<usize>()?)
-                    },
-                )
-            }
-            Aoe2netRequestType::MatchId => {
-                self.aoe2net.match_id.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "match id rating type".to_string(),
-                        ))
-                    },
-                    |val| {
-                        Ok(val["rating_type"].to_string().parse::<usize>()?)
-                    },
-                )
-            }
-            _ => Err(ResponderError::InvalidReqType(req_type.to_string())),
-        }
-    }
-
-    /// Get a the country from the `leaderboard response` for a given player
-    ///
-    /// # Errors
-    /// Won't throw an error, but `Option` is set to `None` resulting in an
-    /// empty player name that is taken if the `looked_up_player` doesn't
-    /// give any value
-    #[must_use]
-    pub fn get_country_code(looked_up_leaderboard: &(RecoveredRating,
-                              JsonValue))
-                            -> Option<String> {
-        let (recover, value) = looked_up_leaderboard;
-
-        if *recover == RecoveredRating::Original {
-            Some(value["country"].to_string().to_lowercase()).map(
-                |mut country| {
-                    country = util::remove_escaping(country);
-                    country
-                },
-            )
-        }
-        else {
-            None
-        }
-    }
-
-    /// Get a `Rating` datastructure from a `response` for a given player
-    ///
-    /// # Arguments
-    /// * `looked_up_rating` - a [`serde_json::Value`] type that holds the
-    ///   [`aoe2net::RatingHistory`] of an [`aoe2net::Player`]
-    /// * `looked_up_leaderboard` - a [`serde_json::Value`] type that holds
-    ///   Leaderboard data of an [`aoe2net::Player`]
-    ///
-    /// # Errors
-    /// Function will throw errors in cases the deserialisation and conversion
-    /// to the corresponding types is not successful
-    pub fn create_rating(looked_up_rating: &JsonValue,
-                         looked_up_leaderboard: &(RecoveredRating,
-                           JsonValue))
-                         -> Result<Rating> {
-        let player_rating = match looked_up_leaderboard {
-            (RecoveredRating::Original, leaderboard) => Rating::builder()
-                .mmr(serde_json::from_str::<u32>(&serde_json::to_string(
-                    &looked_up_rating["rating"],
-                )?)?)
-                .rank(serde_json::from_str::<u64>(&serde_json::to_string(
-                    &leaderboard["rank"],
-                )?)?)
-                .wins(serde_json::from_str::<u64>(&serde_json::to_string(
-                    &looked_up_rating["num_wins"],
-                )?)?)
-                .losses(serde_json::from_str::<u64>(&serde_json::to_string(
-                    &looked_up_rating["num_losses"],
-                )?)?)
-                .streak(serde_json::from_str::<i32>(&serde_json::to_string(
-                    &looked_up_rating["streak"],
-                )?)?)
-                .highest_mmr(serde_json::from_str::<u32>(
-                    &serde_json::to_string(&leaderboard["highest_rating"])?,
-                )?)
-                .build(),
-            (RecoveredRating::Recovered, leaderboard) => Rating::builder()
-                .mmr(serde_json::from_str::<u32>(&serde_json::to_string(
-                    &looked_up_rating["rating"],
-                )?)?)
-                .rank(serde_json::from_str::<u64>(&serde_json::to_string(&{
-                    if leaderboard["rank"] == JsonValue::Null {
-                        json![0]
-                    }
-                    else {
-                        leaderboard["rank"].clone()
-                    }
-                })?)?)
-                .wins(serde_json::from_str::<u64>(&serde_json::to_string(
-                    &looked_up_rating["num_wins"],
-                )?)?)
-                .losses(serde_json::from_str::<u64>(&serde_json::to_string(
-                    &looked_up_rating["num_losses"],
-                )?)?)
-                .streak(serde_json::from_str::<i32>(&serde_json::to_string(
-                    &looked_up_rating["streak"],
-                )?)?)
-                .highest_mmr(serde_json::from_str::<u32>(
-                    &serde_json::to_string(&{
-                        if leaderboard["highest_rating"] == JsonValue::Null {
-                            json![0]
-                        }
-                        else {
-                            leaderboard["highest_rating"].clone()
-                        };
-                    })?,
-                )?)
-                .build(),
-        };
-
-        Ok(player_rating)
-    }
-
-    /// Returns a`serde_json::Value`of the downloaded translation
-    /// strings from AoE2.net
-    ///
-    /// # Errors
-    /// Will throw an error if the translation has moved due to consuming a
-    /// value from the in-memory database. Errors there, due to threaded
-    /// behaviour, will result in `runtime` errors, no compile-time checks
-    /// possible.
-    pub fn get_translation_for_language(&mut self) -> Result<JsonValue> {
-        let mut translation: Option<JsonValue> = None;
-
-        trace!("Length of self.db.aoe2net_languages is: {:?}",
-               self.db.aoe2net_languages.len());
-
-        if self.db.aoe2net_languages.len() == 1 {
-            for (language, translation_value) in
-                self.db.aoe2net_languages.clone()
-            {
-                translation = Some(translation_value);
-                trace!("Translation that was used: {:?}", language);
-            }
-        }
-        else {
-            return Err(ResponderError::TranslationHasBeenMoved);
-        }
-        Ok(translation.expect("Translation should never be None value."))
-    }
-
-    /// Returns the corresponding `String` for our convenience
-    /// by searching through [`Aoe2netStringObj`]'s field `id`
-    ///
-    /// # Arguments
-    /// * `first` - A string slice that holds the element name to be used to
-    ///   lookup inside the `language` (e.g. `civ` or `game_type`).
-    /// * `id` - An integer holding the number that we get from `last_match` or
-    ///   `leaderboard` to translate and be looked up
-    ///
-    /// # Errors
-    /// This will error if the `translation data` cannot be found. Most likely
-    /// due to being moved/consumed. See also
-    /// [`Self::get_translation_for_language()`].
-    ///
-    /// # Panics
-    /// Panics if the conversion to a string failed
-    // TODO: Get rid of panic and handle gracefully
-    pub fn lookup_string_for_id(&self,
-                                first: &str,
-                                id: usize)
-                                -> Result<String> {
-        trace!("Getting translated string in {:?} with id: {:?}", first, id);
-        let language =
-            if let Ok(lang) = self.clone().get_translation_for_language() {
-                lang
-            }
-            else {
-                return Err(ResponderError::NotFound(
-                    "translation data".to_string(),
-                ));
-            };
-
-        let translated_vec = serde_json::from_str::<Vec<Aoe2netStringObj>>(
-            &serde_json::to_string(&language[first]).unwrap_or_else(|_| {
-                panic!(
-                    "Conversion of language [{:?}] to string failed.",
-                    first.to_string(),
-                )
-            }),
-        )
-        .expect("Conversion from translated string failed.");
-
-        let mut translated_string: Option<String> = None;
-
-        for obj_string in &translated_vec {
-            if *obj_string.id() == id {
-                translated_string = Some(obj_string.string().to_string())
-            }
-        }
-
-        if translated_string.is_none() {
-            return Err(ResponderError::TranslationPosError(
-                format!("[{:?}]", first.to_string(),),
-                id,
-            ));
-        }
-
-        Ok(translated_string.unwrap())
-    }
-
-    /// Returns the `map_type` id from a match
-    /// used only for translation purposes to get the id
-    /// for the translation lookup function
-    ///
-    /// # Errors
-    /// Will return an error if the `last_match` could not be found
-    pub fn get_id_for_map_type(&self,
-                               req_type: Aoe2netRequestType)
-                               -> Result<usize> {
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                self.aoe2net.player_last_match.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "last_match map type".to_string(),
-                        ))
-                    },
-                    |val| {
-                        Ok(val["last_match"]["map_type"]
-                            .to_string()
-                            .parse::<usize>()?)
-                    },
-                )
-            }
-            Aoe2netRequestType::MatchId => {
-                self.aoe2net.match_id.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "match_id map type".to_string(),
-                        ))
-                    },
-                    |val| Ok(val["map_type"].to_string().parse::<usize>()?),
-                )
-            }
-            _ => Err(ResponderError::InvalidReqType(req_type.to_string())),
-        }
-    }
-
-    /// Returns the `game_type` id from a match
-    /// used only for translation purposes to get the id
-    /// for the translation lookup function
-    ///
-    /// # Errors
-    /// Will return an error if the `last_match` could not be found
-    pub fn get_id_for_game_type(&self,
-                                req_type: Aoe2netRequestType)
-                                -> Result<usize> {
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                self.aoe2net.player_last_match.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "last_match game type".to_string(),
-                        ))
-                    },
-                    |val| {
-                        Ok(val["last_match"]["game_type"]
-                            .to_string()
-                            .parse::<usize>()?)
-                    },
-                )
-            }
-            Aoe2netRequestType::MatchId => {
-                self.aoe2net.match_id.as_ref().map_or_else(
-                    || {
-                        Err(ResponderError::NotFound(
-                            "match_id game type".to_string(),
-                        ))
-                    },
-                    |val| Ok(val["game_type"].to_string().parse::<usize>()?),
-                )
-            }
-            _ => Err(ResponderError::InvalidReqType(req_type.to_string())),
-        }
-    }
-
-    /// Returns the server location of the match while looking it up from
-    /// aoe2.net data and matching against [Server]
-    ///
-    /// # Errors
-    /// Will return an error if the `last_match` could not be found
-    pub fn get_server_location(&self,
-                               req_type: Aoe2netRequestType)
-                               -> Result<Server> {
-        let mut server = String::new();
-
-        match req_type {
-            Aoe2netRequestType::LastMatch => {
-                if let Some(val) = self.aoe2net.player_last_match.as_ref() {
-                    server = val["last_match"]["server"].to_string();
-                }
-            }
-            Aoe2netRequestType::MatchId => {
-                if let Some(val) = self.aoe2net.match_id.as_ref() {
-                    server = val["server"].to_string();
-                }
-            }
-            _ => {
-                return Err(ResponderError::InvalidReqType(
-                    req_type.to_string(),
-                ))
-            }
-        };
-
-        server = util::remove_escaping(server);
-
-        let server_result = match server.as_str() {
-            "australiasoutheast" => Server::Australia,
-            "brazilsouth" => Server::Brazil,
-            "ukwest" => Server::UK,
-            "westindia" => Server::India,
-            "southeastasia" => Server::SoutheastAsia,
-            "westeurope" => Server::WesternEurope,
-            "eastus" => Server::UsEast,
-            "koreacentral" => Server::Korea,
-            "westus2" => Server::UsWest,
-            _ => Server::NotFound,
-        };
-
-        Ok(server_result)
-    }
-
-    /// Looks up a player rating list in a [`dashmap::DashMap`] for
-    /// `player_id` and returns a `serde_json::Value` with the `rating
-    /// history` for that corresponding player
-    ///
-    /// # Arguments
-    /// * `profile_id` - A string slice that contains the Aoe2.net profile ID of
-    ///   a player
-    ///
-    /// # Errors
-    /// Doesn't throw any errors, but returns an Option type that is `None` if
-    /// the profile ID couldn't be found in the index
-    #[must_use]
-    pub fn lookup_player_rating_for_profile_id(&self,
-                                               profile_id: &str)
-                                               -> Option<JsonValue> {
-        self.aoe2net
-            .rating_history
-            .get(profile_id)
-            .map(|val| val.value().clone())
-    }
-
-    /// Looks up a leaderboard entry in a [`dashmap::DashMap`] for
-    /// `player_id` and returns a [`serde_json::Value`] with the `leaderboard`
-    /// for that corresponding player
-    ///
-    /// # Arguments
-    /// * `profile_id` - A string slice that contains the Aoe2.net profile ID of
-    ///   a player
-    ///
-    /// # Errors
-    /// Doesn't throw any errors, but returns an Option type that is `None` if
-    /// the profile ID couldn't be found in the index
-    #[must_use]
-    pub fn lookup_leaderboard_for_profile_id(&self,
-                                             profile_id: &str)
-                                             -> Option<JsonValue> {
-        self.aoe2net
-            .leaderboard
-            .get(profile_id)
-            .map(|val| val.value().clone())
-    }
+/// Outcome of a request of the aoe2net client
+type ClientResult<R, D> =
+    result::Result<Response<R, D>, ClientRequestError<reqwest::Error>>;
 
+impl MatchDataResponses {
     /// Print debug information of this data structure
     pub fn print_debug_information(&self) {
         debug!("DEBUG: {:#?}", self)
@@ -602,31 +79,29 @@ impl MatchDataResponses {
             .expect("Unable to write data");
     }
 
-    /// Creates a new [`MatchDataResponses`] struct by executing requests for
-    /// match data and putting them into a [`dashmap::DashMap`]
+    /// Creates a new [`MatchDataResponses`] struct by requesting the match,
+    /// the ratings of its players and the translations from a `provider`
     ///
     /// # Arguments
     /// * `par` - holds a [`MatchInfoRequest`] that contains all the request
     ///   parameters to our backend
-    /// * `client` - holds a clone of a [`reqwest::Client`] for connection
-    ///   pooling purposes
+    /// * `provider` - the [`MatchDataProvider`] to request the data from
     /// * `in_memory_db` - holds [`InMemoryDb`] wrapped inside an [`Arc`] with a
     ///   [`Mutex`] due to threading
     ///
     /// # Errors
-    /// This function may throw errors in the form of [`reqwest::Error`] when
-    /// requests fail
+    /// This function will return the errors of the `provider` if the match or
+    /// a rating couldn't be requested
     ///
     /// # Panics
     /// Function could panic if the [`dashmap::DashMap`] of static
     /// global variable [`static@crate::STANDARD`] delivers `None`
-    #[allow(clippy::too_many_lines)]
-    pub async fn with_match_data(par: MatchInfoRequest,
-                                 client: A2NClient<'static, reqwest::Client>,
-                                 in_memory_db: Arc<Mutex<InMemoryDb>>,
-                                 export_path: Option<PathBuf>,
-                                 _root: Url)
-                                 -> Result<MatchDataResponses> {
+    pub async fn with_match_data<P>(par: MatchInfoRequest,
+                                    provider: &P,
+                                    in_memory_db: Arc<Mutex<InMemoryDb>>)
+                                    -> Result<MatchDataResponses>
+        where P: MatchDataProvider + ?Sized,
+    {
         let language: String = par.language.map_or_else(
             || (*STANDARD.get(&"language").unwrap()).to_string(),
             |language| language,
@@ -637,230 +112,115 @@ impl MatchDataResponses {
                .map_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string(),
                             |game| game);
 
-        #[allow(unused_assignments)]
-        let mut db_cloned = InMemoryDb::default();
-        {
-            // Just clone and drop the lock
-            db_cloned = in_memory_db.lock().await.clone();
-        }
+        let ref_data = in_memory_db.lock().await.github_file_content.clone();
 
-        // Include github response
-        let mut responses = MatchDataResponses {
-            db: db_cloned.retain_only_requested_language(&language),
-            ..MatchDataResponses::default()
+        let (requested_profile_id, match_data) = match par.id_type {
+            IdType::SteamId | IdType::ProfileId => {
+                let last_match =
+                    provider.last_match(&game, par.id_type, &par.id_number)
+                            .await?;
+                (last_match.profile_id, last_match.last_match)
+            },
+            IdType::Name => {
+                // Names are resolved to the `profile_id` of the player first
+                let profile_id =
+                    provider.profile_id_for_name(&game,
+                                                 &par.id_number,
+                                                 &ref_data)
+                            .await?;
+                let last_match = provider.last_match(&game,
+                                                     IdType::ProfileId,
+                                                     &profile_id)
+                                         .await?;
+                (last_match.profile_id, last_match.last_match)
+            },
+            IdType::MatchId | IdType::MatchUuid => {
+                (None,
+                 provider.match_by_id(&game, par.id_type, &par.id_number)
+                         .await?)
+            },
         };
 
-        match par.id_type.as_str() {
-            "steam_id" => {
-                let req = GetLastMatchRequest::builder()
-                    .game(game.as_str())
-                    .steam_id(par.id_number.as_str())
-                    .build();
-
-                responses.aoe2net.player_last_match =
-                    Some(client.req_get(req).await.unwrap().data.unwrap());
-                // Get `leaderboard_id` for future requests
-                responses.aoe2net.leaderboard_id =
-                    Some(responses.get_leaderboard_id_from_request(
-                        Aoe2netRequestType::LastMatch,
-                    )?);
-
-                // Get all players from `LastMatch` response
-                responses.aoe2net.players_temp = responses
-                    .parse_players_into::<Vec<aoe2net_Player>>(
-                        Aoe2netRequestType::LastMatch,
-                    )?;
-            }
-            "profile_id" => {
-                let req = GetLastMatchRequest::builder()
-                    .game(game.as_str())
-                    .profile_id(par.id_number.as_str())
-                    .build();
-
-                responses.aoe2net.player_last_match =
-                    Some(client.req_get(req).await.unwrap().data.unwrap());
-                // Get `leaderboard_id` for future requests
-                responses.aoe2net.leaderboard_id =
-                    Some(responses.get_leaderboard_id_from_request(
-                        Aoe2netRequestType::LastMatch,
-                    )?);
+        let leaderboard_id = match_data.leaderboard_id.ok_or_else(|| {
+                                 ResponderError::NotFound(
+                                     "leaderboard ID of the match".to_string(),
+                                 )
+                             })?;
 
-                // Get all players from `LastMatch` response
-                responses.aoe2net.players_temp = responses
-                    .parse_players_into::<Vec<aoe2net_Player>>(
-                        Aoe2netRequestType::LastMatch,
-                    )?;
-            }
-            "match_id" => {
-                let req = GetMatchRequest::builder()
-                    .game(game.as_str())
-                    .match_id(par.id_number.as_str())
-                    .build();
-
-                responses.aoe2net.match_id =
-                    Some(client.req_get(req).await.unwrap().data.unwrap());
-
-                // Get `leaderboard_id` for future requests
-                responses.aoe2net.leaderboard_id =
-                    Some(responses.get_leaderboard_id_from_request(
-                        Aoe2netRequestType::MatchId,
-                    )?);
-
-                // Get all players from `LastMatch` response
-                responses.aoe2net.players_temp = responses
-                    .parse_players_into::<Vec<aoe2net_Player>>(
-                        Aoe2netRequestType::MatchId,
-                    )?;
-            }
-            "uuid" => {
-                let req = GetMatchRequest::builder()
-                    .game(game.as_str())
-                    .uuid(uuid::Uuid::parse_str(par.id_number.as_str())?)
-                    .build();
+        let mut ratings = HashMap::with_capacity(match_data.players.len());
 
-                responses.aoe2net.match_id =
-                    Some(client.req_get(req).await.unwrap().data.unwrap());
-                // Get `leaderboard_id` for future requests
-                responses.aoe2net.leaderboard_id =
-                    Some(responses.get_leaderboard_id_from_request(
-                        Aoe2netRequestType::MatchId,
-                    )?);
-
-                // Get all players from `LastMatch` response
-                responses.aoe2net.players_temp = responses
-                    .parse_players_into::<Vec<aoe2net_Player>>(
-                        Aoe2netRequestType::MatchId,
-                    )?;
-            }
-            _ => {
-                return Err(ResponderError::InvalidIdType(
-                    std::borrow::Cow::Owned(par.id_type),
-                ))
+        for player in &match_data.players {
+            if let Some(rating) =
+                provider.ratings(&game, leaderboard_id, &player.profile_id)
+                        .await?
+            {
+                ratings.insert(player.profile_id.clone(), rating);
             }
         }
 
-        if let Some(mut path) = export_path.clone() {
-            path.push("aoe2net");
-            util::export_to_json(&File { name: "last_match".to_string(),
-                                         ext: FileFormat::Json },
-                                 &path,
-                                 &responses.clone()
-                                           .aoe2net
-                                           .player_last_match
-                                           .map_or(JsonValue::Null, |x| x))
-        };
-
-        // TODO: This error handling still needed?
-        // match match_data_response {
-        //     Err(err) => match err {
-        //         ApiRequestError::NotFoundResponse {
-        //             root: _,
-        //             endpoint: _,
-        //             query: _,
-        //         } => return Err(ResponderError::LastMatchNotFound),
-        //         _ => {
-        //             return Err(ResponderError::OtherApiRequestError(
-        //                 err,
-        //             ))
-        //         }
-        //     },
-
-        let leaderboard_id = responses.aoe2net
-                                      .leaderboard_id
-                                      .clone()
-                                      .expect("Leaderboard ID must be set.");
-
-        for player in &responses.aoe2net.players_temp {
-            let profile_id = player.profile_id.to_string();
-
-            // Get `RatingHistory` data for each player
-            let req_rating = GetRatingHistoryRequest::builder()
-                .game(game.as_str())
-                .profile_id(profile_id.as_str())
-                .leaderboard_id(leaderboard_id.parse::<i32>()?)
-                .build();
-
-            // GET `Leaderboard` data
-            let req_lead = GetLeaderboardRequest::builder()
-                .game(game.as_str())
-                .profile_id(profile_id.as_str())
-                .leaderboard_id(leaderboard_id.parse::<i32>()?)
-                .build();
-
-            let rating_response: JsonValue =
-                client.req_get(req_rating).await.unwrap().data.unwrap();
-            let leaderboard_response: JsonValue =
-                client.req_get(req_lead).await.unwrap().data.unwrap();
-
-            // GET `Rating` data as recovery data
-            let leaderboard_recovery: Option<JsonValue> =
-                if leaderboard_response["count"] == 0 {
-                    let req_lead_rating = GetRatingRequest::builder()
-                        .game(game.as_str())
-                        .profile_id(profile_id.as_str())
-                        .leaderboard_id(leaderboard_id.parse::<i32>()?)
-                        .build();
-
-                    Some(client.req_get(req_lead_rating)
-                               .await
-                               .unwrap()
-                               .data
-                               .unwrap())
-                }
-                else {
-                    None
-                };
-
-            if let Some(mut path) = export_path.clone() {
-                // TODO: Do requests just one time in export path
-                path.push("aoe2net");
-
-                if leaderboard_recovery.is_some() {
-                    util::export_to_json(&File { name: profile_id.clone(),
-                                                 ext: FileFormat::Json },
-                                         &{
-                                             let mut p = path.clone();
-                                             p.push("leaderboard");
-                                             p
-                                         },
-                                         &rating_response);
-                }
-
-                util::export_to_json(&File { name: profile_id.clone(),
-                                             ext: FileFormat::Json },
-                                     &{
-                                         let mut p = path.clone();
-                                         p.push("rating_history");
-                                         p
-                                     },
-                                     &rating_response);
-                util::export_to_json(&File { name: profile_id.clone(),
-                                             ext: FileFormat::Json },
-                                     &{
-                                         let mut p = path.clone();
-                                         p.push("leaderboard");
-                                         p
-                                     },
-                                     &leaderboard_response);
-            }
-
-            responses.aoe2net
-                     .rating_history
-                     .insert(player.profile_id.to_string(), rating_response);
+        // Missing translations are reported for each ID while processing
+        let translations =
+            provider.translations(&game, &language)
+                    .await
+                    .unwrap_or_else(|err| {
+                        warn!("Translations are not available: {}", err);
+                        Translations::default()
+                    });
+
+        Ok(MatchDataResponses { game,
+                                match_data,
+                                requested_profile_id,
+                                ratings,
+                                translations,
+                                ref_data })
+    }
+}
 
-            responses.aoe2net
-                     .leaderboard
-                     .insert(player.profile_id.to_string(),
-                             leaderboard_response);
+/// Returns the data of an aoe2net response or the error that happened while
+/// requesting it
+///
+/// # Errors
+/// Will return an error if the request failed or the response was empty
+pub(crate) fn response_data<R>(response: ClientResult<R, Option<JsonValue>>)
+                               -> Result<JsonValue>
+    where R: Request<Response = Option<JsonValue>>,
+{
+    response.map_err(ApiRequestError::from)?
+            .data
+            .ok_or_else(|| {
+                ResponderError::NotFound("aoe2net response data".to_string())
+            })
+}
 
-            if leaderboard_recovery.is_some() {
-                responses.aoe2net
-                         .leaderboard
-                         .insert(format!("{}_recovery", profile_id.as_str()),
-                                 leaderboard_recovery.unwrap());
-            }
-        }
+/// Returns the data of a typed aoe2net response or the error that happened
+/// while requesting it
+///
+/// # Errors
+/// Will return an error if the request failed or the response couldn't be
+/// parsed
+pub(crate) fn typed_response_data<R, D>(response: ClientResult<R, D>)
+                                        -> Result<D>
+    where R: Request<Response = D>,
+          D: DeserializeOwned + PartialEq,
+{
+    Ok(response.map_err(ApiRequestError::from)?.data)
+}
 
-        Ok(responses)
-    }
+/// Like [`response_data`], but an upstream `404 Not Found` or missing data
+/// mean that the requested player or match is unknown to aoe2.net
+pub(crate) fn last_match_data<R>(response: ClientResult<R, Option<JsonValue>>)
+                                 -> Result<JsonValue>
+    where R: Request<Response = Option<JsonValue>>,
+{
+    response_data(response).map_err(|err| {
+                               match err {
+                                   ResponderError::NotFound(_) => {
+                                       ResponderError::LastMatchNotFound
+                                   },
+                                   err if err.is_upstream_not_found() => {
+                                       ResponderError::LastMatchNotFound
+                                   },
+                                   err => err,
+                               }
+                           })
 }
@@ -0,0 +1,306 @@
+//! Translating the match history of a player and aggregating it for the
+//! `player/{profile_id}/matches` endpoint
+
+use std::{
+    result,
+    sync::Arc,
+};
+
+use aoe2net::{
+    endpoints::match_history::GetMatchHistoryRequest,
+    types::api::Match,
+};
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::typed_response_data,
+        types::{
+            api::{
+                MatchHistoryEntry,
+                MatchHistoryRequest,
+                MatchHistoryResponse,
+                MatchOpponent,
+                OpponentStats,
+                PerformanceStats,
+            },
+            error::ResponderError,
+            InMemoryDb,
+        },
+        util,
+    },
+    STANDARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Kind of leaderboard the match history is requested for, aoe2.net returns
+/// the matches of all leaderboards anyway
+const MATCH_HISTORY_LEADERBOARD: &str = "rm_1v1";
+
+/// Entry point for processing part of `player/{profile_id}/matches` endpoint
+///
+/// # Errors
+/// Returns an error if the match history couldn't be requested
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn build_match_history(par: MatchHistoryRequest,
+                                 client: A2NClient<'static, reqwest::Client>,
+                                 root: Url,
+                                 in_memory_db: Arc<Mutex<InMemoryDb>>)
+                                 -> Result<MatchHistoryResponse> {
+    let client = client.with_root(root);
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    let language = par.language.clone().unwrap_or_else(|| {
+                                           (*STANDARD.get(&"language")
+                                                     .unwrap())
+                                           .to_string()
+                                       });
+
+    let matches = request_match_history(&client,
+                                        &game,
+                                        &par.profile_id,
+                                        par.count).await?;
+
+    let translation = in_memory_db.lock()
+                                  .await
+                                  .language_strings(&game, &language)
+                                  .unwrap_or(JsonValue::Null);
+
+    let matches =
+        match_history_entries(&par.profile_id, &matches, &translation);
+
+    let opponent_id = par.opponent_id.clone().or_else(|| {
+                                                 current_opponent_id(&matches)
+                                             });
+
+    Ok(MatchHistoryResponse { profile_id: par.profile_id,
+                              per_civ: performance_by(&matches, |entry| {
+                                  entry.civ.clone()
+                              }),
+                              per_map: performance_by(&matches, |entry| {
+                                  entry.map.clone()
+                              }),
+                              versus_opponent: opponent_id.map(|opponent_id| {
+                                  versus_opponent(&matches, &opponent_id)
+                              }),
+                              matches })
+}
+
+/// Request the `count` most recent matches of a player from aoe2.net
+///
+/// # Errors
+/// Returns an error if the request failed
+pub(crate) async fn request_match_history(client: &A2NClient<'static,
+                                                      reqwest::Client>,
+                                          game: &str,
+                                          profile_id: &str,
+                                          count: i32)
+                                          -> Result<Vec<Match>> {
+    let leaderboard_id = util::leaderboard_id(game, MATCH_HISTORY_LEADERBOARD)
+        .ok_or_else(|| {
+            ResponderError::NotFound(format!("leaderboard of {game}"))
+        })?;
+
+    // `start` 0 is the most recent match
+    let req = GetMatchHistoryRequest::builder()
+        .game(game)
+        .leaderboard_id(leaderboard_id)
+        .profile_id(profile_id)
+        .start(0)
+        .count(count)
+        .build();
+
+    typed_response_data(client.req_get(req).await)
+}
+
+/// Convert the `matches` of aoe2.net into [`MatchHistoryEntry`]s from the
+/// perspective of the player with `profile_id`, civilisations and maps are
+/// translated with the `translation` of aoe2.net
+///
+/// Matches the player didn't take part in are skipped.
+pub(crate) fn match_history_entries(profile_id: &str,
+                                    matches: &[Match],
+                                    translation: &JsonValue)
+                                    -> Vec<MatchHistoryEntry> {
+    matches.iter()
+           .filter_map(|match_data| {
+               match_history_entry(profile_id, match_data, translation)
+           })
+           .collect()
+}
+
+/// Convert a single match of aoe2.net into a [`MatchHistoryEntry`], `None`
+/// if the player with `profile_id` didn't take part in it
+fn match_history_entry(profile_id: &str,
+                       match_data: &Match,
+                       translation: &JsonValue)
+                       -> Option<MatchHistoryEntry> {
+    let profile_id = profile_id.parse::<i64>().ok()?;
+
+    let player =
+        match_data.players
+                  .iter()
+                  .find(|player| player.profile_id == Some(profile_id))?;
+
+    let opponents = match_data.players
+                              .iter()
+                              .filter(|other| {
+                                  other.profile_id != player.profile_id &&
+                                  (player.team.is_none() ||
+                                   other.team != player.team)
+                              })
+                              .map(|opponent| MatchOpponent {
+                                  profile_id: opponent.profile_id
+                                                      .map(|id| id.to_string())
+                                                      .unwrap_or_default(),
+                                  name: opponent.name
+                                                .clone()
+                                                .unwrap_or_default(),
+                                  civ: util::translate_id(translation,
+                                                          "civ",
+                                                          opponent.civ),
+                              })
+                              .collect();
+
+    let started = match_data.started
+                            .map_or_else(String::new, util::iso_timestamp);
+
+    Some(MatchHistoryEntry {
+        match_id: match_data.match_id.clone().unwrap_or_default(),
+        started,
+        map: util::translate_id(translation, "map_type", match_data.map_type),
+        civ: util::translate_id(translation, "civ", player.civ),
+        won: player.won,
+        rating: player.rating,
+        rating_change: player.rating_change,
+        opponents,
+    })
+}
+
+/// The only opponent of the most recent match, if it was a 1v1
+fn current_opponent_id(matches: &[MatchHistoryEntry]) -> Option<String> {
+    matches.first()
+           .filter(|entry| entry.opponents.len() == 1)
+           .map(|entry| entry.opponents[0].profile_id.clone())
+}
+
+/// Aggregate the performance in `matches` grouped by the name returned by
+/// `key`, most played first
+pub(crate) fn performance_by<F>(matches: &[MatchHistoryEntry],
+                                key: F)
+                                -> Vec<PerformanceStats>
+    where F: Fn(&MatchHistoryEntry) -> String,
+{
+    let mut performances: Vec<PerformanceStats> = Vec::new();
+
+    for entry in matches {
+        let name = key(entry);
+
+        if let Some(stats) =
+            performances.iter_mut().find(|stats| stats.name == name)
+        {
+            stats.add_result(entry.won);
+        }
+        else {
+            let mut stats = PerformanceStats::new(name);
+            stats.add_result(entry.won);
+            performances.push(stats);
+        }
+    }
+
+    performances.sort_by(|left, right| {
+                    right.games
+                         .cmp(&left.games)
+                         .then_with(|| left.name.cmp(&right.name))
+                });
+
+    performances
+}
+
+/// Record in `matches` against the opponent with `opponent_id`
+pub(crate) fn versus_opponent(matches: &[MatchHistoryEntry],
+                              opponent_id: &str)
+                              -> OpponentStats {
+    let mut stats = PerformanceStats::new(opponent_id);
+
+    for entry in matches {
+        if let Some(opponent) = entry.opponents
+                                     .iter()
+                                     .find(|opponent| {
+                                         opponent.profile_id == opponent_id
+                                     })
+        {
+            // The most recent name of the opponent
+            if stats.games == 0 && !opponent.name.is_empty() {
+                stats.name.clone_from(&opponent.name);
+            }
+
+            stats.add_result(entry.won);
+        }
+    }
+
+    OpponentStats { profile_id: opponent_id.to_string(),
+                    stats }
+}
+
+#[test]
+fn match_history_is_aggregated_per_civ_map_and_opponent() {
+    let translation = serde_json::json!({
+        "civ": [{"id": 24, "string": "Mayans"},
+                {"id": 29, "string": "Vikings"}],
+        "map_type": [{"id": 9, "string": "Arabia"},
+                     {"id": 29, "string": "Arena"}]
+    });
+
+    let match_data = |map_type: u64, civ: u64, won: bool, opponent: u64| {
+        serde_json::from_value::<Match>(serde_json::json!({
+            "match_id": "1",
+            "map_type": map_type,
+            "started": 1_614_000_000,
+            "players": [
+                {"profile_id": 196240, "name": "GL.TheViper", "civ": civ,
+                 "team": 1, "won": won, "rating": 2500, "rating_change": 10},
+                {"profile_id": opponent, "name": "Hera", "civ": 24,
+                 "team": 2, "won": !won}
+            ]
+        }))
+        .unwrap()
+    };
+
+    let matches = match_history_entries("196240",
+                                        &[match_data(9, 29, true, 199325),
+                                          match_data(9, 24, false, 199325),
+                                          match_data(29, 29, true, 1),
+                                          match_data(9, 33, true, 199325)],
+                                        &translation);
+
+    assert_eq!(matches[0].map, "Arabia");
+    assert_eq!(matches[0].opponents[0].civ, "Mayans");
+    assert_eq!(matches[3].civ, "33");
+    assert_eq!(matches[0].started, "2021-02-22T13:20:00Z");
+
+    let per_map = performance_by(&matches, |entry| entry.map.clone());
+    assert_eq!((per_map[0].name.as_str(), per_map[0].games, per_map[0].wins),
+               ("Arabia", 3, 2));
+
+    let per_civ = performance_by(&matches, |entry| entry.civ.clone());
+    assert_eq!((per_civ[0].name.as_str(), per_civ[0].wins), ("Vikings", 2));
+
+    let opponent_id = current_opponent_id(&matches).unwrap();
+    let versus = versus_opponent(&matches, &opponent_id);
+    assert_eq!((versus.stats.name.as_str(),
+                versus.stats.wins,
+                versus.stats.losses),
+               ("Hera", 2, 1));
+}
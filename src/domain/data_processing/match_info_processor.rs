@@ -2,18 +2,16 @@
 //! Beware, there is a close connection to the [`MatchDataResponses`]
 //! in many places
 
-use std::result;
-
-use aoe2net::types::{
-    api::Player as aoe2net_Player,
-    helper::{
-        Aoe2netRequestType,
-        RecoveredRating,
-    },
+use std::{
+    convert::TryFrom,
+    result,
 };
+
 use serde::Serialize;
-use serde_json::Value as JsonValue;
-use tracing::trace;
+use tracing::{
+    trace,
+    warn,
+};
 
 use crate::domain::{
     data_processing::MatchDataResponses,
@@ -25,21 +23,46 @@ use crate::domain::{
             MatchSize,
             MatchStatus,
             PlayerRaw,
+            PlayerSort,
             Players,
             Rating,
+            Server,
             TeamRaw,
+            TeamSort,
             Teams,
         },
-        error::ProcessingError,
+        error::{
+            ErrorMessageToFrontend,
+            ProcessingError,
+            ResponderError,
+        },
+        match_data::{
+            MatchPlayerData,
+            PlayerRating,
+        },
     },
-    util,
 };
 
-// Error handling
-type ProcessingErrorStrings = Vec<String>;
-
 type Result<T> = result::Result<T, ProcessingError>;
 
+impl From<&PlayerRating> for Rating {
+    /// Players that are not ranked on the leaderboard get a rank and highest
+    /// rating of `0`
+    fn from(player_rating: &PlayerRating) -> Self {
+        let standing = player_rating.standing.as_ref();
+
+        Rating::builder().mmr(player_rating.rating)
+                         .rank(standing.map_or(0, |standing| standing.rank))
+                         .wins(player_rating.wins)
+                         .losses(player_rating.losses)
+                         .streak(player_rating.streak)
+                         .highest_mmr(standing.map_or(0, |standing| {
+                                                  standing.highest_rating
+                                              }))
+                         .build()
+    }
+}
+
 impl Rating {
     /// Calculate the win rate for the Rating data structure and modifies
     /// `win_rate` in-place
@@ -65,7 +88,14 @@ pub struct MatchInfoProcessor {
     players: Option<Players>,
     teams: Option<Teams>,
     result: Option<MatchInfoResult>,
-    errors: Option<ProcessingErrorStrings>,
+    /// `SoftFail`s collected during processing, they don't stop the
+    /// processing but are handed to the frontend within the
+    /// [`MatchInfoResult`]
+    errors: Vec<ErrorMessageToFrontend>,
+    /// Order of the players inside a team
+    player_sort: Option<PlayerSort>,
+    /// Order of the teams
+    team_sort: Option<TeamSort>,
 }
 
 impl MatchInfoProcessor {
@@ -81,27 +111,42 @@ impl MatchInfoProcessor {
                players: None,
                teams: None,
                result: None,
-               errors: None }
+               errors: Vec::new(),
+               player_sort: None,
+               team_sort: None }
+    }
+
+    /// Set the order of the players inside a team and of the teams
+    ///
+    /// # Arguments
+    /// * `player_sort` - the [`PlayerSort`] for the players inside a team,
+    ///   `None` keeps the players in the order of aoe2.net
+    /// * `team_sort` - the [`TeamSort`] for the teams, `None` orders them
+    ///   descending by team number
+    #[must_use]
+    pub fn with_sorting(mut self,
+                        player_sort: Option<PlayerSort>,
+                        team_sort: Option<TeamSort>)
+                        -> Self {
+        self.player_sort = player_sort;
+        self.team_sort = team_sort;
+        self
     }
 
     /// Process all given information and set up this datastructure to be
     /// finally assembled to a [`MatchInfoResult`]. Modifies its data in-place
     ///
+    /// Non-fatal errors are collected as `SoftFail`s and end up in the
+    /// `error_message` of the [`MatchInfoResult`]
+    ///
     /// # Errors
-    // TODO: Many errors can come up here, that need to be collected and named
+    /// Errors that make it impossible to assemble a [`MatchInfo`] are bubbled
+    /// up
     #[tracing::instrument(name = "Processing MatchDataResponses", skip(self))]
     pub fn process(&mut self) -> Result<Self> {
-        // TODO: Collect errors in &self.errors or alike
         trace!("Processing MatchDataResponses ...");
 
-        let req_type = if self.responses.aoe2net.match_id.is_some() {
-            Aoe2netRequestType::MatchId
-        }
-        else {
-            Aoe2netRequestType::LastMatch
-        };
-
-        let players_vec = &self.responses.aoe2net.players_temp.clone();
+        let players_vec = &self.responses.match_data.players.clone();
 
         let mut players_raw = Vec::with_capacity(players_vec.len() as usize);
         let mut teams_raw: Vec<TeamRaw> = Vec::new();
@@ -119,7 +164,11 @@ impl MatchInfoProcessor {
         trace!("Creating different teams vectors.");
         // Create the different teams vectors
         let amount_of_successfully_processed_teams =
-            assemble_teams(diff_team, &players_raw, &mut teams_raw);
+            assemble_teams(diff_team,
+                           &players_raw,
+                           self.player_sort,
+                           self.team_sort,
+                           &mut teams_raw);
         trace!("Successfully created different teams vectors.");
 
         trace!("Calculating match size ...");
@@ -138,37 +187,32 @@ impl MatchInfoProcessor {
 
         trace!("Translate rating type ...");
         let translated_last_match_rating_type =
-            &self.responses.lookup_string_for_id(
-                "rating_type",
-                self.responses.get_id_for_rating_type(req_type)?,
-            )?;
+            &self.translate("rating_type",
+                            required(self.responses.match_data.rating_type,
+                                     "rating type of the match")?);
         trace!("Successfully translated rating type.");
 
         trace!("Translate map type ...");
         let translated_last_match_map_type =
-            &self.responses
-                 .lookup_string_for_id("map_type",
-                                       self.responses
-                                           .get_id_for_map_type(req_type)?)?;
+            &self.translate("map_type",
+                            required(self.responses.match_data.map_type,
+                                     "map type of the match")?);
         trace!("Successfully translated map type.");
 
         trace!("Translate into game type from match type...");
         let translated_last_match_match_type =
-            &self.responses
-                 .lookup_string_for_id("game_type",
-                                       self.responses
-                                           .get_id_for_game_type(req_type)?)?;
+            &self.translate("game_type",
+                            required(self.responses.match_data.game_type,
+                                     "game type of the match")?);
         trace!("Successfully translated game type.");
 
         trace!("Getting match status ...");
-        let match_status = if let Ok(time) =
-            &self.responses.get_finished_time(req_type)?.parse::<usize>()
-        {
-            MatchStatus::Finished(*time)
-        }
-        else {
-            MatchStatus::Running
-        };
+        let match_status = self.responses
+                               .match_data
+                               .finished
+                               .and_then(|time| usize::try_from(time).ok())
+                               .map_or(MatchStatus::Running,
+                                       MatchStatus::Finished);
         trace!("Sucessfully got match status.");
 
         trace!("Assembling information to MatchInfo and MatchInfoResult ...");
@@ -178,14 +222,19 @@ impl MatchInfoProcessor {
             .game_type(translated_last_match_match_type.to_string())
             .rating_type(translated_last_match_rating_type.to_string())
             .map_name(translated_last_match_map_type.to_string())
-            .server(self.responses.get_server_location(req_type)?)
+            .server(self.responses
+                        .match_data
+                        .server
+                        .as_deref()
+                        .map_or(Server::NotFound, Server::from))
             .teams(Teams(teams_raw.clone()))
             .match_status(match_status)
             .build();
 
-        // Wrap MatchInfo with converted Errors into MatchInfoResult
+        // Wrap MatchInfo with collected Errors into MatchInfoResult
         let match_info_result =
             MatchInfoResult::builder().match_info(match_info_raw.clone())
+                                      .error_message(self.errors.clone())
                                       .build();
         trace!("Successfully assembled information to MatchInfo and MatchInfoResult.");
 
@@ -194,13 +243,41 @@ impl MatchInfoProcessor {
                   players: Some(Players(players_raw)),
                   teams: Some(Teams(teams_raw)),
                   result: Some(match_info_result),
-                  errors: None })
+                  errors: self.errors.clone(),
+                  player_sort: self.player_sort,
+                  team_sort: self.team_sort })
+    }
+
+    /// Collect a `SoftFail` that doesn't stop the processing
+    fn soft_fail(&mut self,
+                 err: &ProcessingError) {
+        warn!("Recovered from {:?}", err);
+        self.errors.push(ErrorMessageToFrontend::soft_fail(err.code(),
+                                                           err.to_string()));
     }
 
-    /// Process all the players given in a `Last_Match` response
+    /// Translate an `id` of the translation category `first`, falls back to
+    /// the untranslated `id` and collects a `SoftFail` if there is no
+    /// translation for it
+    fn translate(&mut self,
+                 first: &str,
+                 id: usize)
+                 -> String {
+        if let Some(translated) = self.responses.translations.lookup(first, id)
+        {
+            translated.to_string()
+        }
+        else {
+            self.soft_fail(&ProcessingError::TranslationError(first.to_string(),
+                                                              id));
+            id.to_string()
+        }
+    }
+
+    /// Process all the players of the match
     ///
     /// # Arguments
-    /// * `players_vec` - a slice of a vector of [`aoe2net_Player`]s that holds
+    /// * `players_vec` - a slice of a vector of [`MatchPlayerData`] that holds
     ///   all the players that are in that corresponding game
     /// * `players_raw` - a mutable reference to a vector of raw Players to push
     ///   each processed [`PlayerRaw`] to
@@ -211,7 +288,7 @@ impl MatchInfoProcessor {
     /// Errors are bubbled up into the processing stage of
     /// [`MatchInfoProcessor`]
     fn process_all_players(&mut self,
-                           players_vec: &[aoe2net_Player],
+                           players_vec: &[MatchPlayerData],
                            players_raw: &mut Vec<PlayerRaw>,
                            diff_team: &mut Vec<i64>)
                            -> Result<usize> {
@@ -232,8 +309,8 @@ impl MatchInfoProcessor {
     /// player. Afterwards pushes it into a vector of [`PlayerRaw`].
     ///
     /// # Arguments
-    /// * `req_player` - holding a reference to [`aoe2net_Player`] that contains
-    ///   all information we got from the `last_match` response
+    /// * `req_player` - holding a reference to [`MatchPlayerData`] that
+    ///   contains all information we got about the player from the match
     /// * `players_processing` - a mutable reference to a vector of
     ///   [`PlayerRaw`] containing the succesfully built players with all
     ///   information belonging to them
@@ -242,59 +319,74 @@ impl MatchInfoProcessor {
     /// Errors are bubbled up into the processing stage of
     /// [`MatchInfoProcessor`]
     fn assemble_player_to_vec(&mut self,
-                              req_player: &aoe2net_Player,
+                              req_player: &MatchPlayerData,
                               players_processing: &mut Vec<PlayerRaw>)
                               -> Result<()> {
         // Lookups
         trace!("Looking up alias ...");
         let looked_up_alias = self.lookup_alias(req_player);
+        if looked_up_alias.is_none() {
+            self.soft_fail(&ProcessingError::AliasNotFound(
+                req_player.profile_id.parse::<u64>()?,
+            ));
+        }
         trace!("Successfully looked up alias.");
 
+        trace!("Looking up team ...");
+        let looked_up_team = self.lookup_team(looked_up_alias.as_ref());
+        trace!("Successfully looked up team: {:?}", looked_up_team);
+
         trace!("Looking up rating ...");
-        let looked_up_rating = self.lookup_rating(req_player)?;
+        let looked_up_rating = self.lookup_rating(req_player)
+                                   .map_err(|err| self.soft_fail(&err))
+                                   .ok();
         trace!("Successfully looked up rating: {:#?}", looked_up_rating);
 
-        trace!("Looking up leaderboard ...");
-        // TODO: Recover from leaderboard array being empty
-        let looked_up_leaderboard = self.lookup_leaderboard(req_player)?;
-
         trace!("Getting requested player ...");
         let requested_player_boolean = self.get_requested_player(req_player);
         trace!("Successfully got requested player: {:#?}",
                requested_player_boolean);
 
         trace!("Getting player's rating ...");
-        let mut player_rating =
-            MatchDataResponses::create_rating(&looked_up_rating,
-                                              &looked_up_leaderboard)?;
+        // Without a rating we can only show the player without any rating
+        // information
+        let player_rating = if let Some(looked_up_rating) = &looked_up_rating
+        {
+            let mut player_rating = Rating::from(looked_up_rating);
+
+            trace!("Calculating player win rate ...");
+            player_rating.calculate_win_rate();
+            trace!("Successfully calculated player win rate.");
+
+            player_rating
+        }
+        else {
+            Rating::default()
+        };
         trace!("Successfully got requested player's rating: {:?}",
                player_rating);
 
         trace!("Getting player country ...");
         let player_country =
-            MatchDataResponses::get_country_code(&looked_up_leaderboard);
+            looked_up_rating.and_then(|rating| rating.standing)
+                            .and_then(|standing| standing.country);
         trace!("Successfully got requested player's country: {:?}",
                player_country);
 
         trace!("Getting player civilisation translation ...");
         let translated_civilisation_string =
-            &self.responses
-                 .lookup_string_for_id("civ",
-                                       req_player.civ
-                                                 .to_string()
-                                                 .parse::<usize>()?)?;
+            &self.translate("civ",
+                            req_player.civ
+                                      .ok_or(ProcessingError::CivilisationError)?);
         trace!("Successfully translated player civilisation.");
 
-        trace!("Calculating player win rate ...");
-        player_rating.calculate_win_rate();
-        trace!("Successfully calculated player win rate.");
-
         trace!("Building player struct ...");
         let player_built =
             build_player(player_rating,
                          player_country,
                          req_player,
                          &looked_up_alias,
+                         &looked_up_team,
                          translated_civilisation_string.to_string(),
                          requested_player_boolean)?;
         trace!("Successfully built player struct.");
@@ -308,97 +400,37 @@ impl MatchInfoProcessor {
     /// was made for on our `matchinfo` endpoint and returns this as Boolean
     ///
     /// # Arguments
-    /// * `req_player` - holding a reference to [`aoe2net_Player`] that contains
-    ///   all information we got from the `last_match` response
+    /// * `req_player` - holding a reference to [`MatchPlayerData`] that
+    ///   contains all information we got about the player from the match
     fn get_requested_player(&self,
-                            req_player: &aoe2net_Player)
+                            req_player: &MatchPlayerData)
                             -> bool {
-        self.responses
-            .aoe2net
-            .player_last_match
-            .as_ref()
-            .map_or(false, |player_last_match| {
-                util::remove_escaping(
-                    player_last_match["profile_id"].to_string(),
-                ) == util::remove_escaping(req_player.profile_id.to_string())
-            })
-    }
-
-    /// Lookup a corresponding player in the `leaderboard` response
-    ///
-    /// # Arguments
-    /// * `req_player` - holding a reference to [`aoe2net_Player`] that contains
-    ///   all information we got from the `last_match` response
-    ///
-    /// # Errors
-    /// This function will error out if the Leaderboard [`serde_json::Value`]
-    /// could not be found
-    fn lookup_leaderboard(&mut self,
-                          req_player: &aoe2net_Player)
-                          -> Result<(RecoveredRating, JsonValue)> {
-        let looked_up_leaderboard = if let Some(looked_up_leaderboard) =
-            self.responses
-                .lookup_leaderboard_for_profile_id(&(req_player.profile_id
-                                                               .to_string()))
-        {
-            looked_up_leaderboard
-        }
-        else {
-            return Err(ProcessingError::LeaderboardNotFound(
-                req_player.profile_id.to_string().parse::<u64>()?,
-            ));
-        };
-        #[allow(clippy::cmp_owned)]
-        if looked_up_leaderboard["count"].to_string() >= 1.to_string() {
-            return Ok((RecoveredRating::Original,
-                       looked_up_leaderboard["leaderboard"][0].clone()));
-        }
-        else if looked_up_leaderboard["count"].to_string() == 0.to_string() {
-            // Try to recover
-            if let Some(looked_up_leaderboard) =
-                self.responses.lookup_leaderboard_for_profile_id(
-                                                                 &(format!(
-                    "{}_recovery",
-                    &req_player.profile_id.to_string()
-                )),
-                )
-            {
-                return Ok((RecoveredRating::Recovered, looked_up_leaderboard));
-            }
-        }
-
-        Err(ProcessingError::NotRankedLeaderboard(req_player.profile_id
-                                                            .to_string()
-                                                            .parse::<u64>()?))
+        self.responses.requested_profile_id.as_deref() ==
+        Some(req_player.profile_id.as_str())
     }
 
     /// Lookup a corresponding player's `rating`
     ///
     /// # Arguments
-    /// * `req_player` - holding a reference to [`aoe2net_Player`] that contains
-    ///   all information we got from the `last_match` response
+    /// * `req_player` - holding a reference to [`MatchPlayerData`] that
+    ///   contains all information we got about the player from the match
     ///
     /// # Errors
-    /// This function will error out if the rating [`serde_json::Value`]
-    /// could not be found
+    /// This function will error out if the [`PlayerRating`] could not be
+    /// found
     fn lookup_rating(&mut self,
-                     req_player: &aoe2net_Player)
-                     -> Result<JsonValue> {
+                     req_player: &MatchPlayerData)
+                     -> Result<PlayerRating> {
         trace!("Looking up rating for player: {:?}", req_player.profile_id);
-        let looked_up_rating = if let Some(looked_up_rating) =
-            self.responses
-                .lookup_player_rating_for_profile_id(&(req_player.profile_id
-                                                                 .to_string()))
+        if let Some(looked_up_rating) =
+            self.responses.ratings.get(&req_player.profile_id)
         {
-            looked_up_rating
+            Ok(looked_up_rating.clone())
         }
         else {
-            return Err(ProcessingError::LookupRatingNotFound(
-                req_player.profile_id.to_string().parse::<u64>()?,
-            ));
-        };
-
-        Ok(looked_up_rating[0].clone())
+            Err(ProcessingError::LookupRatingNotFound(req_player.profile_id
+                                                                .parse::<u64>()?))
+        }
     }
 
     /// Lookup a corresponding player's `alias` in the `index` of the
@@ -407,17 +439,33 @@ impl MatchInfoProcessor {
     /// `players.yaml` for that player
     ///
     /// # Arguments
-    /// * `req_player` - holding a reference to [`aoe2net_Player`] that contains
-    ///   all information we got from the `last_match` response
+    /// * `req_player` - holding a reference to [`MatchPlayerData`] that
+    ///   contains all information we got about the player from the match
     fn lookup_alias(&mut self,
-                    req_player: &aoe2net_Player)
+                    req_player: &MatchPlayerData)
                     -> Option<aoc_ref::players::Player> {
         // Lookup profile id in alias list
         self.responses
-            .db
-            .github_file_content
-            .lookup_player_alias_for_profile_id(&(req_player.profile_id
-                                                            .to_string()))
+            .ref_data
+            .lookup_player_alias_for_profile_id(&self.responses.game,
+                                                &req_player.profile_id)
+    }
+
+    /// Looks up the team of `aoc-reference-data` a player is part of by the
+    /// name of the alias
+    ///
+    /// # Arguments
+    /// * `looked_up_alias` - an Option of a reference to
+    ///   [`aoc_ref::players::Player`] holding the alias of the player
+    fn lookup_team(&self,
+                   looked_up_alias: Option<&aoc_ref::players::Player>)
+                   -> Option<aoc_ref::teams::Team> {
+        looked_up_alias.and_then(|alias| {
+                           self.responses
+                               .ref_data
+                               .lookup_team_for_player_name(&alias.name)
+                               .cloned()
+                       })
     }
 
     /// Creates a [`MatchInfoResult`]
@@ -436,12 +484,24 @@ impl MatchInfoProcessor {
     }
 }
 
+/// Returns the `id` of the match data or an error naming the missing `field`
+///
+/// # Errors
+/// Returns [`ResponderError::NotFound`] if the `id` is missing
+fn required(id: Option<usize>,
+            field: &str)
+            -> result::Result<usize, ResponderError> {
+    id.ok_or_else(|| ResponderError::NotFound(field.to_string()))
+}
+
 /// Create the `Teams` vectors from the `Players`
 ///
 /// # Arguments
 /// * `diff_team` - a mutable vector if integers with the unique team numbers of
 ///   the match to iterate over
 /// * `players_raw` - a vector slice of [`PlayerRaw`]
+/// * `player_sort` - the [`PlayerSort`] for the players inside a team
+/// * `team_sort` - the [`TeamSort`] for the teams
 /// * `teams_raw` - a reference to a mutable vector of [`TeamRaw`] to push the
 ///   `Teams` to
 ///
@@ -449,6 +509,8 @@ impl MatchInfoProcessor {
 /// Errors are bubbled up into the processing stage of [`MatchInfoProcessor`]
 fn assemble_teams(mut diff_team: Vec<i64>,
                   players_raw: &[PlayerRaw],
+                  player_sort: Option<PlayerSort>,
+                  team_sort: Option<TeamSort>,
                   teams_raw: &mut Vec<TeamRaw>)
                   -> usize {
     trace!("Sorting amount of teams vector ...");
@@ -477,38 +539,121 @@ fn assemble_teams(mut diff_team: Vec<i64>,
         }
 
         trace!("Sorting members for team {:?} ...", team);
-        // Sort for requested player
-        player_vec_helper.sort_by(|a, b| a.requested().cmp(&b.requested()));
+        sort_players(&mut player_vec_helper, player_sort);
         trace!("Sorting of team {:?} complete ...", team);
 
         trace!("Build team number {:?} ...", team);
         // Case: team == `-1` then push each player to a different
         // team
         if team == -1 {
+            let mut ffa_team_numbers = available_empty_teams.iter();
+
             for ffa_player in player_vec_helper.clone() {
                 let helper: Vec<PlayerRaw> = vec![ffa_player];
+                let (team_name, team_abbreviation) = common_team(&helper);
                 let own_team = TeamRaw::builder()
-                    .team_number(
-                        available_empty_teams.first().map_or(-1, |val| *val),
-                    )
+                    .team_number(ffa_team_numbers.next().map_or(-1, |val| *val))
                     .players(Players(helper.clone()))
+                    .team_name(team_name)
+                    .team_abbreviation(team_abbreviation)
                     .build();
                 teams_raw.push(own_team);
             }
         }
         else {
+            let (team_name, team_abbreviation) =
+                common_team(&player_vec_helper);
             let single_team =
                 TeamRaw::builder().team_number(team)
                                   .players(Players(player_vec_helper.clone()))
+                                  .team_name(team_name)
+                                  .team_abbreviation(team_abbreviation)
                                   .build();
             teams_raw.push(single_team);
         }
     }
     trace!("Finished iterating through teams.");
 
+    if let Some(team_sort) = team_sort {
+        trace!("Sorting teams by {:?} ...", team_sort);
+        sort_teams(teams_raw, team_sort);
+    }
+
     teams_raw.len()
 }
 
+/// Sort the `players` of a team by `player_sort`, see [`PlayerSort`] for the
+/// place of the requested player
+///
+/// # Arguments
+/// * `players` - a mutable vector slice of [`PlayerRaw`] of the same team
+/// * `player_sort` - the [`PlayerSort`] to order the remaining players by,
+///   `None` keeps their order
+fn sort_players(players: &mut [PlayerRaw],
+                player_sort: Option<PlayerSort>) {
+    players.sort_by(|a, b| {
+               a.requested()
+                .cmp(b.requested())
+                .then_with(|| match player_sort {
+                    None => std::cmp::Ordering::Equal,
+                    Some(PlayerSort::RatingDesc) => {
+                        b.rating().mmr().cmp(a.rating().mmr())
+                    },
+                    Some(PlayerSort::RatingAsc) => {
+                        a.rating().mmr().cmp(b.rating().mmr())
+                    },
+                    Some(PlayerSort::Name) => {
+                        a.name()
+                         .to_lowercase()
+                         .cmp(&b.name().to_lowercase())
+                    },
+                    Some(PlayerSort::Slot) => a.slot().cmp(b.slot()),
+                    Some(PlayerSort::Color) => {
+                        a.player_number().cmp(b.player_number())
+                    },
+                })
+           });
+}
+
+/// Sort the `teams` by `team_sort`
+///
+/// # Arguments
+/// * `teams` - a mutable vector slice of [`TeamRaw`]
+/// * `team_sort` - the [`TeamSort`] to order the teams by
+fn sort_teams(teams: &mut [TeamRaw],
+              team_sort: TeamSort) {
+    let has_requested = |team: &TeamRaw| {
+        team.players().0.iter().any(|player| *player.requested())
+    };
+
+    teams.sort_by(|a, b| {
+             let requested_first = match team_sort {
+                 TeamSort::Requested => has_requested(b).cmp(&has_requested(a)),
+                 TeamSort::TeamNumber => std::cmp::Ordering::Equal,
+             };
+
+             requested_first.then_with(|| a.team_number().cmp(b.team_number()))
+         });
+}
+
+/// Returns the name and abbreviation of the team of `aoc-reference-data` if
+/// all `players` of a side are part of the same team
+///
+/// # Arguments
+/// * `players` - a vector slice of [`PlayerRaw`] playing on the same side
+fn common_team(players: &[PlayerRaw]) -> (Option<String>, Option<String>) {
+    match players.split_first() {
+        Some((first, rest))
+            if first.team_name().is_some() &&
+               rest.iter()
+                   .all(|player| player.team_name() == first.team_name()) =>
+        {
+            (first.team_name().clone(), first.team_abbreviation().clone())
+        },
+        _ => (None, None),
+    }
+}
+
 /// Build a player with the builder pattern
 ///
 /// # Arguments
@@ -516,37 +661,195 @@ fn assemble_teams(mut diff_team: Vec<i64>,
 /// * `player_country` - a String wrapped in an Option that is `None` if the
 ///   country was `not set` resulting in a `Standard Value` for our API of
 ///   `null`
-/// * `req_player` - a reference to [`aoe2net_Player`] information for that
+/// * `req_player` - a reference to [`MatchPlayerData`] information for that
 ///   corresponding player
 /// * `looked_up_alias` - a reference to an Option of
 ///   [`aoc_ref::players::Player`] with all player information coming from
 ///   `aoc-reference-data`
+/// * `looked_up_team` - a reference to an Option of [`aoc_ref::teams::Team`]
+///   the player is part of in `aoc-reference-data`
 /// * `translated_civilisation_string` - a language-dependent String for the
 ///   players civilisation
 /// * `requested` - a Boolean that show if the player we are currently building
 ///   is the player the request on our API was made for
 fn build_player(player_rating: Rating,
                 player_country: Option<String>,
-                req_player: &aoe2net_Player,
+                req_player: &MatchPlayerData,
                 looked_up_alias: &Option<aoc_ref::players::Player>,
+                looked_up_team: &Option<aoc_ref::teams::Team>,
                 translated_civilisation_string: String,
                 requested: bool)
                 -> Result<PlayerRaw> {
     let player_raw = PlayerRaw::builder()
         .rating(player_rating)
-        .player_number(req_player.color.to_string().parse::<i64>()?)
+        .player_number(req_player.color)
+        .slot(req_player.slot)
         .team_number(req_player.team)
         .name(looked_up_alias.as_ref().map_or_else(
-            || util::remove_escaping(req_player.name.to_string()),
+            || req_player.name.clone(),
             |lookup_player| lookup_player.name.clone(),
         ))
         .country(looked_up_alias.as_ref().map_or_else(
             || player_country.unwrap_or_else(|| "null".to_string()),
             |lookup_player| lookup_player.country.clone(),
         ))
+        .team_name(looked_up_team.as_ref().map(|team| team.name.clone()))
+        .team_abbreviation(
+            looked_up_team.as_ref()
+                          .and_then(|team| team.abbreviation.clone()),
+        )
         .civilisation(translated_civilisation_string)
         .requested(requested)
         .build();
 
     Ok(player_raw)
 }
+
+#[test]
+fn team_name_is_set_if_all_players_share_a_team() {
+    let player = |team_number: i64, team_name: Option<&str>| {
+        PlayerRaw::builder().rating(Rating::default())
+                            .player_number(1)
+                            .slot(1)
+                            .team_number(team_number)
+                            .name("Player".to_string())
+                            .country("null".to_string())
+                            .team_name(team_name.map(str::to_string))
+                            .team_abbreviation(team_name.map(|_| {
+                                                            "aM".to_string()
+                                                        }))
+                            .civilisation("Aztecs".to_string())
+                            .requested(false)
+                            .build()
+    };
+
+    let players_raw = vec![player(1, Some("Aftermath")),
+                           player(1, Some("Aftermath")),
+                           player(2, Some("Aftermath")),
+                           player(2, None)];
+    let mut teams_raw = Vec::new();
+
+    assert_eq!(assemble_teams(vec![1, 2],
+                              &players_raw,
+                              None,
+                              None,
+                              &mut teams_raw),
+               2);
+    assert_eq!(*teams_raw[0].team_name(), None);
+    assert_eq!(teams_raw[1].team_name().as_deref(), Some("Aftermath"));
+    assert_eq!(teams_raw[1].team_abbreviation().as_deref(), Some("aM"));
+}
+
+#[cfg(test)]
+fn sortable_player(name: &str,
+                   mmr: u32,
+                   slot: i64,
+                   team_number: i64,
+                   requested: bool)
+                   -> PlayerRaw {
+    PlayerRaw::builder().rating(Rating::builder().mmr(mmr)
+                                                 .rank(1)
+                                                 .wins(0)
+                                                 .losses(0)
+                                                 .streak(0)
+                                                 .highest_mmr(mmr)
+                                                 .build())
+                        .player_number(9 - slot)
+                        .slot(slot)
+                        .team_number(team_number)
+                        .name(name.to_string())
+                        .country("null".to_string())
+                        .civilisation("Aztecs".to_string())
+                        .requested(requested)
+                        .build()
+}
+
+#[cfg(test)]
+fn team_names(teams_raw: &[TeamRaw]) -> Vec<Vec<&str>> {
+    teams_raw.iter()
+             .map(|team| {
+                 team.players()
+                     .0
+                     .iter()
+                     .map(|player| player.name().as_str())
+                     .collect()
+             })
+             .collect()
+}
+
+#[cfg(test)]
+fn team_numbers(teams_raw: &[TeamRaw]) -> Vec<i64> {
+    teams_raw.iter().map(|team| *team.team_number()).collect()
+}
+
+#[test]
+fn players_and_teams_of_4v4_are_sorted() {
+    let players_raw = vec![sortable_player("Hera", 2500, 1, 1, false),
+                           sortable_player("liereyy", 2300, 3, 1, false),
+                           sortable_player("Yo", 2400, 5, 1, false),
+                           sortable_player("Daut", 2200, 7, 1, false),
+                           sortable_player("Viper", 2450, 2, 2, false),
+                           sortable_player("Tatoh", 2350, 4, 2, true),
+                           sortable_player("slam", 2250, 6, 2, false),
+                           sortable_player("Nili", 2420, 8, 2, false)];
+    let sorted = |player_sort: PlayerSort, team_sort: Option<TeamSort>| {
+        let mut teams_raw = Vec::new();
+        assert_eq!(assemble_teams(vec![1, 2],
+                                  &players_raw,
+                                  Some(player_sort),
+                                  team_sort,
+                                  &mut teams_raw),
+                   2);
+        teams_raw
+    };
+
+    assert_eq!(team_names(&sorted(PlayerSort::RatingDesc,
+                                  Some(TeamSort::Requested))),
+               vec![vec!["Viper", "Nili", "slam", "Tatoh"],
+                    vec!["Hera", "Yo", "liereyy", "Daut"]]);
+    assert_eq!(team_names(&sorted(PlayerSort::RatingAsc,
+                                  Some(TeamSort::TeamNumber))),
+               vec![vec!["Daut", "liereyy", "Yo", "Hera"],
+                    vec!["slam", "Nili", "Viper", "Tatoh"]]);
+    assert_eq!(team_names(&sorted(PlayerSort::Name, None)),
+               vec![vec!["Nili", "slam", "Viper", "Tatoh"],
+                    vec!["Daut", "Hera", "liereyy", "Yo"]]);
+    assert_eq!(team_names(&sorted(PlayerSort::Slot, None))[1],
+               vec!["Hera", "liereyy", "Yo", "Daut"]);
+    assert_eq!(team_names(&sorted(PlayerSort::Color, None))[1],
+               vec!["Daut", "Yo", "liereyy", "Hera"]);
+}
+
+#[test]
+fn players_of_ffa_are_sorted_into_own_teams() {
+    let players_raw = vec![sortable_player("Hera", 2500, 3, -1, false),
+                           sortable_player("Viper", 2450, 1, -1, false),
+                           sortable_player("Tatoh", 2350, 4, -1, true),
+                           sortable_player("Daut", 2200, 2, -1, false)];
+    let mut teams_raw = Vec::new();
+
+    assert_eq!(assemble_teams(vec![-1],
+                              &players_raw,
+                              Some(PlayerSort::Slot),
+                              Some(TeamSort::Requested),
+                              &mut teams_raw),
+               4);
+    assert_eq!(team_names(&teams_raw),
+               vec![vec!["Tatoh"], vec!["Viper"], vec!["Daut"], vec!["Hera"]]);
+    assert_eq!(team_numbers(&teams_raw), vec![4, 1, 2, 3]);
+
+    teams_raw.clear();
+    assemble_teams(vec![-1],
+                   &players_raw,
+                   Some(PlayerSort::RatingDesc),
+                   None,
+                   &mut teams_raw);
+    assert_eq!(team_names(&teams_raw),
+               vec![vec!["Hera"], vec!["Viper"], vec!["Daut"], vec!["Tatoh"]]);
+    assert_eq!(team_numbers(&teams_raw), vec![1, 2, 3, 4]);
+
+    teams_raw.clear();
+    assemble_teams(vec![-1], &players_raw, None, None, &mut teams_raw);
+    assert_eq!(team_names(&teams_raw),
+               vec![vec!["Hera"], vec!["Viper"], vec!["Daut"], vec!["Tatoh"]]);
+}
@@ -1,40 +1,193 @@
 //! Starting point of our data processing stage
 
-mod match_data_responder;
+pub(crate) mod civ_stats;
+pub(crate) mod head_to_head;
+pub mod leaderboard;
+pub mod lobbies;
+pub(crate) mod match_data_responder;
+pub mod match_history;
 pub mod match_info_processor;
+pub mod player_lookup;
+pub mod player_profile;
+pub mod player_search;
+pub mod pro_matches;
+pub mod rating_history;
+pub mod subscription;
 
 use std::{
     path::PathBuf,
     sync::Arc,
 };
 
+use dashmap::{
+    mapref::entry::Entry,
+    DashMap,
+};
+use futures::{
+    future::{
+        BoxFuture,
+        Shared,
+    },
+    FutureExt,
+};
+use serde_json::Value as JsonValue;
 use tokio::{
     self,
     sync::Mutex,
 };
-use tracing::error;
+use tracing::{
+    debug,
+    error,
+};
 use tracing_futures::Instrument;
 use url::Url;
 use uuid::Uuid;
 
-use crate::domain::{
-    api_handler::client::A2NClient,
-    data_processing::match_info_processor::MatchInfoProcessor,
-    types::{
-        api::{
-            MatchInfoRequest,
-            MatchInfoResult,
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::{
+            civ_stats::{
+                civ_stats,
+                CIV_STATS_GAME,
+            },
+            head_to_head::head_to_head,
+            match_info_processor::MatchInfoProcessor,
         },
-        error::{
-            ErrorMessageToFrontend,
-            ProcessingError,
-            ResponderError,
+        provider::Aoe2NetProvider,
+        types::{
+            api::{
+                MatchInfoRequest,
+                MatchInfoResult,
+            },
+            error::{
+                ErrorMessageToFrontend,
+                ResponderError,
+            },
+            match_data::MatchData,
+            HeadToHeadKey,
+            InMemoryDb,
+            MatchDataResponses,
         },
-        InMemoryDb,
-        MatchDataResponses,
     },
+    LAST_GOOD_MATCH_INFO_TTL,
+    STANDARD,
 };
 
+/// A pending `build_result` that can be awaited by multiple requests
+type InFlightResult = Shared<BoxFuture<'static, MatchInfoResult>>;
+/// API root and [`MatchInfoRequest`] of a pending `build_result`
+type InFlightKey = (Url, MatchInfoRequest);
+
+lazy_static! {
+/// Currently processed [`MatchInfoRequest`]s keyed by the API root they are
+/// requested from, identified by an id so only the request that created an
+/// entry removes it again
+static ref IN_FLIGHT: DashMap<InFlightKey, (Uuid, InFlightResult)> =
+    DashMap::new();
+}
+
+/// Entry point for processing part of `matchinfo` endpoint which coalesces
+/// concurrent identical requests
+///
+/// If an identical [`MatchInfoRequest`] is already processed against the same
+/// `root`, we wait for its result instead of running [`build_result`] again, so all of them receive
+/// the same [`MatchInfoResult`].
+///
+/// While the circuit breaker for `root` is open, the last successful
+/// [`MatchInfoResult`] for the same request is returned with a
+/// [`ErrorMessageToFrontend::Recover`] note instead, if there is one.
+pub async fn build_result_coalesced(par: MatchInfoRequest,
+                                    client: A2NClient<'static,
+                                                      reqwest::Client>,
+                                    root: Url,
+                                    in_memory_db: Arc<Mutex<InMemoryDb>>)
+                                    -> MatchInfoResult {
+    if client.is_circuit_open(&root) {
+        if let Some(stale) = stale_result(&par, &in_memory_db).await {
+            return stale;
+        }
+    }
+
+    let breaker_client = client.clone();
+    let breaker_root = root.clone();
+
+    let pending = match IN_FLIGHT.entry((root.clone(), par.clone())) {
+        Entry::Occupied(entry) => {
+            debug!("Joining in-flight request for {:?}:{:?}",
+                   par.id_type,
+                   par.id_number);
+            entry.get().1.clone()
+        }
+        Entry::Vacant(entry) => {
+            let id = Uuid::new_v4();
+            let key = entry.key().clone();
+
+            // The result is built in its own task, so it is finished and
+            // removed from `IN_FLIGHT` even if every waiting request has been
+            // cancelled in the meantime
+            let task = tokio::spawn(build_result(par.clone(),
+                                                 client,
+                                                 root,
+                                                 in_memory_db.clone(),
+                                                 None).map(move |result| {
+                IN_FLIGHT.remove_if(&key, |_, (in_flight_id, _)| {
+                             *in_flight_id == id
+                         });
+                result
+            }));
+
+            let pending = async move {
+                task.await.unwrap_or_else(|err| {
+                    std::panic::resume_unwind(err.into_panic())
+                })
+            }.boxed()
+             .shared();
+
+            entry.insert((id, pending)).1.clone()
+        }
+    };
+
+    let result = pending.await;
+
+    if result.match_info.is_some() {
+        in_memory_db.lock()
+                    .await
+                    .last_good_match_infos
+                    .insert(par, result.clone(), *LAST_GOOD_MATCH_INFO_TTL);
+    }
+    else if breaker_client.is_circuit_open(&breaker_root) {
+        if let Some(stale) = stale_result(&par, &in_memory_db).await {
+            return stale;
+        }
+    }
+
+    result
+}
+
+/// Last successful [`MatchInfoResult`] for `par`, marked as stale for the
+/// frontend
+async fn stale_result(par: &MatchInfoRequest,
+                      in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                      -> Option<MatchInfoResult> {
+    let stale = in_memory_db.lock()
+                            .await
+                            .last_good_match_infos
+                            .get(par)?;
+
+    debug!("Serving stale result for {:?}:{:?}", par.id_type, par.id_number);
+
+    let mut error_message = stale.error_message;
+    error_message.push(ErrorMessageToFrontend::recover(
+        "stale_data",
+        "aoe2.net is currently not available, the shown data might be \
+         outdated",
+    ));
+
+    Some(MatchInfoResult { error_message,
+                           ..stale })
+}
+
 /// Entry point for processing part of `matchinfo` endpoint
 ///
 /// # Errors
@@ -59,72 +212,209 @@ pub async fn build_result(par: MatchInfoRequest,
     // in the query future lifetime
     let query_span = tracing::info_span!("Querying for data from APIs...");
 
+    let h2h_client = client.clone().with_root(root.clone());
+    // aoestats.io is requested on the root of its requests
+    let civ_stats_client = client.clone();
+
+    let provider = Aoe2NetProvider::new(client.with_root(root),
+                                        in_memory_db.clone())
+        .with_export_path(export_path);
+
     let responses =
         MatchDataResponses::with_match_data(par.clone(),
-                                            client,
-                                            in_memory_db,
-                                            export_path,
-                                            root).instrument(query_span)
-                                                 .await;
+                                            &provider,
+                                            in_memory_db.clone())
+            .instrument(query_span)
+            .await;
 
     match responses {
         Err(err) => {
-            if let ResponderError::LastMatchNotFound = err {
-                error!("Failed with {:?}", err);
-                MatchInfoResult::builder()
-                    .error_message(ErrorMessageToFrontend::HardFail(
-                        std::borrow::Cow::Owned(format!(
-                            "MatchInfo processing failed: {}",
-                            err
-                        )),
-                    ))
-                    .build()
-            }
-            else {
-                error!("Failed with {:?}", err);
-                MatchInfoResult::builder()
-                    .error_message(
-                        ErrorMessageToFrontend::GenericResponderError(
-                            std::borrow::Cow::Owned(format!("{}", err)),
-                        ),
-                    )
-                    .build()
-            }
+            error!("Failed with {:?}", err);
+            let candidates =
+                if let ResponderError::AmbiguousPlayerName(candidates) = &err {
+                    candidates.clone()
+                }
+                else {
+                    Vec::new()
+                };
+
+            MatchInfoResult::builder()
+                .error_message(vec![ErrorMessageToFrontend::hard_fail(
+                    err.code(),
+                    format!("MatchInfo processing failed: {err}"),
+                )
+                .with_status(err.status_code())])
+                .candidates(candidates)
+                .build()
         },
         Ok(response) => {
-            // Process the Responses
-            let processed_result =
-                MatchInfoProcessor::with_response(response).process().map_err(|err| {
+            let profile_ids = response.match_data
+                                      .players
+                                      .iter()
+                                      .map(|player| player.profile_id.clone())
+                                      .collect::<Vec<_>>();
+            let match_data = response.match_data.clone();
 
-            // Handle all the errors and make sure, we always return a
-            // `MatchInfoResult`
-                    if let ProcessingError::NotRankedLeaderboard(_) = err {
+            // Process the Responses, `SoftFail`s are collected within the
+            // `MatchInfoResult`, so we only need to handle the others here
+            match MatchInfoProcessor::with_response(response)
+                .with_sorting(par.sort, par.team_sort)
+                .process()
+            {
+                Ok(processed) => {
+                    let result = processed.assemble()
+                                          .expect("MatchInfoResult assembly \
+                                                   failed.");
+
+                    let result = if par.h2h {
+                        add_head_to_head(&par,
+                                         result,
+                                         profile_ids,
+                                         &h2h_client,
+                                         &in_memory_db).await
+                    }
+                    else {
+                        result
+                    };
+
+                    if par.civ_stats {
+                        add_civ_stats(&par,
+                                      result,
+                                      &match_data,
+                                      &civ_stats_client,
+                                      &in_memory_db).await
+                    }
+                    else {
+                        result
+                    }
+                },
+                Err(err) => {
                     error!("Failed with {:?}", err);
                     MatchInfoResult::builder()
-                        .error_message(ErrorMessageToFrontend::HardFail(
-                            std::borrow::Cow::Owned(format!(
-                                "MatchInfo processing failed: {}",
-                                err
-                            )),
-                        ))
+                        .error_message(vec![ErrorMessageToFrontend::hard_fail(
+                            err.code(),
+                            format!("MatchInfo processing failed for {:?}:{:?} \
+                                     with {}",
+                                    par.id_type, par.id_number, err),
+                        )
+                        .with_status(err.status_code())])
                         .build()
-                }
-                else {
-                    error!("Failed with {:?}", err);
-                    MatchInfoResult::builder()
-                            .error_message(ErrorMessageToFrontend::HardFail(std::borrow::Cow::Owned(format!(
-                                "MatchInfo processing failed for {:?}:{:?} with {}",
-                                par.id_type,
-                                par.id_number,
-                                err.to_string()
-                            ))))
-                            .build()
-                }
-                });
+                },
+            }
+        },
+    }
+}
+
+/// Add the [`HeadToHead`](crate::domain::types::api::HeadToHead) record of
+/// the players to the `result`, if the match is a 1v1
+///
+/// A failure only adds a `SoftFail` to the `result`, as the match itself is
+/// still worth showing.
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+async fn add_head_to_head(par: &MatchInfoRequest,
+                          mut result: MatchInfoResult,
+                          profile_ids: Vec<String>,
+                          client: &A2NClient<'static, reqwest::Client>,
+                          in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                          -> MatchInfoResult {
+    if result.match_info.is_none() {
+        return result;
+    }
+
+    if let [first, second] = profile_ids.as_slice() {
+        let key = HeadToHeadKey::new(
+            par.game.clone().unwrap_or_else(|| {
+                                (*STANDARD.get(&"game").unwrap()).to_string()
+                            }),
+            par.language.clone().unwrap_or_else(|| {
+                                    (*STANDARD.get(&"language").unwrap())
+                                    .to_string()
+                                }),
+            first.clone(),
+            second.clone(),
+        );
 
-            processed_result.unwrap()
-                            .assemble()
-                            .expect("MatchInfoResult assembly failed.")
+        match head_to_head(key, client, in_memory_db).await {
+            Ok(head_to_head) => {
+                result.match_info =
+                    result.match_info
+                          .map(|info| info.with_head_to_head(head_to_head));
+            },
+            Err(err) => {
+                error!("Head-to-head failed with {:?}", err);
+                result.error_message.push(ErrorMessageToFrontend::soft_fail(
+                    "head_to_head_failed",
+                    format!("Head-to-head could not be built: {err}"),
+                ));
+            },
+        }
+    }
+    else {
+        result.error_message.push(ErrorMessageToFrontend::soft_fail(
+            "head_to_head_unavailable",
+            "Head-to-head is only available for 1v1 matches",
+        ));
+    }
+
+    result
+}
+
+/// Add the [`CivStats`](crate::domain::types::api::CivStats) of the players
+/// from aoestats.io to the `result`, which only has stats for Age of Empires
+/// II: Definitive Edition
+///
+/// A failure only adds a `SoftFail` to the `result`, as the match itself is
+/// still worth showing.
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+async fn add_civ_stats(par: &MatchInfoRequest,
+                       mut result: MatchInfoResult,
+                       match_data: &MatchData,
+                       client: &A2NClient<'static, reqwest::Client>,
+                       in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                       -> MatchInfoResult {
+    if result.match_info.is_none() {
+        return result;
+    }
+
+    let game = par.game.clone().unwrap_or_else(|| {
+                                   (*STANDARD.get(&"game").unwrap()).to_string()
+                               });
+
+    if game != CIV_STATS_GAME {
+        result.error_message.push(ErrorMessageToFrontend::soft_fail(
+            "civ_stats_unavailable",
+            "Civilization stats are only available for AoE2 DE",
+        ));
+        return result;
+    }
+
+    // Civilizations are matched by their English name on aoestats.io
+    let english = *STANDARD.get(&"language").unwrap();
+    let english_strings = in_memory_db.lock()
+                                      .await
+                                      .language_strings(&game, english)
+                                      .unwrap_or(JsonValue::Null);
+
+    match civ_stats(match_data, &english_strings, client, in_memory_db).await {
+        Ok(civ_stats) => {
+            result.match_info =
+                result.match_info
+                      .map(|info| info.with_civ_stats(civ_stats));
+        },
+        Err(err) => {
+            error!("Civilization stats failed with {:?}", err);
+            result.error_message.push(ErrorMessageToFrontend::soft_fail(
+                "civ_stats_failed",
+                format!("Civilization stats could not be requested: {err}"),
+            ));
         },
     }
+
+    result
 }
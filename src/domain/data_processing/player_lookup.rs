@@ -0,0 +1,187 @@
+//! Resolving in-game names of players to their `profile_id`
+//!
+//! Names are looked up in the alias index of the `aoc-reference-data` first,
+//! which also knows the `aka` names of a player. Names that are not part of it
+//! are searched on the leaderboard of aoe2.net.
+
+use std::result;
+
+use aoe2net::{
+    endpoints::leaderboard::GetLeaderboardRequest,
+    types::api::LeaderboardEntry,
+};
+use tracing::debug;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::typed_response_data,
+        types::{
+            aoc_ref::RefDataLists,
+            api::PlayerCandidate,
+            error::ResponderError,
+        },
+        util,
+    },
+    NAME_SEARCH_COUNT,
+    NAME_SEARCH_LEADERBOARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Resolve the in-game `name` of a player to its `profile_id`
+///
+/// # Errors
+/// Returns [`ResponderError::PlayerNotFound`] if no player has that name and
+/// [`ResponderError::AmbiguousPlayerName`] with all candidates if more than
+/// one player has it
+pub async fn resolve_profile_id_for_name(name: &str,
+                                         game: &str,
+                                         client: &A2NClient<'static,
+                                                  reqwest::Client>,
+                                         ref_data: &RefDataLists)
+                                         -> Result<String> {
+    let aliases = ref_data.lookup_players_for_name(name)
+                          .into_iter()
+                          .filter_map(|player| {
+                              let profile_id =
+                                  player.platforms.profile_ids(game).first()?;
+
+                              Some(PlayerCandidate {
+                                  name: player.name.clone(),
+                                  profile_id: profile_id.clone(),
+                                  country: Some(player.country.clone()),
+                              })
+                          })
+                          .collect::<Vec<_>>();
+
+    if !aliases.is_empty() {
+        debug!("Resolved name {:?} through the alias index", name);
+        return choose_alias(aliases);
+    }
+
+    debug!("Name {:?} not found in the alias index, searching the leaderboard",
+           name);
+
+    let leaderboard_id = util::leaderboard_id(game, *NAME_SEARCH_LEADERBOARD)
+        .ok_or_else(|| ResponderError::PlayerNotFound(name.to_string()))?;
+
+    let req = GetLeaderboardRequest::builder()
+        .game(game)
+        .leaderboard_id(leaderboard_id)
+        .count(*NAME_SEARCH_COUNT)
+        .search(Some(name))
+        .build();
+
+    let leaderboard = typed_response_data(client.req_get(req).await)?;
+
+    choose_candidate(name, leaderboard_candidates(leaderboard.leaderboard))
+}
+
+/// Convert the `entries` of a leaderboard into [`PlayerCandidate`]s, entries
+/// without a `profile_id` are skipped
+pub(crate) fn leaderboard_candidates(entries: Vec<LeaderboardEntry>)
+                                     -> Vec<PlayerCandidate> {
+    entries.into_iter()
+           .filter_map(|entry| {
+               Some(PlayerCandidate {
+                   profile_id: entry.profile_id?.to_string(),
+                   name: entry.name.unwrap_or_default(),
+                   country: entry.country.as_deref().map(str::to_lowercase),
+               })
+           })
+           .collect()
+}
+
+/// Pick the `profile_id` of the only player of the `aoc-reference-data` with
+/// the name
+///
+/// Every alias matches the name exactly, either by its primary name or one of
+/// its `aka` names, so different players are ambiguous even if only one of
+/// them has it as primary name. A player with multiple accounts is listed
+/// only once with its main account.
+///
+/// # Errors
+/// Returns an error if more than one player has the name
+fn choose_alias(mut aliases: Vec<PlayerCandidate>) -> Result<String> {
+    if aliases.len() == 1 {
+        Ok(aliases.remove(0).profile_id)
+    }
+    else {
+        Err(ResponderError::AmbiguousPlayerName(aliases))
+    }
+}
+
+/// Pick the `profile_id` of the only candidate or the only exact match
+///
+/// # Errors
+/// Returns an error if there is no candidate or it is ambiguous which one is
+/// meant
+fn choose_candidate(name: &str,
+                    mut candidates: Vec<PlayerCandidate>)
+                    -> Result<String> {
+    let normalized_name = name.trim().to_lowercase();
+
+    let mut exact_matches =
+        candidates.iter()
+                  .filter(|candidate| {
+                      candidate.name.to_lowercase() == normalized_name
+                  })
+                  .cloned()
+                  .collect::<Vec<_>>();
+
+    let err = match (exact_matches.len(), candidates.len()) {
+        (1, _) => return Ok(exact_matches.remove(0).profile_id),
+        (0, 1) => return Ok(candidates.remove(0).profile_id),
+        (0, 0) => ResponderError::PlayerNotFound(name.to_string()),
+        (0, _) => ResponderError::AmbiguousPlayerName(candidates),
+        (..) => ResponderError::AmbiguousPlayerName(exact_matches),
+    };
+
+    Err(err)
+}
+
+#[test]
+fn exact_name_match_wins_over_partial_matches() {
+    let entry = |profile_id: Option<i64>, name: &str, country: Option<&str>| {
+        LeaderboardEntry { profile_id,
+                           name: Some(name.to_string()),
+                           country: country.map(str::to_string),
+                           ..LeaderboardEntry::default() }
+    };
+
+    let candidates =
+        leaderboard_candidates(vec![entry(Some(196240), "TheViper", Some("NO")),
+                                    entry(Some(1), "TheViperFan", None),
+                                    entry(None, "TheViperBot", None)]);
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].country, Some("no".to_string()));
+
+    assert_eq!(choose_candidate("theviper", candidates.clone()).unwrap(),
+               "196240");
+    assert!(matches!(choose_candidate("Viper", candidates),
+                     Err(ResponderError::AmbiguousPlayerName(candidates))
+                     if candidates.len() == 2));
+    assert!(matches!(choose_candidate("Hera", Vec::new()),
+                     Err(ResponderError::PlayerNotFound(_))));
+}
+
+#[test]
+fn alias_matching_an_aka_is_ambiguous_with_a_primary_name() {
+    let candidate = |name: &str, profile_id: &str| {
+        PlayerCandidate { name: name.to_string(),
+                          profile_id: profile_id.to_string(),
+                          country: None }
+    };
+
+    assert_eq!(choose_alias(vec![candidate("TheViper", "196240")]).unwrap(),
+               "196240");
+
+    // "Viper" is the primary name of one player and an `aka` of TheViper
+    let aliases =
+        vec![candidate("Viper", "1"), candidate("TheViper", "196240")];
+
+    assert!(matches!(choose_alias(aliases),
+                     Err(ResponderError::AmbiguousPlayerName(candidates))
+                     if candidates.len() == 2));
+}
@@ -0,0 +1,194 @@
+//! Assembling the player card of the `player/{profile_id}` endpoint
+//!
+//! The player is looked up in the `aoc-reference-data` and `teams.json`, the
+//! ratings are requested from aoe2.net for each leaderboard concurrently.
+
+use std::{
+    result,
+    sync::Arc,
+};
+
+use aoe2net::{
+    endpoints::{
+        leaderboard::GetLeaderboardRequest,
+        rating_history::GetRatingHistoryRequest,
+    },
+    types::api::LeaderboardEntry,
+};
+use tokio::sync::Mutex;
+use tracing::{
+    debug,
+    warn,
+};
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::typed_response_data,
+        provider::aoe2net::player_rating,
+        types::{
+            api::{
+                PlayerProfile,
+                PlayerProfileRequest,
+                PlayerTeam,
+                Rating,
+            },
+            error::{
+                ErrorMessageToFrontend,
+                ResponderError,
+            },
+            InMemoryDb,
+        },
+        util,
+    },
+    STANDARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+
+/// Entry point for processing part of `player/{profile_id}` endpoint
+///
+/// Ratings that can't be looked up are reported as soft fails in the
+/// `error_message` of the [`PlayerProfile`].
+///
+/// # Errors
+/// Returns [`ResponderError::PlayerNotFound`] if the player is neither part
+/// of the `aoc-reference-data` nor ranked on any leaderboard, or the first
+/// error of the rating lookups if none of them succeeded
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn build_player_profile(par: PlayerProfileRequest,
+                                  client: A2NClient<'static,
+                                                    reqwest::Client>,
+                                  root: Url,
+                                  in_memory_db: Arc<Mutex<InMemoryDb>>)
+                                  -> Result<PlayerProfile> {
+    let client = client.with_root(root);
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    let (rm_1v1, rm_team, ew_1v1, dm_1v1) = futures::join!(
+        lookup_rating(&client, &game, &par.profile_id, "rm_1v1"),
+        lookup_rating(&client, &game, &par.profile_id, "rm_team"),
+        lookup_rating(&client, &game, &par.profile_id, "ew_1v1"),
+        lookup_rating(&client, &game, &par.profile_id, "dm_1v1")
+    );
+
+    let mut profile = PlayerProfile { profile_id: par.profile_id.clone(),
+                                      ..PlayerProfile::default() };
+    let mut leaderboard_entry: Option<LeaderboardEntry> = None;
+    let mut errors: Vec<ResponderError> = Vec::new();
+
+    for (looked_up, rating) in [(rm_1v1, &mut profile.ratings.rm_1v1),
+                                (rm_team, &mut profile.ratings.rm_team),
+                                (ew_1v1, &mut profile.ratings.ew_1v1),
+                                (dm_1v1, &mut profile.ratings.dm_1v1)]
+    {
+        match looked_up {
+            Ok(Some((looked_up_rating, entry))) => {
+                *rating = Some(looked_up_rating);
+                leaderboard_entry.get_or_insert(entry);
+            },
+            Ok(None) => {},
+            Err(err) => {
+                warn!("Rating lookup for player {:?} failed: {}",
+                      par.profile_id, err);
+                errors.push(err);
+            },
+        }
+    }
+
+    let ref_data = &in_memory_db.lock().await.github_file_content;
+
+    if let Some(alias) =
+        ref_data.lookup_player_alias_for_profile_id(&game, &par.profile_id)
+    {
+        profile.team = ref_data.lookup_team_for_player_name(&alias.name)
+                               .map(PlayerTeam::from);
+        profile.name = alias.name;
+        profile.country = Some(alias.country);
+        profile.twitch = alias.twitch;
+        profile.youtube = alias.youtube;
+        profile.liquipedia = alias.liquipedia;
+        profile.esportsearnings = alias.esportsearnings;
+    }
+    else if let Some(entry) = leaderboard_entry {
+        debug!("No alias found for player {:?}, using the leaderboard",
+               par.profile_id);
+        profile.name = entry.name.unwrap_or_default();
+        profile.country = entry.country.as_deref().map(str::to_lowercase);
+    }
+    else if errors.is_empty() {
+        return Err(ResponderError::PlayerNotFound(par.profile_id));
+    }
+    else {
+        return Err(errors.remove(0));
+    }
+
+    profile.error_message = errors.iter()
+                                  .map(|err| {
+                                      ErrorMessageToFrontend::soft_fail(
+                                          err.code(),
+                                          format!("Rating lookup failed: \
+                                                   {err}"),
+                                      )
+                                  })
+                                  .collect();
+
+    Ok(profile)
+}
+
+/// Look up the current [`Rating`] of a player on the kind of `leaderboard` of
+/// the `game`, e.g. `rm_1v1`, together with its entry on that leaderboard
+///
+/// # Errors
+/// Returns an error if a request failed or a response couldn't be parsed,
+/// returns `None` if the player isn't ranked on the leaderboard or the game
+/// doesn't have that kind of leaderboard
+async fn lookup_rating(client: &A2NClient<'static, reqwest::Client>,
+                       game: &str,
+                       profile_id: &str,
+                       leaderboard: &str)
+                       -> Result<Option<(Rating, LeaderboardEntry)>> {
+    let Some(leaderboard_id) = util::leaderboard_id(game, leaderboard) else {
+        return Ok(None);
+    };
+
+    let req_lead = GetLeaderboardRequest::builder()
+        .game(game)
+        .profile_id(profile_id)
+        .leaderboard_id(leaderboard_id)
+        .build();
+
+    let leaderboard = typed_response_data(client.req_get(req_lead).await)?;
+
+    let Some(entry) = leaderboard.leaderboard.into_iter().next() else {
+        return Ok(None);
+    };
+
+    let req_rating = GetRatingHistoryRequest::builder()
+        .game(game)
+        .profile_id(profile_id)
+        .leaderboard_id(leaderboard_id)
+        .build();
+
+    let rating_history =
+        typed_response_data(client.req_get(req_rating).await)?;
+
+    let Some(looked_up_rating) = player_rating(&rating_history, Some(&entry))
+    else {
+        return Ok(None);
+    };
+
+    let mut rating = Rating::from(&looked_up_rating);
+    rating.calculate_win_rate();
+
+    Ok(Some((rating, entry)))
+}
@@ -0,0 +1,205 @@
+//! Searching for players by (parts of) their name
+//!
+//! Players of the `aoc-reference-data` are found through its search index,
+//! which also knows the `aka` names of a player. They are complemented by the
+//! players found when searching the leaderboard of aoe2.net.
+
+use std::sync::Arc;
+
+use aoe2net::endpoints::leaderboard::GetLeaderboardRequest;
+use tokio::sync::Mutex;
+use tracing::{
+    debug,
+    warn,
+};
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::{
+            match_data_responder::typed_response_data,
+            player_lookup::leaderboard_candidates,
+        },
+        types::{
+            aoc_ref::RefDataLists,
+            api::{
+                PlayerCandidate,
+                PlayerSearchRequest,
+                PlayerSearchResponse,
+                PlayerSearchResult,
+                PlayerSearchSource,
+                StreamingLinks,
+            },
+            InMemoryDb,
+        },
+        util,
+    },
+    NAME_SEARCH_COUNT,
+    NAME_SEARCH_LEADERBOARD,
+    PLAYER_SEARCH_LIMIT,
+    STANDARD,
+};
+
+/// Entry point for processing part of `players/search` endpoint
+///
+/// Players of the `aoc-reference-data` come first, players that are only
+/// found on the leaderboard are appended to them. If the leaderboard can't be
+/// searched, only the players of the `aoc-reference-data` are returned.
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn search_players(par: PlayerSearchRequest,
+                            client: A2NClient<'static, reqwest::Client>,
+                            root: Url,
+                            in_memory_db: Arc<Mutex<InMemoryDb>>)
+                            -> PlayerSearchResponse {
+    let client = client.with_root(root);
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    let leaderboard = if let Some(leaderboard_id) =
+        util::leaderboard_id(&game, *NAME_SEARCH_LEADERBOARD)
+    {
+        let req = GetLeaderboardRequest::builder()
+            .game(game.as_str())
+            .leaderboard_id(leaderboard_id)
+            .count(*NAME_SEARCH_COUNT)
+            .search(Some(par.q.as_str()))
+            .build();
+
+        match typed_response_data(client.req_get(req).await) {
+            Ok(page) => leaderboard_candidates(page.leaderboard),
+            Err(err) => {
+                warn!("Searching the leaderboard for {:?} failed: {}",
+                      par.q, err);
+                Vec::new()
+            },
+        }
+    }
+    else {
+        warn!("Game {:?} has no leaderboard to search for {:?}", game, par.q);
+        Vec::new()
+    };
+
+    let players = merge_results(&par.q,
+                                &in_memory_db.lock().await.github_file_content,
+                                &game,
+                                leaderboard);
+
+    debug!("Found {} players for {:?}", players.len(), par.q);
+
+    PlayerSearchResponse { players }
+}
+
+/// Merge the players found in the `aoc-reference-data` with the players
+/// found on the `leaderboard` of the `game`, a player is only listed once
+fn merge_results(query: &str,
+                 ref_data: &RefDataLists,
+                 game: &str,
+                 leaderboard: Vec<PlayerCandidate>)
+                 -> Vec<PlayerSearchResult> {
+    let mut players = ref_data.search_players(query, *PLAYER_SEARCH_LIMIT)
+                              .iter()
+                              .map(|player| {
+                                  PlayerSearchResult::with_reference_data(
+                                      player,
+                                      game,
+                                      ref_data.lookup_team_for_player_name(
+                                          &player.name,
+                                      ),
+                                  )
+                              })
+                              .collect::<Vec<_>>();
+
+    for candidate in leaderboard {
+        if players.len() >= *PLAYER_SEARCH_LIMIT {
+            break;
+        }
+
+        let profile_id = &candidate.profile_id;
+
+        if players.iter()
+                  .any(|player| player.profile_ids.contains(profile_id))
+        {
+            continue;
+        }
+
+        // Players that are only found by their current in-game name are
+        // still enriched with the `aoc-reference-data`
+        let player = ref_data
+            .lookup_player_alias_for_profile_id(game, &candidate.profile_id)
+            .map_or_else(
+                || PlayerSearchResult {
+                    name: candidate.name,
+                    profile_ids: vec![candidate.profile_id],
+                    country: candidate.country,
+                    team: None,
+                    streams: StreamingLinks::default(),
+                    source: PlayerSearchSource::Leaderboard,
+                },
+                |alias| {
+                    PlayerSearchResult::with_reference_data(
+                        &alias,
+                        game,
+                        ref_data.lookup_team_for_player_name(&alias.name),
+                    )
+                },
+            );
+
+        players.push(player);
+    }
+
+    players
+}
+
+#[test]
+fn leaderboard_results_are_merged_into_reference_data() {
+    use crate::domain::types::testing::ref_data_lists;
+
+    let player = |name: &str, profile_id: &str| {
+        serde_json::json!({
+            "name": name,
+            "country": "no",
+            "twitch": "https://www.twitch.tv/theviper",
+            "platforms": { "de": [profile_id] }
+        })
+    };
+
+    let ref_data = ref_data_lists(
+        serde_json::json!([player("TheViper", "196240"),
+                           player("Hera", "199325")]),
+        serde_json::json!([{
+            "name": "GamerLegion",
+            "abbreviation": "GL",
+            "players": ["TheViper"]
+        }]),
+    );
+
+    let candidate = |name: &str, profile_id: &str| PlayerCandidate {
+        name: name.to_string(),
+        profile_id: profile_id.to_string(),
+        country: None,
+    };
+
+    let players = merge_results("viper",
+                                &ref_data,
+                                "aoe2de",
+                                vec![candidate("GL.TheViper", "196240"),
+                                     candidate("ViperFan", "1"),
+                                     candidate("[aM] Hera", "199325")]);
+
+    assert_eq!(players.iter()
+                      .map(|player| player.name.as_str())
+                      .collect::<Vec<_>>(),
+               vec!["TheViper", "ViperFan", "Hera"]);
+    assert_eq!(players[0].team, Some("GamerLegion".to_string()));
+    assert_eq!(players[0].source, PlayerSearchSource::ReferenceData);
+    assert_eq!(players[1].source, PlayerSearchSource::Leaderboard);
+    assert_eq!(players[2].streams.twitch,
+               Some("https://www.twitch.tv/theviper".to_string()));
+}
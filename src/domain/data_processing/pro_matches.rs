@@ -0,0 +1,385 @@
+//! Discovery of ongoing matches of known players for the `live/pro_matches`
+//! endpoint
+//!
+//! A background task polls the `matches` endpoint of aoe2.net incrementally,
+//! starting after the most recent start time it has seen so far. Every match
+//! with a player that is part of the `aoc-reference-data` is kept in the
+//! [`InMemoryDb`] and requested again on each poll, until it is finished or
+//! older than [`static@crate::PRO_MATCHES_MAX_AGE`].
+
+use std::{
+    convert::TryFrom,
+    result,
+    sync::Arc,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use aoe2net::{
+    endpoints::{
+        matches::GetMatchesRequest,
+        r#match::GetMatchRequest,
+    },
+    types::api::Match,
+};
+use futures::future;
+use serde_json::Value as JsonValue;
+use tokio::{
+    sync::Mutex,
+    time,
+};
+use tracing::{
+    debug,
+    warn,
+};
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::match_data_responder::typed_response_data,
+        types::{
+            aoc_ref::RefDataLists,
+            api::{
+                ProMatch,
+                ProMatchPlayer,
+                ProMatchesRequest,
+                ProMatchesResponse,
+                Server,
+            },
+            error::ResponderError,
+            InMemoryDb,
+        },
+        util,
+    },
+    PRO_MATCHES_MAX_AGE,
+    PRO_MATCHES_POLLING_INTERVAL,
+    PRO_MATCHES_POLL_COUNT,
+    STANDARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Spawn the background task that discovers matches of known players
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub fn spawn_pro_match_discovery(client: A2NClient<'static, reqwest::Client>,
+                                 root: Url,
+                                 in_memory_db: Arc<Mutex<InMemoryDb>>) {
+    let client = client.with_root(root);
+
+    tokio::spawn(async move {
+        let game = (*STANDARD.get(&"game").unwrap()).to_string();
+        let mut since = None;
+
+        loop {
+            match poll_pro_matches(&client, &game, since, &in_memory_db).await
+            {
+                Ok(cursor) => since = cursor,
+                Err(err) => {
+                    warn!("Polling for pro matches experienced an error: \
+                           {:#?}",
+                          err);
+                },
+            }
+
+            time::sleep(*PRO_MATCHES_POLLING_INTERVAL).await;
+        }
+    });
+}
+
+/// Request the matches of aoe2.net that started after `since`, keep the ones
+/// with a known player, refresh the ones that are already tracked and drop the
+/// ones that are not ongoing anymore
+///
+/// Returns the start time of the most recent match seen so far, which is the
+/// cursor for the next poll.
+///
+/// # Errors
+/// Returns an error if the matches couldn't be requested
+pub async fn poll_pro_matches(client: &A2NClient<'static, reqwest::Client>,
+                              game: &str,
+                              since: Option<i64>,
+                              in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                              -> Result<Option<i64>> {
+    let since_param = since.map(|since| since.to_string());
+
+    let req = GetMatchesRequest::builder().game(game)
+                                          .count(*PRO_MATCHES_POLL_COUNT)
+                                          .since(since_param.as_deref())
+                                          .build();
+
+    let matches = typed_response_data(client.req_get(req).await)?;
+
+    let cursor = matches.iter()
+                        .filter_map(|match_data| match_data.started)
+                        .chain(since)
+                        .max();
+
+    let tracked = in_memory_db.lock()
+                              .await
+                              .pro_matches
+                              .iter()
+                              .map(|entry| entry.key().clone())
+                              .collect::<Vec<_>>();
+
+    // A match is only returned once by the incremental poll, so the tracked
+    // ones need to be requested again to know when they are finished
+    let refreshed = future::join_all(
+        tracked.iter()
+               .map(|match_id| request_match(client, game, match_id)),
+    ).await;
+
+    let db = in_memory_db.lock().await;
+
+    for (match_id, match_data) in tracked.into_iter().zip(refreshed) {
+        match match_data {
+            Ok(match_data) => {
+                db.pro_matches.insert(match_id, match_data);
+            },
+            Err(err) => {
+                warn!("Refreshing pro match {} experienced an error: {:?}",
+                      match_id,
+                      err);
+            },
+        }
+    }
+
+    for match_data in matches {
+        if has_known_player(&match_data, &db.github_file_content, game) {
+            if let Some(match_id) = match_data.match_id.clone() {
+                db.pro_matches.insert(match_id, match_data);
+            }
+        }
+    }
+
+    let oldest_start = unix_now() -
+                       i64::try_from(PRO_MATCHES_MAX_AGE.as_secs()).unwrap_or(
+                           i64::MAX,
+                       );
+
+    db.pro_matches
+      .retain(|_, match_data| is_ongoing(match_data, oldest_start));
+
+    debug!("Tracking {} pro matches, next poll since {:?}",
+           db.pro_matches.len(),
+           cursor);
+
+    Ok(cursor)
+}
+
+/// Request the current state of the match with `match_id`
+async fn request_match(client: &A2NClient<'static, reqwest::Client>,
+                       game: &str,
+                       match_id: &str)
+                       -> Result<Match> {
+    let req = GetMatchRequest::builder().game(game)
+                                        .match_id(match_id)
+                                        .build();
+
+    typed_response_data(client.req_get(req).await)
+}
+
+/// Entry point for processing part of `live/pro_matches` endpoint
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn build_pro_matches(par: ProMatchesRequest,
+                               in_memory_db: Arc<Mutex<InMemoryDb>>)
+                               -> ProMatchesResponse {
+    let language = par.language.unwrap_or_else(|| {
+                                   (*STANDARD.get(&"language").unwrap())
+                                   .to_string()
+                               });
+
+    // Pro matches are only discovered for the standard game
+    let game = *STANDARD.get(&"game").unwrap();
+
+    let db = in_memory_db.lock().await;
+
+    let translation = db.language_strings(game, &language)
+                        .unwrap_or(JsonValue::Null);
+
+    let mut matches = db.pro_matches
+                        .iter()
+                        .map(|entry| {
+                            (entry.value().started,
+                             translate_pro_match(entry.value(),
+                                                 &translation,
+                                                 &db.github_file_content,
+                                                 game))
+                        })
+                        .collect::<Vec<_>>();
+
+    matches.sort_by(|(first, _), (second, _)| second.cmp(first));
+
+    ProMatchesResponse { matches: matches.into_iter()
+                                         .map(|(_, pro_match)| pro_match)
+                                         .collect() }
+}
+
+/// Returns `true` if any player of the `match_data` is part of the
+/// `aoc-reference-data` of the `game`
+fn has_known_player(match_data: &Match,
+                    ref_data: &RefDataLists,
+                    game: &str)
+                    -> bool {
+    match_data.players.iter().any(|player| {
+                                 player.profile_id.is_some_and(|id| {
+                                     ref_data.is_known_profile_id(
+                                         game,
+                                         &id.to_string(),
+                                     )
+                                 })
+                             })
+}
+
+/// Returns `true` if the match is not finished and started after
+/// `oldest_start`
+fn is_ongoing(match_data: &Match,
+              oldest_start: i64)
+              -> bool {
+    match_data.finished.is_none() &&
+    match_data.started
+              .is_some_and(|started| started >= oldest_start)
+}
+
+/// Current unix timestamp in seconds
+fn unix_now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+                     .ok()
+                     .and_then(|now| i64::try_from(now.as_secs()).ok())
+                     .unwrap_or_default()
+}
+
+/// Convert a match of aoe2.net into a [`ProMatch`] with names translated by
+/// the `translation` and players enriched with the `aoc-reference-data` of the
+/// `game`
+fn translate_pro_match(match_data: &Match,
+                       translation: &JsonValue,
+                       ref_data: &RefDataLists,
+                       game: &str)
+                       -> ProMatch {
+    let players = match_data.players
+                            .iter()
+                            .filter_map(|player| {
+                                let profile_id = player.profile_id?.to_string();
+                                let alias = ref_data
+                                    .lookup_player_alias_for_profile_id(
+                                        game,
+                                        &profile_id,
+                                    );
+                                let country = player.country
+                                                    .as_deref()
+                                                    .map(str::to_lowercase);
+
+                                Some(ProMatchPlayer {
+                                    name: player.name
+                                                .clone()
+                                                .unwrap_or_default(),
+                                    country: alias.as_ref()
+                                                  .map(|alias| {
+                                                      alias.country.clone()
+                                                  })
+                                                  .or(country),
+                                    alias: alias.map(|alias| alias.name),
+                                    civ: util::translate_id(translation,
+                                                            "civ",
+                                                            player.civ),
+                                    team: player.team,
+                                    rating: player.rating,
+                                    profile_id,
+                                })
+                            })
+                            .collect();
+
+    ProMatch { match_id: match_data.match_id.clone().unwrap_or_default(),
+               map: util::translate_id(translation,
+                                       "map_type",
+                                       match_data.map_type),
+               rating_type: util::translate_id(translation,
+                                               "rating_type",
+                                               match_data.rating_type),
+               server: match_data.server
+                                 .as_deref()
+                                 .map_or(Server::NotFound, Server::from),
+               started: match_data.started.map(util::iso_timestamp),
+               players }
+}
+
+#[test]
+fn only_unfinished_recent_matches_are_ongoing() {
+    let oldest_start = 1_614_000_000;
+
+    let match_data = |started: i64, finished: Option<i64>| Match {
+        started: Some(started),
+        finished,
+        ..Match::default()
+    };
+
+    assert!(is_ongoing(&match_data(oldest_start, None), oldest_start));
+    assert!(!is_ongoing(&match_data(oldest_start, Some(oldest_start + 60)),
+                        oldest_start));
+    assert!(!is_ongoing(&match_data(oldest_start - 1, None), oldest_start));
+}
+
+#[test]
+fn pro_match_is_translated_and_enriched() {
+    use crate::domain::types::testing::ref_data_lists;
+
+    let ref_data = ref_data_lists(
+        serde_json::json!([{
+            "name": "Hera",
+            "country": "ca",
+            "platforms": { "de": ["199325"] }
+        }]),
+        serde_json::json!([]),
+    );
+
+    let translation = serde_json::json!({
+        "map_type": [{"id": 9, "string": "Arabia"}],
+        "rating_type": [{"id": 2, "string": "1v1 Random Map"}],
+        "civ": [{"id": 1, "string": "Aztecs"}, {"id": 2, "string": "Berbers"}]
+    });
+
+    let match_data: Match = serde_json::from_value(serde_json::json!({
+        "match_id": "66",
+        "map_type": 9,
+        "rating_type": 2,
+        "server": "westeurope",
+        "started": 1_614_000_000,
+        "finished": null,
+        "players": [
+            {"profile_id": 199325, "name": "[aM] Hera", "country": "CA",
+             "civ": 1, "team": 1, "rating": 2400},
+            {"profile_id": 1, "name": "Someone", "country": "DE",
+             "civ": 2, "team": 2, "rating": 1000}
+        ]
+    }))
+    .unwrap();
+
+    assert!(has_known_player(&match_data, &ref_data, "aoe2de"));
+    assert!(!has_known_player(&match_data, &ref_data, "aoe3de"));
+    assert!(!has_known_player(&Match { players: vec![match_data.players[1]
+                                                         .clone()],
+                                       ..Match::default() },
+                              &ref_data,
+                              "aoe2de"));
+
+    let pro_match =
+        translate_pro_match(&match_data, &translation, &ref_data, "aoe2de");
+
+    assert_eq!((pro_match.map.as_str(), pro_match.rating_type.as_str()),
+               ("Arabia", "1v1 Random Map"));
+    assert_eq!(pro_match.server, Server::WesternEurope);
+    assert_eq!(pro_match.started.as_deref(), Some("2021-02-22T13:20:00Z"));
+    assert_eq!(pro_match.players[0].alias.as_deref(), Some("Hera"));
+    assert_eq!(pro_match.players[0].civ, "Aztecs");
+    assert_eq!(pro_match.players[1].alias, None);
+    assert_eq!(pro_match.players[1].country.as_deref(), Some("de"));
+}
@@ -0,0 +1,178 @@
+//! Turning the rating history of a player into a time series for the
+//! `player/{profile_id}/rating_history` endpoint
+
+use std::result;
+
+use aoe2net::{
+    endpoints::rating_history::GetRatingHistoryRequest,
+    types::api::RatingHistory,
+};
+use url::Url;
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        types::{
+            api::{
+                RatingHistoryRequest,
+                RatingHistoryResponse,
+                RatingPoint,
+            },
+            error::{
+                ApiRequestError,
+                ResponderError,
+            },
+        },
+        util::iso_timestamp,
+    },
+    STANDARD,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Entry point for processing part of `player/{profile_id}/rating_history`
+/// endpoint
+///
+/// # Errors
+/// Returns an error if the rating history couldn't be requested or parsed
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn build_rating_history(par: RatingHistoryRequest,
+                                  client: A2NClient<'static,
+                                                    reqwest::Client>,
+                                  root: Url)
+                                  -> Result<RatingHistoryResponse> {
+    let client = client.with_root(root);
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    // `start` 0 is the most recent game
+    let req = GetRatingHistoryRequest::builder()
+        .game(game.as_str())
+        .profile_id(par.profile_id.as_str())
+        .leaderboard_id(par.leaderboard_id)
+        .start(0)
+        .count(par.count)
+        .build();
+
+    let history = client.req_get(req)
+                        .await
+                        .map_err(ApiRequestError::from)?
+                        .data;
+
+    Ok(rating_time_series(par, history))
+}
+
+/// Convert the `history` of aoe2.net, which is ordered from the most recent
+/// game, into a [`RatingHistoryResponse`] ordered from the oldest game
+fn rating_time_series(par: RatingHistoryRequest,
+                      mut history: Vec<RatingHistory>)
+                      -> RatingHistoryResponse {
+    let current_streak = history.first().map_or(0, |latest| latest.streak);
+
+    history.reverse();
+
+    let points = history.iter()
+                        .enumerate()
+                        .map(|(index, entry)| {
+                            let delta = index.checked_sub(1)
+                                             .map_or(0, |previous| {
+                                                 entry.rating -
+                                                 history[previous].rating
+                                             });
+
+                            RatingPoint { timestamp:
+                                              iso_timestamp(entry.timestamp),
+                                          rating: entry.rating,
+                                          delta,
+                                          num_wins: entry.num_wins,
+                                          num_losses: entry.num_losses }
+                        })
+                        .collect::<Vec<_>>();
+
+    // The most recent peak and trough are the interesting ones, `max_by_key`
+    // returns the last and `min_by_key` the first of equal elements
+    let peak = points.iter().max_by_key(|point| point.rating).cloned();
+    let trough = points.iter().rev().min_by_key(|point| point.rating).cloned();
+
+    let points = match par.points {
+        Some(amount) => downsample(points, amount),
+        None => points,
+    };
+
+    RatingHistoryResponse { profile_id: par.profile_id,
+                            leaderboard_id: par.leaderboard_id,
+                            points,
+                            peak,
+                            trough,
+                            current_streak }
+}
+
+/// Pick `amount` evenly spaced points of the time series, the first and the
+/// last point are always kept
+fn downsample(points: Vec<RatingPoint>,
+              amount: usize)
+              -> Vec<RatingPoint> {
+    if amount < 2 || points.len() <= amount {
+        return points;
+    }
+
+    let last = points.len() - 1;
+
+    (0..amount).map(|step| points[step * last / (amount - 1)].clone())
+               .collect()
+}
+
+#[test]
+fn rating_history_becomes_time_series() {
+    let entry = |rating: i64, streak: i64, timestamp: i64| RatingHistory {
+        drops: 0,
+        num_losses: 10,
+        num_wins: 20,
+        rating,
+        streak,
+        timestamp,
+    };
+
+    let par = RatingHistoryRequest { profile_id: "196240".to_string(),
+                                     game: None,
+                                     leaderboard_id: 3,
+                                     count: 100,
+                                     points: None };
+
+    // aoe2.net returns the most recent game first
+    let response = rating_time_series(par,
+                                      vec![entry(2510, -1, 1_614_000_200),
+                                           entry(2530, 2, 1_614_000_100),
+                                           entry(2500, 1, 1_614_000_000),]);
+
+    assert_eq!(response.points
+                       .iter()
+                       .map(|point| (point.rating, point.delta))
+                       .collect::<Vec<_>>(),
+               vec![(2500, 0), (2530, 30), (2510, -20)]);
+    assert_eq!(response.points[0].timestamp, "2021-02-22T13:20:00Z");
+    assert_eq!(response.peak.unwrap().rating, 2530);
+    assert_eq!(response.trough.unwrap().rating, 2500);
+    assert_eq!(response.current_streak, -1);
+}
+
+#[test]
+fn downsampling_keeps_first_and_last_point() {
+    let points = (0..10).map(|rating| RatingPoint { rating,
+                                                    ..RatingPoint::default() })
+                        .collect::<Vec<_>>();
+
+    let ratings = |points: Vec<RatingPoint>| {
+        points.iter().map(|point| point.rating).collect::<Vec<_>>()
+    };
+
+    assert_eq!(ratings(downsample(points.clone(), 4)), vec![0, 3, 6, 9]);
+    assert_eq!(ratings(downsample(points.clone(), 2)), vec![0, 9]);
+    assert_eq!(ratings(downsample(points, 20)).len(), 10);
+}
@@ -0,0 +1,301 @@
+//! Subscriptions on the `last_match` of a player
+//!
+//! A subscription sends the initial [`MatchInfoResult`] to the client and
+//! afterwards polls the `last_match` endpoint of aoe2net in the background.
+//! Whenever the match changes (new `match_id` or the match has been finished)
+//! the freshly processed [`MatchInfoResult`] gets diffed against the one the
+//! client already has and only the resulting `JSON Patch` (RFC 6902) gets
+//! pushed to the client.
+//!
+//! Every message is wrapped into a [`SubscriptionMessage`], so the client can
+//! tell the initial snapshot and the patches apart.
+//!
+//! [`MatchInfoResult`]: crate::domain::types::api::MatchInfoResult
+
+use std::sync::Arc;
+
+use aoe2net::{
+    endpoints::last_match::GetLastMatchRequest,
+    types::api::Match,
+};
+use futures::{
+    SinkExt,
+    StreamExt,
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use tracing::{
+    debug,
+    warn,
+};
+use url::Url;
+use warp::ws::{
+    Message,
+    WebSocket,
+};
+
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::{
+            build_result_coalesced,
+            match_data_responder::typed_response_data,
+            player_lookup::resolve_profile_id_for_name,
+        },
+        json_patch,
+        types::{
+            api::{
+                IdType,
+                MatchInfoRequest,
+            },
+            error::ResponderError,
+            InMemoryDb,
+        },
+    },
+    STANDARD,
+    SUBSCRIPTION_POLLING_INTERVAL,
+};
+
+/// Envelope of a message sent to a subscriber, serialized as
+/// `{"type": "snapshot" | "patch", "data": ...}`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum SubscriptionMessage {
+    /// The complete [`MatchInfoResult`], sent once after subscribing
+    ///
+    /// [`MatchInfoResult`]: crate::domain::types::api::MatchInfoResult
+    Snapshot(JsonValue),
+    /// `JSON Patch` to apply to the state of the client
+    Patch(JsonValue),
+}
+
+/// The parts of a `last_match` response we use to detect a change of the
+/// match a player is in
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MatchFingerprint {
+    /// `match_id` of the last match
+    match_id: String,
+    /// Finished time of the last match, `None` while it is running
+    finished: Option<i64>,
+}
+
+impl MatchFingerprint {
+    /// Create a [`MatchFingerprint`] from the `last_match` of a player
+    #[must_use]
+    pub fn with_last_match(last_match: &Match) -> Self {
+        Self { match_id: last_match.match_id.clone().unwrap_or_default(),
+               finished: last_match.finished }
+    }
+}
+
+/// Poll the `last_match` endpoint for the requested player and return the
+/// current [`MatchFingerprint`]
+///
+/// # Errors
+/// Returns an error if the request failed or the response was empty
+///
+/// # Panics
+/// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+/// returning None
+pub async fn poll_last_match(par: &MatchInfoRequest,
+                             client: &A2NClient<'static, reqwest::Client>)
+                             -> Result<MatchFingerprint, ResponderError> {
+    let game: String =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+
+    let req = match par.id_type {
+        IdType::SteamId => GetLastMatchRequest::builder()
+            .game(game.as_str())
+            .steam_id(par.id_number.as_str())
+            .build(),
+        IdType::ProfileId => GetLastMatchRequest::builder()
+            .game(game.as_str())
+            .profile_id(par.id_number.as_str())
+            .build(),
+        _ => {
+            return Err(ResponderError::InvalidIdType(
+                std::borrow::Cow::Borrowed(par.id_type.as_str()),
+            ))
+        }
+    };
+
+    typed_response_data(client.req_get(req).await)?
+        .last_match
+        .map(|last_match| MatchFingerprint::with_last_match(&last_match))
+        .ok_or(ResponderError::LastMatchNotFound)
+}
+
+/// Turn a [`MatchInfoRequest`] for a name into one for the `profile_id` of
+/// that player, other requests are returned unchanged
+async fn resolve_name(par: MatchInfoRequest,
+                      client: &A2NClient<'static, reqwest::Client>,
+                      in_memory_db: &Arc<Mutex<InMemoryDb>>)
+                      -> MatchInfoRequest {
+    if par.id_type != IdType::Name {
+        return par;
+    }
+
+    let game =
+        par.game
+           .clone()
+           .unwrap_or_else(|| (*STANDARD.get(&"game").unwrap()).to_string());
+    let ref_data = in_memory_db.lock().await.github_file_content.clone();
+
+    match resolve_profile_id_for_name(&par.id_number, &game, client, &ref_data)
+        .await
+    {
+        Ok(profile_id) => MatchInfoRequest { id_type: IdType::ProfileId,
+                                             id_number: profile_id,
+                                             ..par },
+        Err(err) => {
+            warn!("Resolving name {:?} for subscription failed with {:?}",
+                  par.id_number, err);
+            par
+        },
+    }
+}
+
+/// Serve a single subscription on an upgraded [`WebSocket`] until the client
+/// disconnects
+///
+/// # Panics
+/// Panics if the hardcoded aoe2net API root is not a valid [`Url`]
+pub async fn serve_subscription(socket: WebSocket,
+                                par: MatchInfoRequest,
+                                client: A2NClient<'static, reqwest::Client>,
+                                in_memory_db: Arc<Mutex<InMemoryDb>>) {
+    let (mut tx, mut rx) = socket.split();
+
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    // Names are resolved once, so we don't need to search for them on every
+    // poll. If that fails, the initial result contains the error
+    let par = resolve_name(par, &client, &in_memory_db).await;
+
+    let mut fingerprint = poll_last_match(&par, &client).await.ok();
+
+    let initial = build_result_coalesced(par.clone(),
+                                         client.clone(),
+                                         root.clone(),
+                                         in_memory_db.clone()).await;
+
+    // The state the client currently has, used to compute the deltas
+    let mut current_state =
+        serde_json::to_value(&initial).expect("MatchInfoResult should \
+                                               serialize to JSON");
+
+    let snapshot = SubscriptionMessage::Snapshot(current_state.clone());
+
+    if send_message(&mut tx, snapshot).await.is_err() {
+        return;
+    }
+
+    let mut interval = tokio::time::interval(*SUBSCRIPTION_POLLING_INTERVAL);
+    // The first tick completes immediately
+    interval.tick().await;
+
+    loop {
+        tokio::select! {
+            incoming = rx.next() => {
+                match incoming {
+                    // We don't expect any messages from the client, they are
+                    // only used to keep the connection alive
+                    Some(Ok(msg)) if !msg.is_close() => {},
+                    _ => {
+                        debug!("Subscription for {:?}:{:?} closed",
+                               par.id_type, par.id_number);
+                        break;
+                    }
+                }
+            }
+            _ = interval.tick() => {
+                let current = match poll_last_match(&par, &client).await {
+                    Ok(current) => current,
+                    Err(err) => {
+                        warn!("Polling last_match for subscription failed with {:?}", err);
+                        continue;
+                    }
+                };
+
+                if fingerprint.as_ref() == Some(&current) {
+                    continue;
+                }
+
+                debug!("Match changed for {:?}:{:?}, pushing delta of MatchInfoResult",
+                       par.id_type, par.id_number);
+                fingerprint = Some(current);
+
+                let result = build_result_coalesced(par.clone(),
+                                                    client.clone(),
+                                                    root.clone(),
+                                                    in_memory_db.clone()).await;
+
+                let new_state =
+                    serde_json::to_value(&result).expect("MatchInfoResult \
+                                                          should serialize \
+                                                          to JSON");
+
+                let patch = json_patch::diff(&current_state, &new_state);
+                current_state = new_state;
+
+                if patch.is_empty() {
+                    continue;
+                }
+
+                let patch =
+                    serde_json::to_value(&patch).expect("JSON Patch should \
+                                                         serialize to JSON");
+
+                if send_message(&mut tx, SubscriptionMessage::Patch(patch))
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Send a [`SubscriptionMessage`] to the client
+async fn send_message<S>(tx: &mut S,
+                         message: SubscriptionMessage)
+                         -> Result<(), warp::Error>
+    where S: SinkExt<Message, Error = warp::Error> + Unpin,
+{
+    let text = serde_json::to_string(&message).expect("SubscriptionMessage \
+                                                       should serialize to \
+                                                       JSON");
+
+    tx.send(Message::text(text)).await.map_err(|err| {
+                                      debug!("Sending to subscriber failed \
+                                              with {:?}",
+                                             err);
+                                      err
+                                  })
+}
+
+#[test]
+fn messages_are_wrapped_into_an_envelope() {
+    use serde_json::json;
+
+    let patch = json!([{"op": "remove", "path": "/error_message"}]);
+
+    assert_eq!(serde_json::to_value(SubscriptionMessage::Patch(patch.clone()))
+                   .unwrap(),
+               json!({"type": "patch", "data": patch}));
+    assert_eq!(serde_json::to_value(SubscriptionMessage::Snapshot(json!({})))
+                   .unwrap(),
+               json!({"type": "snapshot", "data": {}}));
+}
+
+#[test]
+fn every_poll_gets_a_fresh_last_match() {
+    let ttl = *crate::AOE2NET_CACHE_TTLS.get("player/lastmatch").unwrap();
+
+    assert!(ttl < *SUBSCRIPTION_POLLING_INTERVAL);
+}
@@ -0,0 +1,228 @@
+//! Computing deltas between two `JSON` documents as an
+//! [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902) `JSON Patch`
+//!
+//! We use that to only send the changes of a [`MatchInfoResult`] to subscribed
+//! clients instead of the whole document.
+//!
+//! [`MatchInfoResult`]: crate::domain::types::api::MatchInfoResult
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+#[cfg(test)]
+use serde_json::json;
+use serde_json::Value as JsonValue;
+
+/// A single operation of a `JSON Patch`
+///
+/// We only create `add`, `remove` and `replace` operations, the other
+/// operations of RFC 6902 (`move`, `copy`, `test`) are not needed to describe
+/// a delta.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Add `value` at `path`
+    Add {
+        /// `JSON Pointer` to the location
+        path: String,
+        /// Value to add
+        value: JsonValue,
+    },
+    /// Remove the value at `path`
+    Remove {
+        /// `JSON Pointer` to the location
+        path: String,
+    },
+    /// Replace the value at `path` with `value`
+    Replace {
+        /// `JSON Pointer` to the location
+        path: String,
+        /// New value
+        value: JsonValue,
+    },
+}
+
+/// A `JSON Patch` document, which is an ordered list of [`PatchOperation`]s
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Patch(pub Vec<PatchOperation>);
+
+impl Patch {
+    /// Returns `true` if there are no differences
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Compute the [`Patch`] that transforms `old` into `new`
+#[must_use]
+pub fn diff(old: &JsonValue,
+            new: &JsonValue)
+            -> Patch {
+    let mut operations = Vec::new();
+    diff_into(old, new, "", &mut operations);
+    Patch(operations)
+}
+
+/// Escape a single reference token of a `JSON Pointer` (RFC 6901)
+fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Recursively walk both values and push the differences to `operations`
+fn diff_into(old: &JsonValue,
+             new: &JsonValue,
+             path: &str,
+             operations: &mut Vec<PatchOperation>) {
+    match (old, new) {
+        (JsonValue::Object(old_map), JsonValue::Object(new_map)) => {
+            for (key, old_value) in old_map {
+                let child = format!("{}/{}", path, escape_token(key));
+                match new_map.get(key) {
+                    Some(new_value) => {
+                        diff_into(old_value, new_value, &child, operations);
+                    },
+                    None => {
+                        operations.push(PatchOperation::Remove { path: child });
+                    },
+                }
+            }
+            for (key, new_value) in new_map {
+                if !old_map.contains_key(key) {
+                    operations.push(PatchOperation::Add {
+                        path: format!("{}/{}", path, escape_token(key)),
+                        value: new_value.clone(),
+                    });
+                }
+            }
+        },
+        (JsonValue::Array(old_vec), JsonValue::Array(new_vec)) => {
+            let common = old_vec.len().min(new_vec.len());
+            for (idx, (old_value, new_value)) in
+                old_vec.iter().zip(new_vec.iter()).enumerate()
+            {
+                diff_into(old_value,
+                          new_value,
+                          &format!("{path}/{idx}"),
+                          operations);
+            }
+            // Remove from the back, so the indices of the remaining elements
+            // stay valid while applying the patch
+            for idx in (common .. old_vec.len()).rev() {
+                let child = format!("{path}/{idx}");
+                operations.push(PatchOperation::Remove { path: child });
+            }
+            for (idx, new_value) in new_vec.iter().enumerate().skip(common) {
+                let child = format!("{path}/{idx}");
+                let value = new_value.clone();
+                operations.push(PatchOperation::Add { path: child,
+                                                      value });
+            }
+        },
+        _ => {
+            if old != new {
+                let (path, value) = (path.to_string(), new.clone());
+                operations.push(PatchOperation::Replace { path,
+                                                          value });
+            }
+        },
+    }
+}
+
+#[test]
+fn diff_of_equal_documents_is_empty() {
+    let doc = json!({"match_info": {"teams": [{"team_number": 1}]}});
+    assert!(diff(&doc, &doc).is_empty());
+}
+
+#[cfg(test)]
+fn replace(path: &str,
+           value: JsonValue)
+           -> PatchOperation {
+    PatchOperation::Replace { path: path.to_string(),
+                              value }
+}
+
+#[cfg(test)]
+fn remove(path: &str) -> PatchOperation {
+    PatchOperation::Remove { path: path.to_string() }
+}
+
+#[test]
+fn diff_detects_rating_changes() {
+    let old = json!({
+        "match_info": {
+            "teams": [{"players": [{
+                "name": "Hoang",
+                "rating": {"mmr": 2223, "wins": 1905, "streak": -1}
+            }]}]
+        }
+    });
+    let new = json!({
+        "match_info": {
+            "teams": [{"players": [{
+                "name": "Hoang",
+                "rating": {"mmr": 2239, "wins": 1906, "streak": 1}
+            }]}]
+        }
+    });
+
+    let patch = diff(&old, &new);
+    let rating = "/match_info/teams/0/players/0/rating";
+
+    assert_eq!(patch,
+               Patch(vec![replace(&format!("{rating}/mmr"), json!(2239)),
+                          replace(&format!("{rating}/streak"), json!(1)),
+                          replace(&format!("{rating}/wins"), json!(1906)),]));
+}
+
+#[test]
+fn diff_detects_teams_reordering() {
+    let team_1 = json!({"team_number": 1, "players": [{"name": "Hoang"}]});
+    let team_2 = json!({"team_number": 2, "players": [{"name": "Valas"}]});
+
+    let old = json!({"match_info": {"teams": [team_1, team_2]}});
+    let new = json!({"match_info": {"teams": [team_2, team_1]}});
+
+    let patch = diff(&old, &new);
+
+    assert_eq!(patch,
+               Patch(vec![replace("/match_info/teams/0/players/0/name",
+                                  json!("Valas")),
+                          replace("/match_info/teams/0/team_number",
+                                  json!(2)),
+                          replace("/match_info/teams/1/players/0/name",
+                                  json!("Hoang")),
+                          replace("/match_info/teams/1/team_number",
+                                  json!(1)),]));
+}
+
+#[test]
+fn diff_handles_added_and_removed_elements() {
+    let old = json!({"teams": [1, 2, 3], "error_message": "gone", "a/b": 1});
+    let new = json!({"teams": [1], "match_info": {}, "a/b": 2});
+
+    let patch = diff(&old, &new);
+
+    assert_eq!(patch,
+               Patch(vec![replace("/a~1b", json!(2)),
+                          remove("/error_message"),
+                          remove("/teams/2"),
+                          remove("/teams/1"),
+                          PatchOperation::Add { path:
+                                                    "/match_info".to_string(),
+                                                value: json!({}) },]));
+}
+
+#[test]
+fn patch_serializes_to_rfc6902() {
+    let patch = Patch(vec![remove("/teams/1"), replace("/teams/0", json!(1))]);
+
+    assert_eq!(serde_json::to_value(&patch).unwrap(),
+               json!([
+                   {"op": "remove", "path": "/teams/1"},
+                   {"op": "replace", "path": "/teams/0", "value": 1}
+               ]));
+}
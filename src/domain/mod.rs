@@ -2,5 +2,7 @@
 
 pub mod api_handler;
 pub mod data_processing;
+pub mod json_patch;
+pub mod provider;
 pub mod types;
 pub mod util;
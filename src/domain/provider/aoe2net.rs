@@ -0,0 +1,405 @@
+//! [`MatchDataProvider`] for the API of aoe2.net
+//!
+//! The translations are taken from the languages of each game preloaded into
+//! the [`InMemoryDb`], everything else is requested from aoe2.net and
+//! converted into the provider-neutral types.
+
+use std::{
+    convert::TryFrom,
+    path::PathBuf,
+    result,
+    sync::Arc,
+};
+
+use aoe2net::{
+    endpoints::{
+        last_match::GetLastMatchRequest,
+        leaderboard::GetLeaderboardRequest,
+        r#match::GetMatchRequest,
+        rating_history::GetRatingHistoryRequest,
+        Raw,
+    },
+    types::{
+        api::{
+            LastMatch,
+            LeaderboardEntry,
+            Match,
+            MatchPlayer,
+            RatingHistory,
+        },
+        helper::Aoe2netStringObj,
+    },
+};
+use futures::{
+    future::BoxFuture,
+    FutureExt,
+};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::MatchDataProvider;
+use crate::domain::{
+    api_handler::client::A2NClient,
+    data_processing::{
+        match_data_responder::{
+            last_match_data,
+            typed_response_data,
+        },
+        player_lookup::resolve_profile_id_for_name,
+    },
+    types::{
+        aoc_ref::RefDataLists,
+        api::IdType,
+        error::ResponderError,
+        match_data::{
+            LeaderboardStanding,
+            MatchData,
+            MatchPlayerData,
+            PlayerLastMatch,
+            PlayerRating,
+            Translations,
+        },
+        File,
+        FileFormat,
+        InMemoryDb,
+    },
+    util,
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Match data of aoe2.net
+#[derive(Clone, Debug)]
+pub struct Aoe2NetProvider {
+    /// Client to request aoe2.net with, the API root is already set
+    client: A2NClient<'static, reqwest::Client>,
+    /// Holds the preloaded translations
+    in_memory_db: Arc<Mutex<InMemoryDb>>,
+    /// Folder to export the raw responses to, used to create test cases
+    export_path: Option<PathBuf>,
+}
+
+impl Aoe2NetProvider {
+    /// Create a new [`Aoe2NetProvider`]
+    ///
+    /// # Arguments
+    /// * `client` - an [`A2NClient`] with the API root to request set
+    /// * `in_memory_db` - holds [`InMemoryDb`] wrapped inside an [`Arc`] with a
+    ///   [`Mutex`] due to threading
+    #[must_use]
+    pub fn new(client: A2NClient<'static, reqwest::Client>,
+               in_memory_db: Arc<Mutex<InMemoryDb>>)
+               -> Self {
+        Self { client,
+               in_memory_db,
+               export_path: None }
+    }
+
+    /// Export the raw responses of aoe2.net to `export_path`, if it is set
+    #[must_use]
+    pub fn with_export_path(mut self,
+                            export_path: Option<PathBuf>)
+                            -> Self {
+        self.export_path = export_path;
+        self
+    }
+
+    /// Write a `response` as `name` into the `folder` of the export path, if
+    /// there is one
+    ///
+    /// # Panics
+    /// Will panic if the `response` can't be serialized or written
+    fn export<T>(&self,
+                 folder: Option<&str>,
+                 name: &str,
+                 response: &T)
+        where T: Serialize,
+    {
+        if let Some(mut path) = self.export_path.clone() {
+            path.push("aoe2net");
+            if let Some(folder) = folder {
+                path.push(folder);
+            }
+
+            util::export_to_json(&File { name: name.to_string(),
+                                         ext: FileFormat::Json },
+                                 &path,
+                                 &serde_json::to_value(response)
+                                     .expect("Serialising the response \
+                                              failed."));
+        }
+    }
+}
+
+impl MatchDataProvider for Aoe2NetProvider {
+    fn last_match<'a>(&'a self,
+                      game: &'a str,
+                      id_type: IdType,
+                      id_number: &'a str)
+                      -> BoxFuture<'a, Result<PlayerLastMatch>> {
+        async move {
+            let req = match id_type {
+                IdType::SteamId => {
+                    GetLastMatchRequest::builder().game(game)
+                                                  .steam_id(id_number)
+                                                  .build()
+                },
+                IdType::ProfileId => {
+                    GetLastMatchRequest::builder().game(game)
+                                                  .profile_id(id_number)
+                                                  .build()
+                },
+                _ => {
+                    return Err(ResponderError::InvalidIdType(
+                        id_type.as_str().into(),
+                    ))
+                },
+            };
+
+            let response =
+                last_match_data(self.client.req_get(Raw(req)).await)?;
+            self.export(None, "last_match", &response);
+
+            let LastMatch { profile_id,
+                            last_match,
+                            .. } = serde_json::from_value(response)?;
+            let last_match =
+                last_match.ok_or(ResponderError::LastMatchNotFound)?;
+
+            Ok(PlayerLastMatch { profile_id: profile_id.map(|id| {
+                                                           id.to_string()
+                                                       }),
+                                 last_match: match_data(last_match) })
+        }.boxed()
+    }
+
+    fn match_by_id<'a>(&'a self,
+                       game: &'a str,
+                       id_type: IdType,
+                       id_number: &'a str)
+                       -> BoxFuture<'a, Result<MatchData>> {
+        async move {
+            let req = match id_type {
+                IdType::MatchId => {
+                    GetMatchRequest::builder().game(game)
+                                              .match_id(id_number)
+                                              .build()
+                },
+                IdType::MatchUuid => {
+                    GetMatchRequest::builder().game(game)
+                                              .uuid(Uuid::parse_str(id_number)?)
+                                              .build()
+                },
+                _ => {
+                    return Err(ResponderError::InvalidIdType(
+                        id_type.as_str().into(),
+                    ))
+                },
+            };
+
+            let response =
+                last_match_data(self.client.req_get(Raw(req)).await)?;
+            self.export(None, "match", &response);
+
+            Ok(match_data(serde_json::from_value::<Match>(response)?))
+        }.boxed()
+    }
+
+    fn ratings<'a>(&'a self,
+                   game: &'a str,
+                   leaderboard_id: i32,
+                   profile_id: &'a str)
+                   -> BoxFuture<'a, Result<Option<PlayerRating>>> {
+        async move {
+            let req_rating = GetRatingHistoryRequest::builder()
+                .game(game)
+                .profile_id(profile_id)
+                .leaderboard_id(leaderboard_id)
+                .build();
+
+            let req_lead = GetLeaderboardRequest::builder()
+                .game(game)
+                .profile_id(profile_id)
+                .leaderboard_id(leaderboard_id)
+                .build();
+
+            let (rating_response, leaderboard_response) =
+                futures::join!(self.client.req_get(req_rating),
+                               self.client.req_get(req_lead));
+
+            let rating_history = typed_response_data(rating_response)?;
+            let leaderboard = typed_response_data(leaderboard_response)?;
+
+            self.export(Some("rating_history"), profile_id, &rating_history);
+            self.export(Some("leaderboard"), profile_id, &leaderboard);
+
+            Ok(player_rating(&rating_history, leaderboard.leaderboard.first()))
+        }.boxed()
+    }
+
+    fn translations<'a>(&'a self,
+                        game: &'a str,
+                        language: &'a str)
+                        -> BoxFuture<'a, Result<Translations>> {
+        async move {
+            self.in_memory_db
+                .lock()
+                .await
+                .language_strings(game, language)
+                .map(|strings| translations(&strings))
+                .ok_or_else(|| {
+                    ResponderError::NotFound(format!("translations for \
+                                                      {language} of {game}"))
+                })
+        }.boxed()
+    }
+
+    fn profile_id_for_name<'a>(&'a self,
+                               game: &'a str,
+                               name: &'a str,
+                               ref_data: &'a RefDataLists)
+                               -> BoxFuture<'a, Result<String>> {
+        resolve_profile_id_for_name(name, game, &self.client, ref_data).boxed()
+    }
+}
+
+/// Convert the current entry of a `rating_history` response and the entry of
+/// the player on the leaderboard into a [`PlayerRating`], `None` if the player
+/// has no rating history
+pub(crate) fn player_rating(rating_history: &[RatingHistory],
+                            leaderboard_entry: Option<&LeaderboardEntry>)
+                            -> Option<PlayerRating> {
+    let standing = leaderboard_entry.map(|entry| {
+        LeaderboardStanding {
+            rank: entry.rank
+                       .and_then(|rank| u64::try_from(rank).ok())
+                       .unwrap_or_default(),
+            highest_rating: entry.highest_rating
+                                 .and_then(|rating| u32::try_from(rating).ok())
+                                 .unwrap_or_default(),
+            country: entry.country.as_deref().map(str::to_lowercase),
+        }
+    });
+
+    rating_history.first().map(|current| {
+                              PlayerRating {
+                                  rating: u32::try_from(current.rating)
+                                      .unwrap_or_default(),
+                                  wins: u64::try_from(current.num_wins)
+                                      .unwrap_or_default(),
+                                  losses: u64::try_from(current.num_losses)
+                                      .unwrap_or_default(),
+                                  streak: i32::try_from(current.streak)
+                                      .unwrap_or_default(),
+                                  standing,
+                              }
+                          })
+}
+
+/// Convert a match of aoe2.net into a [`MatchData`]
+fn match_data(aoe2net_match: Match) -> MatchData {
+    let id = |id: Option<i64>| id.and_then(|id| usize::try_from(id).ok());
+
+    MatchData { match_id: aoe2net_match.match_id,
+                leaderboard_id: aoe2net_match.leaderboard_id
+                                             .and_then(|id| {
+                                                 i32::try_from(id).ok()
+                                             }),
+                rating_type: id(aoe2net_match.rating_type),
+                map_type: id(aoe2net_match.map_type),
+                game_type: id(aoe2net_match.game_type),
+                server: aoe2net_match.server,
+                started: aoe2net_match.started,
+                finished: aoe2net_match.finished,
+                players: aoe2net_match.players
+                                      .into_iter()
+                                      .map(match_player)
+                                      .collect() }
+}
+
+/// Convert a player of a match of aoe2.net into a [`MatchPlayerData`]
+fn match_player(player: MatchPlayer) -> MatchPlayerData {
+    MatchPlayerData { profile_id: player.profile_id
+                                        .map(|id| id.to_string())
+                                        .unwrap_or_default(),
+                      name: player.name.unwrap_or_default(),
+                      civ: player.civ.and_then(|id| usize::try_from(id).ok()),
+                      color: player.color.unwrap_or_default(),
+                      slot: player.slot.unwrap_or_default(),
+                      team: player.team.unwrap_or(-1) }
+}
+
+/// Convert the response of the `strings` endpoint into [`Translations`]
+fn translations(strings: &JsonValue) -> Translations {
+    let Some(categories) = strings.as_object() else {
+        return Translations::default();
+    };
+
+    let names = |names: Vec<Aoe2netStringObj>| {
+        names.into_iter()
+             .map(|name| (*name.id(), name.string().clone()))
+             .collect()
+    };
+
+    Translations(categories.iter()
+                           .filter_map(|(category, category_names)| {
+                               serde_json::from_value(category_names.clone())
+                                   .ok()
+                                   .map(|category_names| {
+                                       (category.clone(), names(category_names))
+                                   })
+                           })
+                           .collect())
+}
+
+#[test]
+fn aoe2net_responses_are_converted() {
+    let last_match: LastMatch = serde_json::from_value(serde_json::json!({
+        "profile_id": 196240,
+        "last_match": {
+            "match_id": "75984064",
+            "leaderboard_id": 3,
+            "rating_type": 2,
+            "map_type": 9,
+            "server": "ukwest",
+            "finished": null,
+            "players": [{"profile_id": 196240, "name": "GL.TheViper",
+                         "civ": 23, "color": 4, "slot": 1, "team": null}]
+        }
+    }))
+    .unwrap();
+
+    let converted = match_data(last_match.last_match.unwrap());
+    assert_eq!(converted.leaderboard_id, Some(3));
+    assert_eq!(converted.finished, None);
+    assert_eq!(converted.players[0],
+               MatchPlayerData { profile_id: "196240".to_string(),
+                                 name: "GL.TheViper".to_string(),
+                                 civ: Some(23),
+                                 color: 4,
+                                 slot: 1,
+                                 team: -1 });
+
+    let rating_history = [RatingHistory { rating: 2415,
+                                          num_wins: 556,
+                                          num_losses: 276,
+                                          streak: 3,
+                                          ..RatingHistory::default() }];
+    let entry = LeaderboardEntry { rank: Some(18),
+                                   highest_rating: Some(2510),
+                                   country: Some("NO".to_string()),
+                                   ..LeaderboardEntry::default() };
+
+    let rating = player_rating(&rating_history, Some(&entry)).unwrap();
+    assert_eq!(rating.standing.unwrap().country.as_deref(), Some("no"));
+    assert_eq!(player_rating(&[], None), None);
+
+    let translated = translations(&serde_json::json!({
+        "language": "en",
+        "civ": [{"id": 23, "string": "Sicilians"}]
+    }));
+    assert_eq!(translated.lookup("civ", 23), Some("Sicilians"));
+    assert_eq!(translated.lookup("language", 0), None);
+}
@@ -0,0 +1,131 @@
+//! [`MatchDataProvider`] serving fixed data, to test the processing stage
+//! without any upstream API
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::BufReader,
+    path::Path,
+    result,
+};
+
+use futures::{
+    future::{
+        self,
+        BoxFuture,
+    },
+    FutureExt,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::MatchDataProvider;
+use crate::domain::types::{
+    aoc_ref::RefDataLists,
+    api::IdType,
+    error::ResponderError,
+    match_data::{
+        MatchData,
+        PlayerLastMatch,
+        PlayerRating,
+        Translations,
+    },
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// Match data read from a fixture
+///
+/// The data is the same for every game, ratings are the same for every
+/// leaderboard.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FixtureProvider {
+    /// Last matches keyed by the `steam_id` or `profile_id` of the player
+    #[serde(default)]
+    pub last_matches: HashMap<String, PlayerLastMatch>,
+    /// Matches keyed by their `match_id` or `match_uuid`
+    #[serde(default)]
+    pub matches: HashMap<String, MatchData>,
+    /// Ratings keyed by the `profile_id` of the player
+    #[serde(default)]
+    pub ratings: HashMap<String, PlayerRating>,
+    /// Translations keyed by their language
+    #[serde(default)]
+    pub translations: HashMap<String, Translations>,
+    /// `profile_id`s keyed by the lowercase name of the player
+    #[serde(default)]
+    pub names: HashMap<String, String>,
+}
+
+impl FixtureProvider {
+    /// Create a [`FixtureProvider`] from a parsed `RON` file
+    ///
+    /// # Panics
+    /// Panics when the file can not be opened or parsed
+    #[must_use]
+    pub fn with_file<P>(path: P) -> Self
+        where P: AsRef<Path>, {
+        let file = fs::File::open(path).expect("file should open read only");
+        ron::de::from_reader::<_, Self>(BufReader::new(file)).unwrap()
+    }
+}
+
+impl MatchDataProvider for FixtureProvider {
+    fn last_match<'a>(&'a self,
+                      _game: &'a str,
+                      _id_type: IdType,
+                      id_number: &'a str)
+                      -> BoxFuture<'a, Result<PlayerLastMatch>> {
+        future::ready(self.last_matches
+                          .get(id_number)
+                          .cloned()
+                          .ok_or(ResponderError::LastMatchNotFound)).boxed()
+    }
+
+    fn match_by_id<'a>(&'a self,
+                       _game: &'a str,
+                       _id_type: IdType,
+                       id_number: &'a str)
+                       -> BoxFuture<'a, Result<MatchData>> {
+        future::ready(self.matches
+                          .get(id_number)
+                          .cloned()
+                          .ok_or(ResponderError::LastMatchNotFound)).boxed()
+    }
+
+    fn ratings<'a>(&'a self,
+                   _game: &'a str,
+                   _leaderboard_id: i32,
+                   profile_id: &'a str)
+                   -> BoxFuture<'a, Result<Option<PlayerRating>>> {
+        future::ready(Ok(self.ratings.get(profile_id).cloned())).boxed()
+    }
+
+    fn translations<'a>(&'a self,
+                        _game: &'a str,
+                        language: &'a str)
+                        -> BoxFuture<'a, Result<Translations>> {
+        future::ready(self.translations.get(language).cloned().ok_or_else(
+                          || {
+                              ResponderError::NotFound(format!(
+                                  "translations for {language}"
+                              ))
+                          },
+                      )).boxed()
+    }
+
+    fn profile_id_for_name<'a>(&'a self,
+                               _game: &'a str,
+                               name: &'a str,
+                               _ref_data: &'a RefDataLists)
+                               -> BoxFuture<'a, Result<String>> {
+        future::ready(self.names
+                          .get(&name.trim().to_lowercase())
+                          .cloned()
+                          .ok_or_else(|| {
+                              ResponderError::PlayerNotFound(name.to_string())
+                          })).boxed()
+    }
+}
@@ -0,0 +1,91 @@
+//! Providers of the match data the `matchinfo` endpoint is built from
+//!
+//! The processing stage only knows the provider-neutral types of
+//! [`crate::domain::types::match_data`], so switching to another upstream API
+//! only needs another implementation of [`MatchDataProvider`].
+
+pub mod aoe2net;
+pub mod fixture;
+
+use std::result;
+
+use futures::future::BoxFuture;
+
+pub use self::{
+    aoe2net::Aoe2NetProvider,
+    fixture::FixtureProvider,
+};
+use crate::domain::types::{
+    aoc_ref::RefDataLists,
+    api::IdType,
+    error::ResponderError,
+    match_data::{
+        MatchData,
+        PlayerLastMatch,
+        PlayerRating,
+        Translations,
+    },
+};
+
+type Result<T> = result::Result<T, ResponderError>;
+
+/// A source of matches, ratings and translations
+pub trait MatchDataProvider: Send + Sync {
+    /// Last match of the player with the `id_number` of `id_type`, which is
+    /// either [`IdType::SteamId`] or [`IdType::ProfileId`]
+    ///
+    /// # Errors
+    /// Returns [`ResponderError::LastMatchNotFound`] if the player is unknown
+    /// to the provider
+    fn last_match<'a>(&'a self,
+                      game: &'a str,
+                      id_type: IdType,
+                      id_number: &'a str)
+                      -> BoxFuture<'a, Result<PlayerLastMatch>>;
+
+    /// Match with the `id_number` of `id_type`, which is either
+    /// [`IdType::MatchId`] or [`IdType::MatchUuid`]
+    ///
+    /// # Errors
+    /// Returns [`ResponderError::LastMatchNotFound`] if the match is unknown
+    /// to the provider
+    fn match_by_id<'a>(&'a self,
+                       game: &'a str,
+                       id_type: IdType,
+                       id_number: &'a str)
+                       -> BoxFuture<'a, Result<MatchData>>;
+
+    /// Rating of the player with `profile_id` on the leaderboard with
+    /// `leaderboard_id`, `None` if the player has never played on it
+    ///
+    /// # Errors
+    /// Returns an error if the rating couldn't be requested
+    fn ratings<'a>(&'a self,
+                   game: &'a str,
+                   leaderboard_id: i32,
+                   profile_id: &'a str)
+                   -> BoxFuture<'a, Result<Option<PlayerRating>>>;
+
+    /// Translations of the IDs in the matches of the provider into
+    /// `language`
+    ///
+    /// # Errors
+    /// Returns an error if there are no translations for `language`
+    fn translations<'a>(&'a self,
+                        game: &'a str,
+                        language: &'a str)
+                        -> BoxFuture<'a, Result<Translations>>;
+
+    /// Resolve the in-game `name` of a player to its `profile_id`, the
+    /// `ref_data` knows the names of well-known players
+    ///
+    /// # Errors
+    /// Returns [`ResponderError::PlayerNotFound`] if no player has that name
+    /// and [`ResponderError::AmbiguousPlayerName`] if more than one player
+    /// has it
+    fn profile_id_for_name<'a>(&'a self,
+                               game: &'a str,
+                               name: &'a str,
+                               ref_data: &'a RefDataLists)
+                               -> BoxFuture<'a, Result<String>>;
+}
@@ -1,12 +1,19 @@
 pub mod platforms;
 pub mod players;
+pub mod search;
 pub mod teams;
 
+use std::collections::HashMap;
+
 use ::serde::Serialize;
 use dashmap::DashMap;
 use tracing::debug;
 
-use crate::domain::types::error::IndexingError;
+use self::search::PlayerSearchIndex;
+use crate::{
+    domain::types::error::IndexingError,
+    GAME_STRINGS,
+};
 
 /// A list of Players
 pub type AoePlayers = Vec<players::Player>;
@@ -26,8 +33,14 @@ pub type PositionInAoePlayers = usize;
 pub struct RefDataLists {
     /// from `players.yaml`
     pub players: AoePlayers,
-    /// Index over `players.yaml` `profile_id` for Aoe2DE
-    pub players_index_aoe2de: DashMap<String, PositionInAoePlayers>,
+    /// Index over `players.yaml` `profile_id` for each game, keyed by the
+    /// game of aoe2net, e.g. `aoe2de`
+    pub players_index: HashMap<String, DashMap<String, PositionInAoePlayers>>,
+    /// Index over the lowercased `name` and `aka` names of `players.yaml`
+    pub players_index_names: DashMap<String, Vec<PositionInAoePlayers>>,
+    /// Search index over the `name` and `aka` names of `players.yaml`
+    #[serde(skip)]
+    pub players_search_index: PlayerSearchIndex,
     /// from `teams.json`
     pub teams: AoeTeams,
     /// from `platforms.json`
@@ -41,20 +54,36 @@ impl RefDataLists {
         RefDataLists::default()
     }
 
-    /// Index `players` into `players_index` [`dashmap::DashMap`]
+    /// Index `players` into a [`dashmap::DashMap`] of `players_index` for
+    /// each game and their names into `players_index_names` and
+    /// `players_search_index`
     ///
     /// # Errors
     // TODO
     pub fn index(&mut self) -> Result<(), Vec<IndexingError>> {
-        let index: DashMap<String, PositionInAoePlayers> = DashMap::new();
+        let index: HashMap<String, DashMap<String, PositionInAoePlayers>> =
+            GAME_STRINGS.iter()
+                        .map(|game| ((*game).to_string(), DashMap::new()))
+                        .collect();
+        let names_index: DashMap<String, Vec<PositionInAoePlayers>> =
+            DashMap::new();
 
         let mut indexing_errors: Vec<IndexingError> = Vec::new();
 
         for (player_number, player) in self.players.iter().enumerate() {
-            if !&player.platforms.de.is_empty() {
-                for profile_id in &player.platforms.de {
-                    let old_value =
-                        index.insert(profile_id.to_string(), player_number);
+            for name in std::iter::once(&player.name).chain(&player.aka) {
+                let mut positions =
+                    names_index.entry(normalize_name(name)).or_default();
+
+                if !positions.contains(&player_number) {
+                    positions.push(player_number);
+                }
+            }
+
+            for (game, game_index) in &index {
+                for profile_id in player.platforms.profile_ids(game) {
+                    let old_value = game_index.insert(profile_id.clone(),
+                                                      player_number);
 
                     if let Some(x) = old_value {
                         // TODO Better error handling, we shouldn't fail to
@@ -81,7 +110,10 @@ impl RefDataLists {
         debug!("Alias index: {:#?}", index);
 
         // Fill index field in struct
-        self.players_index_aoe2de = index;
+        self.players_index = index;
+        self.players_index_names = names_index;
+        self.players_search_index =
+            PlayerSearchIndex::with_players(&self.players);
 
         // Return `indexing_errors`
         if !indexing_errors.is_empty() {
@@ -91,15 +123,78 @@ impl RefDataLists {
         Ok(())
     }
 
-    /// Search through alias list for `player_id` and return `players::Player`
+    /// Search through alias list of a `game` for `player_id` and return
+    /// `players::Player`
     #[must_use]
     pub fn lookup_player_alias_for_profile_id(&self,
+                                              game: &str,
                                               profile_id: &str)
                                               -> Option<players::Player> {
-        self.players_index_aoe2de
+        self.players_index
+            .get(game)?
             .get(profile_id)
             .map(|alias_position| self.players[*alias_position].clone())
     }
+
+    /// Returns `true` if the player with `profile_id` in a `game` is part of
+    /// the alias list
+    #[must_use]
+    pub fn is_known_profile_id(&self,
+                               game: &str,
+                               profile_id: &str)
+                               -> bool {
+        self.players_index
+            .get(game)
+            .is_some_and(|game_index| game_index.contains_key(profile_id))
+    }
+
+    /// Search through alias list for a `name` or one of the `aka` names of a
+    /// player, ignoring case, and return all matching `players::Player`s
+    #[must_use]
+    pub fn lookup_players_for_name(&self,
+                                   name: &str)
+                                   -> Vec<players::Player> {
+        self.players_index_names
+            .get(&normalize_name(name))
+            .map_or_else(Vec::new, |positions| {
+                positions.iter()
+                         .map(|position| self.players[*position].clone())
+                         .collect()
+            })
+    }
+
+    /// Search for players whose `name` or one of their `aka` names matches
+    /// the `query` by prefix or fuzzy, returns at most `limit` players with
+    /// the best matches first
+    #[must_use]
+    pub fn search_players(&self,
+                          query: &str,
+                          limit: usize)
+                          -> Vec<players::Player> {
+        self.players_search_index
+            .search(query, limit)
+            .into_iter()
+            .map(|position| self.players[position].clone())
+            .collect()
+    }
+
+    /// Search through the teams for the team a player with that `name` is
+    /// part of
+    #[must_use]
+    pub fn lookup_team_for_player_name(&self,
+                                       name: &str)
+                                       -> Option<&teams::Team> {
+        let is_member = |player: &String| player.eq_ignore_ascii_case(name);
+
+        self.teams
+            .iter()
+            .find(|team| team.players.iter().any(is_member))
+    }
+}
+
+/// Names are looked up case-insensitive and without surrounding whitespace
+fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase()
 }
 
 // impl Iterator for X {
@@ -115,3 +210,49 @@ impl RefDataLists {
 //         })
 //     }
 // }
+
+#[test]
+fn players_are_indexed_by_name_and_aka() {
+    use crate::domain::types::testing::ref_data_lists;
+
+    let player = |name: &str, aka: Vec<&str>, profile_id: &str| {
+        serde_json::json!({
+            "name": name,
+            "aka": aka,
+            "country": "de",
+            "platforms": { "de": [profile_id] }
+        })
+    };
+
+    let ref_data = ref_data_lists(
+        serde_json::json!([player("TheViper", vec!["Viper"], "196240"),
+                           player("Viper", vec![], "1"),
+                           player("Hera", vec![], "199325")]),
+        serde_json::json!([]),
+    );
+
+    assert_eq!(ref_data.lookup_players_for_name("theviper").len(), 1);
+    assert_eq!(ref_data.lookup_players_for_name(" VIPER ").len(), 2);
+    assert!(ref_data.lookup_players_for_name("Liereyy").is_empty());
+}
+
+#[test]
+fn profile_ids_are_indexed_per_game() {
+    use crate::domain::types::testing::ref_data_lists;
+
+    let ref_data = ref_data_lists(
+        serde_json::json!([{
+            "name": "TheViper",
+            "country": "no",
+            "platforms": { "de": ["196240"] }
+        }]),
+        serde_json::json!([]),
+    );
+
+    assert!(ref_data.lookup_player_alias_for_profile_id("aoe2de", "196240")
+                    .is_some());
+    // `aoc-reference-data` only lists profile IDs of Age of Empires II
+    assert!(ref_data.lookup_player_alias_for_profile_id("aoe4", "196240")
+                    .is_none());
+    assert!(!ref_data.is_known_profile_id("aoe3de", "196240"));
+}
@@ -53,6 +53,7 @@ pub struct Player {
            Serialize,
            Deserialize)]
 pub struct Platforms {
+    /// `profile_ids` for Age of Empires II: Definitive Edition
     #[serde(default)]
     pub de: Vec<String>,
     #[serde(default)]
@@ -73,6 +74,23 @@ pub struct Platforms {
     pub zone: Vec<String>,
 }
 
+impl Platforms {
+    /// Returns the `profile_ids` of the player for a `game` of aoe2net, e.g.
+    /// `aoe2de`
+    ///
+    /// `aoc-reference-data` only lists `profile_ids` of Age of Empires II, so
+    /// there are no aliases for the players of the other games.
+    #[must_use]
+    pub fn profile_ids(&self,
+                       game: &str)
+                       -> &[String] {
+        match game {
+            "aoe2de" => &self.de,
+            _ => &[],
+        }
+    }
+}
+
 #[test]
 fn ensure_players_roundtrips() {
     let t = <Vec<Player>>::default();
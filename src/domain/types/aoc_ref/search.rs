@@ -0,0 +1,245 @@
+//! Search index over the `name` and `aka` names of `players.yaml`
+//!
+//! Names are matched case-insensitive by their prefix first, which are found
+//! by a binary search over the sorted names. Only if there are not enough of
+//! them, all names are scanned for containing the query, e.g. `viper` for
+//! `TheViper`. Queries with a few characters are additionally matched fuzzy,
+//! so that small typos still find the player.
+
+use super::{
+    players::Player,
+    PositionInAoePlayers,
+};
+
+/// Queries shorter than this are only matched by their prefix
+const FUZZY_MIN_QUERY_LEN: usize = 3;
+
+/// How well a name matches a query, lower is better
+type Score = usize;
+
+/// Search index over the lowercased `name` and `aka` names of the players
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerSearchIndex {
+    /// Lowercased names with the position of their player, sorted by name
+    names: Vec<(String, PositionInAoePlayers)>,
+}
+
+impl PlayerSearchIndex {
+    /// Build the search index over the names of `players`
+    #[must_use]
+    pub fn with_players(players: &[Player]) -> Self {
+        let mut names = players.iter()
+                               .enumerate()
+                               .flat_map(|(position, player)| {
+                                   std::iter::once(&player.name)
+                                       .chain(&player.aka)
+                                       .map(move |name| {
+                                           (normalize_query(name), position)
+                                       })
+                               })
+                               .collect::<Vec<_>>();
+
+        names.sort();
+        names.dedup();
+
+        Self { names }
+    }
+
+    /// Returns `true` if no names are indexed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Search for players whose name or one of their `aka` names matches the
+    /// `query` and return at most `limit` of their positions, best matches
+    /// first
+    #[must_use]
+    pub fn search(&self,
+                  query: &str,
+                  limit: usize)
+                  -> Vec<PositionInAoePlayers> {
+        let query = normalize_query(query);
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        // All names starting with the query are next to each other
+        let start = self.names.partition_point(|(name, _)| *name < query);
+        let end = start +
+                  self.names[start..].iter()
+                                     .take_while(|(name, _)| {
+                                         name.starts_with(query.as_str())
+                                     })
+                                     .count();
+
+        let mut positions = Vec::new();
+        best_positions(&mut positions,
+                       &self.names[start..end],
+                       &query,
+                       limit);
+
+        // Prefix matches always rank before the others, so the remaining names
+        // are only scored if they are needed to fill up the `limit`
+        if positions.len() < limit {
+            let rest = self.names[..start].iter().chain(&self.names[end..]);
+
+            best_positions(&mut positions, rest, &query, limit);
+        }
+
+        positions
+    }
+}
+
+/// Add the positions of the players of the best matching `names` to
+/// `positions`, until there are `limit` of them
+fn best_positions<'a>(positions: &mut Vec<PositionInAoePlayers>,
+                      names: impl IntoIterator<Item = &'a (String,
+                                               PositionInAoePlayers)>,
+                      query: &str,
+                      limit: usize) {
+    let mut matches =
+        names.into_iter()
+             .filter_map(|(name, position)| {
+                 score(name, query).map(|score| {
+                                       (score, name.len(), name, *position)
+                                   })
+             })
+             .collect::<Vec<_>>();
+
+    // Shorter names are closer to the query if both match equally well
+    matches.sort();
+
+    // A player can match with multiple names, only its best match counts
+    for (.., position) in matches {
+        if positions.len() == limit {
+            break;
+        }
+
+        if !positions.contains(&position) {
+            positions.push(position);
+        }
+    }
+}
+
+/// Queries are matched case-insensitive and without surrounding whitespace
+fn normalize_query(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// Score a `name` for a `query`, an exact match is best, followed by prefix
+/// matches, names containing the `query` and names that start with something
+/// close to the `query`
+fn score(name: &str,
+         query: &str)
+         -> Option<Score> {
+    if name == query {
+        return Some(0);
+    }
+
+    if name.starts_with(query) {
+        return Some(1);
+    }
+
+    if name.contains(query) {
+        return Some(2);
+    }
+
+    let query_len = query.chars().count();
+
+    if query_len < FUZZY_MIN_QUERY_LEN {
+        return None;
+    }
+
+    let name_prefix = name.chars().take(query_len).collect::<String>();
+    let distance = edit_distance(&name_prefix, query);
+
+    if distance <= (query_len / 4).max(1) {
+        Some(2 + distance)
+    }
+    else {
+        None
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(left: &str,
+                 right: &str)
+                 -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous_row = (0..=right.len()).collect::<Vec<_>>();
+
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution =
+                previous_row[j] + usize::from(left_char != *right_char);
+
+            current_row.push(substitution.min(previous_row[j + 1] + 1)
+                                         .min(current_row[j] + 1));
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[right.len()]
+}
+
+#[test]
+fn edit_distance_counts_single_char_edits() {
+    assert_eq!(edit_distance("viper", "viper"), 0);
+    assert_eq!(edit_distance("viper", "vipre"), 2);
+    assert_eq!(edit_distance("hera", "hero"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn prefix_matches_rank_before_fuzzy_matches() {
+    let player = |name: &str, aka: Vec<&str>| {
+        serde_json::from_value::<Player>(serde_json::json!({
+            "name": name,
+            "aka": aka,
+            "country": "de",
+            "platforms": {}
+        }))
+        .unwrap()
+    };
+
+    let index = PlayerSearchIndex::with_players(&[
+        player("TheViper", vec!["Viper"]),
+        player("Vinchester", vec![]),
+        player("Hera", vec!["Hearttt"]),
+        player("Liereyy", vec![]),
+    ]);
+
+    assert_eq!(index.search("vi", 10), vec![0, 1]);
+    assert_eq!(index.search(" VIPER", 10), vec![0]);
+    assert_eq!(index.search("the", 10), vec![0]);
+    assert_eq!(index.search("chest", 10), vec![1]);
+    // Typo in the name
+    assert_eq!(index.search("lieryy", 10), vec![3]);
+    assert_eq!(index.search("he", 1), vec![2]);
+    assert!(index.search("", 10).is_empty());
+}
+
+#[test]
+fn enough_prefix_matches_skip_the_other_names() {
+    let player = |name: &str| {
+        serde_json::from_value::<Player>(serde_json::json!({
+            "name": name,
+            "country": "de",
+            "platforms": {}
+        }))
+        .unwrap()
+    };
+
+    let index = PlayerSearchIndex::with_players(&[player("Hera"),
+                                                  player("TheHero"),
+                                                  player("Heroic"),
+                                                  player("Herb")]);
+
+    assert_eq!(index.search("her", 2), vec![0, 3]);
+    assert_eq!(index.search("her", 10), vec![0, 3, 2, 1]);
+}
@@ -0,0 +1,163 @@
+//! Datastructures of the `leaderboard/{leaderboard_id}` endpoint
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    parse_in_range,
+    validate_game,
+    validate_language,
+    PlayerTeam,
+};
+use crate::domain::types::error::RequestValidationError;
+
+/// Amount of entries if none is requested
+const DEFAULT_COUNT: i32 = 10;
+/// Maximum amount of entries of a single request
+const MAX_COUNT: i32 = 1000;
+
+/// Datastructure for an incoming `request` on the
+/// `leaderboard/{leaderboard_id}` endpoint
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct LeaderboardRequest {
+    /// aoe2.net ID of the leaderboard, e.g. `3` for `1v1 Random Map`
+    pub leaderboard_id: i32,
+    /// Requested language (Optional), Standard value is "en"
+    pub language: Option<String>,
+    /// Requested game (Optional), Standard value is "aoe2de"
+    pub game: Option<String>,
+    /// Rank of the first entry, starting at `1`
+    pub start: i32,
+    /// Amount of entries
+    pub count: i32,
+}
+
+/// Unvalidated query parameters of a [`LeaderboardRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct LeaderboardQuery {
+    /// Requested language
+    pub language: Option<String>,
+    /// Requested game
+    pub game: Option<String>,
+    /// Rank of the first entry
+    pub start: Option<String>,
+    /// Amount of entries
+    pub count: Option<String>,
+}
+
+impl LeaderboardRequest {
+    /// Create a [`LeaderboardRequest`] from the `leaderboard_id` in the path
+    /// and the `query` parameters
+    ///
+    /// # Errors
+    /// Returns a [`RequestValidationError`] with every invalid field
+    pub fn with_query(leaderboard_id: &str,
+                      query: LeaderboardQuery)
+                      -> Result<Self, RequestValidationError> {
+        let mut invalid_fields = Vec::new();
+
+        let leaderboard_id = parse_in_range(&mut invalid_fields,
+                                            "leaderboard_id",
+                                            Some(leaderboard_id),
+                                            0..=i32::MAX);
+
+        if let Some(language) = &query.language {
+            if let Err(err) = validate_language(language) {
+                invalid_fields.push(err);
+            }
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        let start = parse_in_range(&mut invalid_fields,
+                                   "start",
+                                   query.start.as_deref(),
+                                   1..=i32::MAX).unwrap_or(1);
+
+        let count = parse_in_range(&mut invalid_fields,
+                                   "count",
+                                   query.count.as_deref(),
+                                   1..=MAX_COUNT)
+            .unwrap_or(DEFAULT_COUNT);
+
+        match leaderboard_id {
+            Some(leaderboard_id) if invalid_fields.is_empty() => {
+                Ok(Self { leaderboard_id,
+                          language: query.language,
+                          game: query.game,
+                          start,
+                          count })
+            },
+            _ => Err(RequestValidationError { invalid_fields }),
+        }
+    }
+}
+
+/// Result of the `leaderboard/{leaderboard_id}` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardResponse {
+    /// aoe2.net ID of the leaderboard
+    pub leaderboard_id: i32,
+    /// Translated name of the leaderboard, if known
+    pub leaderboard_name: Option<String>,
+    /// Amount of players on the leaderboard
+    pub total: Option<i64>,
+    /// Requested entries, ordered by rank
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// A player on the leaderboard, enriched with the `aoc-reference-data`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    /// Rank on the leaderboard
+    pub rank: i64,
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Current in-game name of the player
+    pub name: String,
+    /// Known name of the player from the `aoc-reference-data`, if there is
+    /// one
+    pub alias: Option<String>,
+    /// Team of the player, if known
+    pub team: Option<PlayerTeam>,
+    /// Country of the player, from the `aoc-reference-data` if known
+    pub country: Option<String>,
+    /// Current rating
+    pub rating: i64,
+    /// Highest rating on the leaderboard
+    pub highest_rating: Option<i64>,
+    /// Current streak, positive for wins and negative for losses
+    pub streak: i64,
+    /// Amount of won games
+    pub wins: i64,
+    /// Amount of lost games
+    pub losses: i64,
+    /// Percentage of won games
+    pub win_rate: Option<f32>,
+}
+
+#[test]
+fn leaderboard_query_is_validated() {
+    let request = LeaderboardRequest::with_query("3",
+                                                 LeaderboardQuery::default())
+        .unwrap();
+    assert_eq!((request.leaderboard_id, request.start, request.count),
+               (3, 1, DEFAULT_COUNT));
+
+    let query = LeaderboardQuery { start: Some("0".to_string()),
+                                   count: Some("1001".to_string()),
+                                   ..LeaderboardQuery::default() };
+
+    let err = LeaderboardRequest::with_query("rm", query).unwrap_err();
+    assert_eq!(err.invalid_fields
+                  .iter()
+                  .map(|field| field.name.as_ref())
+                  .collect::<Vec<_>>(),
+               vec!["leaderboard_id", "start", "count"]);
+}
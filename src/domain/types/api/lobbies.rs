@@ -0,0 +1,243 @@
+//! Datastructures of the `lobbies` endpoint
+
+use std::convert::TryFrom;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    parse_bool,
+    parse_in_range,
+    validate_game,
+    validate_language,
+    Server,
+};
+use crate::domain::types::error::{
+    InvalidField,
+    RequestValidationError,
+};
+
+/// Datastructure for an incoming `request` on the `lobbies` endpoint, every
+/// filter is optional
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LobbiesRequest {
+    /// Requested language (Optional), Standard value is "en"
+    pub language: Option<String>,
+    /// Requested game (Optional), Standard value is "aoe2de"
+    pub game: Option<String>,
+    /// Name of the map in the requested language, ignoring case
+    pub map: Option<String>,
+    /// Lowest average rating of a lobby
+    pub min_rating: Option<i64>,
+    /// Highest average rating of a lobby
+    pub max_rating: Option<i64>,
+    /// Server of a lobby
+    pub server: Option<Server>,
+    /// Only lobbies with a player of the `aoc-reference-data`
+    pub known_players: bool,
+}
+
+/// Unvalidated query parameters of a [`LobbiesRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct LobbiesQuery {
+    /// Requested language
+    pub language: Option<String>,
+    /// Requested game
+    pub game: Option<String>,
+    /// Name of the map
+    pub map: Option<String>,
+    /// Lowest average rating
+    pub min_rating: Option<String>,
+    /// Highest average rating
+    pub max_rating: Option<String>,
+    /// Server location of aoe2.net, e.g. `westeurope`
+    pub server: Option<String>,
+    /// Only lobbies with a known player
+    pub known_players: Option<String>,
+}
+
+impl TryFrom<LobbiesQuery> for LobbiesRequest {
+    type Error = RequestValidationError;
+
+    fn try_from(query: LobbiesQuery) -> Result<Self, Self::Error> {
+        let mut invalid_fields = Vec::new();
+
+        if let Some(language) = &query.language {
+            if let Err(err) = validate_language(language) {
+                invalid_fields.push(err);
+            }
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        let min_rating = parse_in_range(&mut invalid_fields,
+                                        "min_rating",
+                                        query.min_rating.as_deref(),
+                                        0..=i64::MAX);
+
+        let max_rating = parse_in_range(&mut invalid_fields,
+                                        "max_rating",
+                                        query.max_rating.as_deref(),
+                                        min_rating.unwrap_or(0)..=i64::MAX);
+
+        let server = query.server.as_deref().and_then(|location| {
+            match Server::from(location) {
+                Server::NotFound => {
+                    invalid_fields.push(InvalidField::new(
+                        "server",
+                        format!("unknown server {location:?}"),
+                    ));
+                    None
+                },
+                server => Some(server),
+            }
+        });
+
+        let known_players = parse_bool(&mut invalid_fields,
+                                       "known_players",
+                                       query.known_players.as_deref())
+            .unwrap_or_default();
+
+        if invalid_fields.is_empty() {
+            Ok(Self { language: query.language,
+                      game: query.game,
+                      map: query.map,
+                      min_rating,
+                      max_rating,
+                      server,
+                      known_players })
+        }
+        else {
+            Err(RequestValidationError { invalid_fields })
+        }
+    }
+}
+
+impl LobbiesRequest {
+    /// Returns `true` if the `lobby` passes all requested filters
+    #[must_use]
+    pub fn matches(&self,
+                   lobby: &Lobby)
+                   -> bool {
+        let in_rating_range = match (self.min_rating, self.max_rating) {
+            (None, None) => true,
+            (min, max) => {
+                lobby.average_rating.is_some_and(|rating| {
+                    min.is_none_or(|min| rating >= min) &&
+                    max.is_none_or(|max| rating <= max)
+                })
+            },
+        };
+
+        in_rating_range &&
+        self.map
+            .as_ref()
+            .is_none_or(|map| lobby.map.eq_ignore_ascii_case(map)) &&
+        self.server
+            .as_ref()
+            .is_none_or(|server| &lobby.server == server) &&
+        (!self.known_players ||
+         lobby.players.iter().any(|player| player.alias.is_some()))
+    }
+}
+
+/// Result of the `lobbies` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LobbiesResponse {
+    /// Open lobbies that pass the requested filters
+    pub lobbies: Vec<Lobby>,
+}
+
+/// An open lobby with translated names
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lobby {
+    /// aoe2.net ID of the match
+    pub match_id: String,
+    /// Name of the lobby
+    pub name: String,
+    /// Translated name of the map
+    pub map: String,
+    /// Translated name of the game type
+    pub game_type: String,
+    /// Translated name of the rating type
+    pub rating_type: String,
+    /// Server of the lobby
+    pub server: Server,
+    /// Average rating of the players, if known
+    pub average_rating: Option<i64>,
+    /// Amount of players in the lobby
+    pub num_players: i64,
+    /// Amount of slots of the lobby
+    pub num_slots: i64,
+    /// Time the lobby was opened as ISO 8601 timestamp in UTC
+    pub opened: Option<String>,
+    /// Players in the lobby
+    pub players: Vec<LobbyPlayer>,
+}
+
+/// A player in a [`Lobby`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LobbyPlayer {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Current in-game name of the player
+    pub name: String,
+    /// Known name of the player from the `aoc-reference-data`, if there is
+    /// one
+    pub alias: Option<String>,
+    /// Country of the player, if known
+    pub country: Option<String>,
+    /// Rating of the player, if known
+    pub rating: Option<i64>,
+}
+
+#[test]
+fn lobbies_are_filtered() {
+    let query = LobbiesQuery { map: Some("arabia".to_string()),
+                               min_rating: Some("1500".to_string()),
+                               server: Some("westeurope".to_string()),
+                               known_players: Some("true".to_string()),
+                               ..LobbiesQuery::default() };
+    let request = LobbiesRequest::try_from(query).unwrap();
+
+    let player = |alias: Option<&str>| LobbyPlayer {
+        alias: alias.map(str::to_string),
+        ..LobbyPlayer::default()
+    };
+
+    let lobby = Lobby { map: "Arabia".to_string(),
+                        server: Server::WesternEurope,
+                        average_rating: Some(1600),
+                        players: vec![player(None), player(Some("Hera"))],
+                        ..Lobby::default() };
+
+    assert!(request.matches(&lobby));
+    assert!(!request.matches(&Lobby { average_rating: None,
+                                      ..lobby.clone() }));
+    assert!(!request.matches(&Lobby { server: Server::UsEast,
+                                      ..lobby.clone() }));
+    assert!(!request.matches(&Lobby { players: vec![player(None)],
+                                      ..lobby }));
+}
+
+#[test]
+fn lobbies_query_is_validated() {
+    let query = LobbiesQuery { min_rating: Some("2000".to_string()),
+                               max_rating: Some("1000".to_string()),
+                               server: Some("mars".to_string()),
+                               known_players: Some("1".to_string()),
+                               ..LobbiesQuery::default() };
+
+    let err = LobbiesRequest::try_from(query).unwrap_err();
+    assert_eq!(err.invalid_fields
+                  .iter()
+                  .map(|field| field.name.as_ref())
+                  .collect::<Vec<_>>(),
+               vec!["max_rating", "server", "known_players"]);
+}
@@ -0,0 +1,221 @@
+//! Datastructures of the `player/{profile_id}/matches` endpoint
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    parse_in_range,
+    validate_game,
+    validate_language,
+    validate_profile_id,
+};
+use crate::domain::types::error::RequestValidationError;
+
+/// Amount of matches in the match history if none is requested
+const DEFAULT_COUNT: i32 = 50;
+/// Maximum amount of matches aoe2.net returns for a match history
+const MAX_COUNT: i32 = 1000;
+
+/// Datastructure for an incoming `request` on the
+/// `player/{profile_id}/matches` endpoint
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct MatchHistoryRequest {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Requested language (Optional), Standard value is "en"
+    pub language: Option<String>,
+    /// Requested game (Optional), Standard value is "aoe2de"
+    pub game: Option<String>,
+    /// Amount of most recent matches
+    pub count: i32,
+    /// Opponent to show the record against (Optional), Standard value is the
+    /// opponent of the most recent 1v1 match
+    pub opponent_id: Option<String>,
+}
+
+/// Unvalidated query parameters of a [`MatchHistoryRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct MatchHistoryQuery {
+    /// Requested language
+    pub language: Option<String>,
+    /// Requested game
+    pub game: Option<String>,
+    /// Amount of most recent matches
+    pub count: Option<String>,
+    /// Opponent to show the record against
+    pub opponent_id: Option<String>,
+}
+
+impl MatchHistoryRequest {
+    /// Create a [`MatchHistoryRequest`] from the `profile_id` in the path and
+    /// the `query` parameters
+    ///
+    /// # Errors
+    /// Returns a [`RequestValidationError`] with every invalid field
+    pub fn with_query(profile_id: String,
+                      query: MatchHistoryQuery)
+                      -> Result<Self, RequestValidationError> {
+        let mut invalid_fields = Vec::new();
+
+        if let Err(err) = validate_profile_id(&profile_id) {
+            invalid_fields.push(err);
+        }
+
+        if let Some(language) = &query.language {
+            if let Err(err) = validate_language(language) {
+                invalid_fields.push(err);
+            }
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        let count = parse_in_range(&mut invalid_fields,
+                                   "count",
+                                   query.count.as_deref(),
+                                   1..=MAX_COUNT)
+            .unwrap_or(DEFAULT_COUNT);
+
+        if let Some(opponent_id) = &query.opponent_id {
+            if let Err(mut err) = validate_profile_id(opponent_id) {
+                err.name = "opponent_id".into();
+                invalid_fields.push(err);
+            }
+        }
+
+        if invalid_fields.is_empty() {
+            Ok(Self { profile_id,
+                      language: query.language,
+                      game: query.game,
+                      count,
+                      opponent_id: query.opponent_id })
+        }
+        else {
+            Err(RequestValidationError { invalid_fields })
+        }
+    }
+}
+
+/// Result of the `player/{profile_id}/matches` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchHistoryResponse {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Recent matches of the player, most recent first
+    pub matches: Vec<MatchHistoryEntry>,
+    /// Performance with each civilisation, most played first
+    pub per_civ: Vec<PerformanceStats>,
+    /// Performance on each map, most played first
+    pub per_map: Vec<PerformanceStats>,
+    /// Record against the requested or current opponent
+    pub versus_opponent: Option<OpponentStats>,
+}
+
+/// A match of the match history from the perspective of the player
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchHistoryEntry {
+    /// aoe2.net ID of the match
+    pub match_id: String,
+    /// Start of the match as ISO 8601 timestamp in UTC
+    pub started: String,
+    /// Translated name of the map
+    pub map: String,
+    /// Translated name of the civilisation of the player
+    pub civ: String,
+    /// `true` if the player won, `None` if the result is unknown
+    pub won: Option<bool>,
+    /// Rating of the player before the match
+    pub rating: Option<i64>,
+    /// Change of the rating of the player through the match
+    pub rating_change: Option<i64>,
+    /// Players of the other teams
+    pub opponents: Vec<MatchOpponent>,
+}
+
+/// A player of another team in a [`MatchHistoryEntry`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchOpponent {
+    /// aoe2.net profile ID of the opponent
+    pub profile_id: String,
+    /// Name of the opponent
+    pub name: String,
+    /// Translated name of the civilisation of the opponent
+    pub civ: String,
+}
+
+/// Wins and losses of a player, e.g. with a civilisation or on a map
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceStats {
+    /// Name of the civilisation, map or opponent
+    pub name: String,
+    /// Amount of matches, including matches with an unknown result
+    pub games: u32,
+    /// Amount of won matches
+    pub wins: u32,
+    /// Amount of lost matches
+    pub losses: u32,
+    /// Percentage of won matches of the matches with a known result
+    pub win_rate: Option<f32>,
+}
+
+impl PerformanceStats {
+    /// Create empty [`PerformanceStats`] for `name`
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(),
+               ..Self::default() }
+    }
+
+    /// Add a match with the result `won`, `None` if it is unknown
+    #[allow(clippy::cast_precision_loss)]
+    pub fn add_result(&mut self,
+                      won: Option<bool>) {
+        self.games += 1;
+
+        match won {
+            Some(true) => self.wins += 1,
+            Some(false) => self.losses += 1,
+            None => {},
+        }
+
+        let decided = self.wins + self.losses;
+
+        self.win_rate = if decided == 0 {
+            None
+        }
+        else {
+            Some(self.wins as f32 / decided as f32 * 100_f32)
+        };
+    }
+}
+
+/// Record of a player against an opponent
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpponentStats {
+    /// aoe2.net profile ID of the opponent
+    pub profile_id: String,
+    /// Wins and losses against the opponent
+    #[serde(flatten)]
+    pub stats: PerformanceStats,
+}
+
+#[test]
+fn performance_stats_ignore_unknown_results_for_win_rate() {
+    let mut stats = PerformanceStats::new("Arabia");
+
+    stats.add_result(None);
+    assert_eq!(stats.win_rate, None);
+
+    stats.add_result(Some(true));
+    stats.add_result(Some(true));
+    stats.add_result(Some(true));
+    stats.add_result(Some(false));
+
+    assert_eq!((stats.games, stats.wins, stats.losses), (5, 3, 1));
+    assert_eq!(stats.win_rate, Some(75.0));
+}
@@ -26,6 +26,7 @@ use serde::{
 };
 use typed_builder::TypedBuilder;
 
+use super::PerformanceStats;
 use crate::domain::types::error::ErrorMessageToFrontend;
 
 /// An enum describing the different `MatchSizes` we support on our overlay
@@ -105,6 +106,24 @@ impl Default for Server {
     }
 }
 
+impl From<&str> for Server {
+    /// Convert the `server` location of aoe2.net, e.g. `westeurope`
+    fn from(location: &str) -> Self {
+        match location {
+            "australiasoutheast" => Server::Australia,
+            "brazilsouth" => Server::Brazil,
+            "ukwest" => Server::UK,
+            "westindia" => Server::India,
+            "southeastasia" => Server::SoutheastAsia,
+            "westeurope" => Server::WesternEurope,
+            "eastus" => Server::UsEast,
+            "koreacentral" => Server::Korea,
+            "westus2" => Server::UsWest,
+            _ => Server::NotFound,
+        }
+    }
+}
+
 /// Head struct to assemble `MatchInfo` into and save `error_messages` within to
 /// delegate to the frontend
 #[derive(Clone,
@@ -118,19 +137,38 @@ pub struct MatchInfoResult {
     /// Contains all the data about the players and the match
     #[builder(default=None, setter(strip_option))]
     pub match_info: Option<MatchInfo>,
-    /// Error message strings that are important to give to the frontend
+    /// Error messages that are important to give to the frontend
     /// e.g. parsing errors to keep that in cache in the frontend,
     /// or also problems with the HTTP client in general, for example if
-    /// the aoe2net API is not reachable
-    #[builder(default=None, setter(strip_option))]
-    pub error_message: Option<ErrorMessageToFrontend>,
+    /// the aoe2net API is not reachable. Contains all `SoftFail`s that
+    /// occurred during processing, so partial data can be shown together with
+    /// its warnings
+    #[builder(default)]
+    #[serde(default)]
+    pub error_message: Vec<ErrorMessageToFrontend>,
+    /// Players the requested name could belong to, if it is ambiguous
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<PlayerCandidate>,
+}
+
+/// A player a requested name could belong to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerCandidate {
+    /// Name of the player
+    pub name: String,
+    /// `profile_id` to request the player unambiguously with
+    pub profile_id: String,
+    /// Country of the player, if known
+    pub country: Option<String>,
 }
 
 impl MatchInfoResult {
     #[must_use]
     pub fn new() -> Self {
         Self { match_info: None,
-               error_message: None }
+               error_message: Vec::new(),
+               candidates: Vec::new() }
     }
 
     /// Create a [`MatchInfoResult`]from a parsed `RON` file
@@ -201,6 +239,108 @@ pub struct MatchInfo {
     server: Server,
     /// Vector of Teams
     teams: Teams,
+    /// Head-to-head record of the players, if requested and the match is a
+    /// 1v1
+    #[builder(default, setter(strip_option))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    head_to_head: Option<HeadToHead>,
+    /// Win rates of the civilizations of the players from aoestats.io, if
+    /// requested
+    #[builder(default, setter(strip_option))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    civ_stats: Option<CivStats>,
+}
+
+impl MatchInfo {
+    /// Add the [`HeadToHead`] record of the players
+    #[must_use]
+    pub fn with_head_to_head(self,
+                             head_to_head: HeadToHead)
+                             -> Self {
+        Self { head_to_head: Some(head_to_head),
+               ..self }
+    }
+
+    /// Add the [`CivStats`] of the players
+    #[must_use]
+    pub fn with_civ_stats(self,
+                          civ_stats: CivStats)
+                          -> Self {
+        Self { civ_stats: Some(civ_stats),
+               ..self }
+    }
+}
+
+/// Win rates of the civilizations of a match, as calculated by aoestats.io
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CivStats {
+    /// Win rate of the civilization of each player on the map of the match
+    pub players: Vec<CivMapWinRate>,
+    /// Win rate of the civilization of the first player against the one of
+    /// the second player, only for 1v1s
+    pub matchup: Option<CivMatchup>,
+}
+
+/// Win rate of the civilization of a player on the map of a match
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CivMapWinRate {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Name of the civilization in English
+    pub civilisation: String,
+    /// Win rate in percent, `None` if aoestats.io has no stats for the
+    /// civilization on the map
+    pub win_rate: Option<f32>,
+}
+
+/// Win rate of a civilization against the civilization of the opponent in a
+/// 1v1
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CivMatchup {
+    /// Name of the civilization in English
+    pub civilisation: String,
+    /// Name of the civilization of the opponent in English
+    pub opponent_civilisation: String,
+    /// Win rate in percent, `None` if aoestats.io has no stats for the
+    /// matchup
+    pub win_rate: Option<f32>,
+}
+
+/// Head-to-head record of the two players of a 1v1, built from the matches
+/// they played against each other
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeadToHead {
+    /// Both players with their wins against each other
+    pub players: Vec<HeadToHeadPlayer>,
+    /// Amount of shared matches, including matches with an unknown result
+    pub games: u32,
+    /// Results of the most recent shared matches, most recent first
+    pub last_results: Vec<HeadToHeadResult>,
+    /// Most played maps of the shared matches, from the perspective of the
+    /// first player
+    pub maps: Vec<PerformanceStats>,
+}
+
+/// A player of a [`HeadToHead`] record
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadToHeadPlayer {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Amount of shared matches the player won
+    pub wins: u32,
+}
+
+/// Result of a shared match of a [`HeadToHead`] record
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeadToHeadResult {
+    /// aoe2.net ID of the match
+    pub match_id: String,
+    /// Start of the match as ISO 8601 timestamp in UTC
+    pub started: String,
+    /// Translated name of the map
+    pub map: String,
+    /// aoe2.net profile ID of the winner, `None` if the result is unknown
+    pub winner: Option<String>,
 }
 
 /// Wrapper struct around `PlayerRaw` for `Players`
@@ -218,9 +358,15 @@ pub struct Players(pub Vec<PlayerRaw>);
 pub struct PlayerRaw {
     rating: Rating,
     player_number: i64,
+    #[serde(default)]
+    slot: i64,
     team_number: i64,
     name: String,
     country: String,
+    #[builder(default)]
+    team_name: Option<String>,
+    #[builder(default)]
+    team_abbreviation: Option<String>,
     civilisation: String,
     requested: bool,
 }
@@ -235,14 +381,17 @@ pub struct Teams(pub Vec<TeamRaw>);
            Debug,
            Default,
            TypedBuilder,
+           Getters,
            PartialEq,
            Serialize,
            Deserialize)]
 pub struct TeamRaw {
     players: Players,
     team_number: i64,
-    #[builder(default, setter(strip_option))]
+    #[builder(default)]
     team_name: Option<String>,
+    #[builder(default)]
+    team_abbreviation: Option<String>,
 }
 
 /// Rating part of the our `matchinfo` endpoint
@@ -250,6 +399,7 @@ pub struct TeamRaw {
            Default,
            Debug,
            TypedBuilder,
+           Getters,
            PartialEq,
            Serialize,
            Deserialize)]
@@ -273,6 +423,25 @@ fn ensure_match_info_roundtrips() {
     assert_eq!(t, r);
 }
 
+#[test]
+fn ensure_error_messages_are_structured() {
+use crate::domain::types::error::ErrorMessageToFrontend;
+
+    let t = MatchInfoResult::builder()
+        .error_message(vec![ErrorMessageToFrontend::soft_fail(
+            "alias_not_found",
+            "Haven't found an alias for player id: 196240",
+        )])
+        .build();
+
+    assert_eq!(serde_json::to_value(&t).unwrap()["error_message"],
+               serde_json::json!([{
+                   "code": "alias_not_found",
+                   "severity": "SoftFail",
+                   "message": "Haven't found an alias for player id: 196240"
+               }]));
+}
+
 #[test]
 fn ensure_match_info_from_sample() {
     let sample = r#"
@@ -335,7 +504,7 @@ fn ensure_match_info_from_sample() {
       }
     ]
   },
-  "error_message":null
+  "error_message":[]
 }
 "#;
 
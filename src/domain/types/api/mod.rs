@@ -1,18 +1,36 @@
 //! Our API root module
+pub mod leaderboard;
+pub mod lobbies;
+pub mod match_history;
 pub mod match_info_response;
+pub mod player_profile;
+pub mod player_search;
+pub mod pro_matches;
+pub mod rating_history;
 use std::{
+    convert::TryFrom,
+    fmt,
     fs,
     io::{
         BufReader,
         BufWriter,
     },
+    ops::RangeInclusive,
     path::{
         Path,
         PathBuf,
     },
+    str::FromStr,
 };
 
+pub use leaderboard::*;
+pub use lobbies::*;
+pub use match_history::*;
 pub use match_info_response::*;
+pub use player_profile::*;
+pub use player_search::*;
+pub use pro_matches::*;
+pub use rating_history::*;
 use ron::ser::{
     to_writer_pretty,
     PrettyConfig,
@@ -21,20 +39,460 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use uuid::Uuid;
+
+use crate::{
+    domain::types::error::{
+        InvalidField,
+        RequestValidationError,
+    },
+    GAME_STRINGS,
+    LANGUAGE_STRINGS,
+};
+
+/// Type of the ID in a [`MatchInfoRequest`]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum IdType {
+    /// Steam ID of a player
+    SteamId,
+    /// aoe2.net profile ID of a player
+    #[default]
+    ProfileId,
+    /// aoe2.net ID of a match
+    MatchId,
+    /// UUID of a match
+    MatchUuid,
+    /// In-game name of a player, resolved to a `profile_id` through the
+    /// `aoc-reference-data` or the leaderboard
+    Name,
+}
+
+impl IdType {
+    /// Value of the `id_type` query parameter
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdType::SteamId => "steam_id",
+            IdType::ProfileId => "profile_id",
+            IdType::MatchId => "match_id",
+            IdType::MatchUuid => "match_uuid",
+            IdType::Name => "name",
+        }
+    }
+
+    /// Returns `true` if `id_number` is a valid ID of this type, which is
+    /// numeric, a UUID for [`IdType::MatchUuid`] or a non-empty name for
+    /// [`IdType::Name`]
+    #[must_use]
+    pub fn is_valid_id(&self,
+                       id_number: &str)
+                       -> bool {
+        match self {
+            IdType::MatchUuid => Uuid::parse_str(id_number).is_ok(),
+            IdType::Name => !id_number.trim().is_empty(),
+            _ => {
+                !id_number.is_empty() &&
+                id_number.bytes().all(|byte| byte.is_ascii_digit())
+            },
+        }
+    }
+}
+
+impl FromStr for IdType {
+    type Err = InvalidField;
+
+    fn from_str(id_type: &str) -> Result<Self, Self::Err> {
+        match id_type {
+            "steam_id" => Ok(IdType::SteamId),
+            "profile_id" => Ok(IdType::ProfileId),
+            "match_id" => Ok(IdType::MatchId),
+            // `uuid` was used before `match_uuid`
+            "match_uuid" | "uuid" => Ok(IdType::MatchUuid),
+            "name" => Ok(IdType::Name),
+            _ => {
+                Err(InvalidField::new("id_type",
+                                      format!("unknown id type {id_type:?}, \
+                                               expected one of steam_id, \
+                                               profile_id, match_id, \
+                                               match_uuid, name")))
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for IdType {
+    type Error = InvalidField;
+
+    fn try_from(id_type: String) -> Result<Self, Self::Error> {
+        id_type.parse()
+    }
+}
+
+impl From<IdType> for String {
+    fn from(id_type: IdType) -> Self {
+        id_type.as_str().to_string()
+    }
+}
+
+impl fmt::Display for IdType {
+    fn fmt(&self,
+           f: &mut fmt::Formatter<'_>)
+           -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Order of the players inside a team on the `matchinfo` endpoint
+///
+/// The requested player always stays at the end of their team, with or
+/// without a sort. The other players keep the order of aoe2.net if no sort is
+/// requested.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum PlayerSort {
+    /// Highest rating first
+    RatingDesc,
+    /// Lowest rating first
+    RatingAsc,
+    /// Alphabetically by name, ignoring the case
+    Name,
+    /// By the slot of the player in the lobby
+    Slot,
+    /// By the color of the player, which is the `player_number`
+    Color,
+}
+
+impl PlayerSort {
+    /// Value of the `sort` query parameter
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlayerSort::RatingDesc => "rating_desc",
+            PlayerSort::RatingAsc => "rating_asc",
+            PlayerSort::Name => "name",
+            PlayerSort::Slot => "slot",
+            PlayerSort::Color => "color",
+        }
+    }
+}
+
+impl FromStr for PlayerSort {
+    type Err = InvalidField;
+
+    fn from_str(sort: &str) -> Result<Self, Self::Err> {
+        match sort {
+            "rating_desc" => Ok(PlayerSort::RatingDesc),
+            "rating_asc" => Ok(PlayerSort::RatingAsc),
+            "name" => Ok(PlayerSort::Name),
+            "slot" => Ok(PlayerSort::Slot),
+            "color" => Ok(PlayerSort::Color),
+            _ => {
+                Err(InvalidField::new("sort",
+                                      format!("unknown sort {sort:?}, expected \
+                                               one of rating_desc, \
+                                               rating_asc, name, slot, color")))
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for PlayerSort {
+    type Error = InvalidField;
+
+    fn try_from(sort: String) -> Result<Self, Self::Error> {
+        sort.parse()
+    }
+}
+
+impl From<PlayerSort> for String {
+    fn from(sort: PlayerSort) -> Self {
+        sort.as_str().to_string()
+    }
+}
+
+/// Order of the teams on the `matchinfo` endpoint
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub enum TeamSort {
+    /// Team of the requested player first, the others by team number
+    Requested,
+    /// Ascending by team number
+    TeamNumber,
+}
+
+impl TeamSort {
+    /// Value of the `team_sort` query parameter
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TeamSort::Requested => "requested",
+            TeamSort::TeamNumber => "team_number",
+        }
+    }
+}
+
+impl FromStr for TeamSort {
+    type Err = InvalidField;
+
+    fn from_str(team_sort: &str) -> Result<Self, Self::Err> {
+        match team_sort {
+            "requested" => Ok(TeamSort::Requested),
+            "team_number" => Ok(TeamSort::TeamNumber),
+            _ => {
+                Err(InvalidField::new("team_sort",
+                                      format!("unknown team sort \
+                                               {team_sort:?}, expected one \
+                                               of requested, team_number")))
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for TeamSort {
+    type Error = InvalidField;
+
+    fn try_from(team_sort: String) -> Result<Self, Self::Error> {
+        team_sort.parse()
+    }
+}
+
+impl From<TeamSort> for String {
+    fn from(team_sort: TeamSort) -> Self {
+        team_sort.as_str().to_string()
+    }
+}
 
 /// Datastructure for an incoming `request` on our api
 /// on the `matchinfo` endpoint
-#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct MatchInfoRequest {
     /// Requested language (Optional), Standard value is "en"
     pub language: Option<String>,
     /// Requested game (Optional), Standard value is "aoe2de"
     pub game: Option<String>,
-    /// Requested type of ID, possible values are ["steam_id", "profile_id",
-    /// "match_id"]
-    pub id_type: String,
-    /// The ID itself as a String
+    /// Requested type of ID
+    pub id_type: IdType,
+    /// The ID itself as a String, numeric, a UUID or a name depending on
+    /// `id_type`
     pub id_number: String,
+    /// Add the head-to-head record of the players of a 1v1 (Optional),
+    /// Standard value is `false`
+    #[serde(default)]
+    pub h2h: bool,
+    /// Add the win rates of the civilizations of the players from aoestats.io
+    /// (Optional), Standard value is `false`
+    #[serde(default)]
+    pub civ_stats: bool,
+    /// Order of the players inside a team (Optional), the order of aoe2.net
+    /// if not set
+    #[serde(default)]
+    pub sort: Option<PlayerSort>,
+    /// Order of the teams (Optional), descending by team number if not set
+    #[serde(default)]
+    pub team_sort: Option<TeamSort>,
+}
+
+/// Unvalidated query parameters of a [`MatchInfoRequest`], so we can report
+/// every invalid field at once
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct MatchInfoQuery {
+    /// Requested language
+    pub language: Option<String>,
+    /// Requested game
+    pub game: Option<String>,
+    /// Requested type of ID
+    pub id_type: Option<String>,
+    /// The ID itself
+    pub id_number: Option<String>,
+    /// Add the head-to-head record
+    pub h2h: Option<String>,
+    /// Add the win rates of the civilizations
+    pub civ_stats: Option<String>,
+    /// Order of the players inside a team
+    pub sort: Option<String>,
+    /// Order of the teams
+    pub team_sort: Option<String>,
+}
+
+impl TryFrom<MatchInfoQuery> for MatchInfoRequest {
+    type Error = RequestValidationError;
+
+    fn try_from(query: MatchInfoQuery) -> Result<Self, Self::Error> {
+        let mut invalid_fields = Vec::new();
+
+        let id_type = if let Some(id_type) = query.id_type.as_deref() {
+            id_type.parse::<IdType>()
+                   .map_err(|err| invalid_fields.push(err))
+                   .ok()
+        }
+        else {
+            invalid_fields.push(InvalidField::missing("id_type"));
+            None
+        };
+
+        match (&query.id_number, id_type) {
+            (None, _) => {
+                invalid_fields.push(InvalidField::missing("id_number"));
+            },
+            (Some(id_number), Some(id_type))
+                if !id_type.is_valid_id(id_number) =>
+            {
+                invalid_fields.push(InvalidField::new(
+                    "id_number",
+                    format!("{id_number:?} is not a valid {id_type}"),
+                ));
+            },
+            _ => {},
+        }
+
+        if let Some(language) = &query.language {
+            if let Err(err) = validate_language(language) {
+                invalid_fields.push(err);
+            }
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        let h2h = parse_bool(&mut invalid_fields, "h2h", query.h2h.as_deref())
+            .unwrap_or_default();
+
+        let civ_stats = parse_bool(&mut invalid_fields,
+                                   "civ_stats",
+                                   query.civ_stats.as_deref())
+            .unwrap_or_default();
+
+        let sort = query.sort
+                        .as_deref()
+                        .map(str::parse::<PlayerSort>)
+                        .transpose()
+                        .map_err(|err| invalid_fields.push(err))
+                        .ok()
+                        .flatten();
+
+        let team_sort = query.team_sort
+                             .as_deref()
+                             .map(str::parse::<TeamSort>)
+                             .transpose()
+                             .map_err(|err| invalid_fields.push(err))
+                             .ok()
+                             .flatten();
+
+        match (id_type, query.id_number) {
+            (Some(id_type), Some(id_number)) if invalid_fields.is_empty() => {
+                Ok(Self { language: query.language,
+                          game: query.game,
+                          id_type,
+                          id_number,
+                          h2h,
+                          civ_stats,
+                          sort,
+                          team_sort })
+            },
+            _ => Err(RequestValidationError { invalid_fields }),
+        }
+    }
+}
+
+/// Check that we support the requested `language`
+///
+/// # Errors
+/// Returns an [`InvalidField`] for `language` if it is not one of
+/// [`static@crate::LANGUAGE_STRINGS`]
+pub(crate) fn validate_language(language: &str) -> Result<(), InvalidField> {
+    if LANGUAGE_STRINGS.contains(&language) {
+        Ok(())
+    }
+    else {
+        Err(InvalidField::new("language",
+                              format!("unsupported language {:?}, expected \
+                                       one of {}",
+                                      language,
+                                      LANGUAGE_STRINGS.join(", "))))
+    }
+}
+
+/// Check that we support the requested `game`
+///
+/// # Errors
+/// Returns an [`InvalidField`] for `game` if it is not one of
+/// [`static@crate::GAME_STRINGS`]
+pub(crate) fn validate_game(game: &str) -> Result<(), InvalidField> {
+    if GAME_STRINGS.contains(&game) {
+        Ok(())
+    }
+    else {
+        Err(InvalidField::new("game",
+                              format!("unsupported game {:?}, expected one \
+                                       of {}",
+                                      game,
+                                      GAME_STRINGS.join(", "))))
+    }
+}
+
+/// Check that `profile_id` is a valid aoe2.net profile ID, which is numeric
+///
+/// # Errors
+/// Returns an [`InvalidField`] for `profile_id` if it is not numeric
+pub(crate) fn validate_profile_id(profile_id: &str)
+                                  -> Result<(), InvalidField> {
+    if IdType::ProfileId.is_valid_id(profile_id) {
+        Ok(())
+    }
+    else {
+        Err(InvalidField::new("profile_id",
+                              format!("{profile_id:?} is not a valid \
+                                       profile_id")))
+    }
+}
+
+/// Parse an optional numeric query parameter, pushes an [`InvalidField`] if
+/// it is not a number within `range`
+pub(crate) fn parse_in_range<T>(invalid_fields: &mut Vec<InvalidField>,
+                                name: &'static str,
+                                value: Option<&str>,
+                                range: RangeInclusive<T>)
+                                -> Option<T>
+    where T: FromStr + PartialOrd + fmt::Display,
+{
+    let value = value?;
+
+    match value.parse::<T>() {
+        Ok(number) if range.contains(&number) => Some(number),
+        _ => {
+            invalid_fields.push(InvalidField::new(
+                name,
+                format!("{:?} is not a number between {} and {}",
+                        value,
+                        range.start(),
+                        range.end()),
+            ));
+            None
+        },
+    }
+}
+
+/// Parse an optional boolean query parameter, pushes an [`InvalidField`] if
+/// it is neither `true` nor `false`
+pub(crate) fn parse_bool(invalid_fields: &mut Vec<InvalidField>,
+                         name: &'static str,
+                         value: Option<&str>)
+                         -> Option<bool> {
+    let value = value?;
+
+    value.parse::<bool>()
+         .map_err(|_| {
+             invalid_fields.push(InvalidField::new(
+                 name,
+                 format!("{value:?} is not a boolean, expected true or false"),
+             ));
+         })
+         .ok()
 }
 
 impl MatchInfoRequest {
@@ -92,3 +550,93 @@ impl MatchInfoRequest {
             .expect("Unable to write data");
     }
 }
+
+#[test]
+fn validation_lists_every_invalid_field() {
+    let query = MatchInfoQuery { language: Some("xx".to_string()),
+                                 game: Some("aoe2de".to_string()),
+                                 id_type: Some("player".to_string()),
+                                 id_number: None,
+                                 ..MatchInfoQuery::default() };
+
+    let err = MatchInfoRequest::try_from(query).unwrap_err();
+
+    let names = err.invalid_fields
+                   .iter()
+                   .map(|field| field.name.as_ref())
+                   .collect::<Vec<_>>();
+
+    assert_eq!(names, vec!["id_type", "id_number", "language"]);
+}
+
+#[test]
+fn id_number_is_validated_for_id_type() {
+    let query = |id_type: &str, id_number: &str| {
+        MatchInfoQuery { id_type: Some(id_type.to_string()),
+                         id_number: Some(id_number.to_string()),
+                         ..MatchInfoQuery::default() }
+    };
+
+    assert!(MatchInfoRequest::try_from(query("profile_id", "196240")).is_ok());
+    assert!(MatchInfoRequest::try_from(query("profile_id", "abc")).is_err());
+    assert!(MatchInfoRequest::try_from(query("match_uuid",
+                                             "66ec2575-5ee4-d241-a1fc-d7ffeffb48b6"))
+            .is_ok());
+    assert!(MatchInfoRequest::try_from(query("match_uuid", "196240")).is_err());
+}
+
+#[test]
+fn h2h_is_opt_in() {
+    let query = |h2h: Option<&str>| MatchInfoQuery {
+        id_type: Some("profile_id".to_string()),
+        id_number: Some("196240".to_string()),
+        h2h: h2h.map(str::to_string),
+        ..MatchInfoQuery::default()
+    };
+
+    assert!(!MatchInfoRequest::try_from(query(None)).unwrap().h2h);
+    assert!(MatchInfoRequest::try_from(query(Some("true"))).unwrap().h2h);
+    assert_eq!(MatchInfoRequest::try_from(query(Some("yes"))).unwrap_err()
+                                                              .invalid_fields[0]
+                                                              .name,
+               "h2h");
+}
+
+#[test]
+fn civ_stats_are_opt_in() {
+    let query = |civ_stats: Option<&str>| MatchInfoQuery {
+        id_type: Some("profile_id".to_string()),
+        id_number: Some("196240".to_string()),
+        civ_stats: civ_stats.map(str::to_string),
+        ..MatchInfoQuery::default()
+    };
+
+    assert!(!MatchInfoRequest::try_from(query(None)).unwrap().civ_stats);
+    assert!(MatchInfoRequest::try_from(query(Some("true"))).unwrap()
+                                                             .civ_stats);
+    assert!(MatchInfoRequest::try_from(query(Some("1"))).is_err());
+}
+
+#[test]
+fn sort_options_are_validated() {
+    let query = |sort: &str, team_sort: &str| MatchInfoQuery {
+        id_type: Some("profile_id".to_string()),
+        id_number: Some("196240".to_string()),
+        sort: Some(sort.to_string()),
+        team_sort: Some(team_sort.to_string()),
+        ..MatchInfoQuery::default()
+    };
+
+    let request = MatchInfoRequest::try_from(query("rating_desc", "requested"))
+        .unwrap();
+    assert_eq!((request.sort, request.team_sort),
+               (Some(PlayerSort::RatingDesc), Some(TeamSort::Requested)));
+
+    let names = MatchInfoRequest::try_from(query("elo", "random"))
+        .unwrap_err()
+        .invalid_fields
+        .into_iter()
+        .map(|field| field.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["sort", "team_sort"]);
+}
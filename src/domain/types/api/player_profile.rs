@@ -0,0 +1,144 @@
+//! Datastructures of the `player/{profile_id}` endpoint
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    validate_game,
+    validate_profile_id,
+    Rating,
+};
+use crate::domain::types::{
+    aoc_ref::teams::Team,
+    error::{
+        ErrorMessageToFrontend,
+        RequestValidationError,
+    },
+};
+
+/// Datastructure for an incoming `request` on the `player/{profile_id}`
+/// endpoint
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerProfileRequest {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Requested game (Optional), Standard value is "aoe2de"
+    pub game: Option<String>,
+}
+
+/// Unvalidated query parameters of a [`PlayerProfileRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlayerProfileQuery {
+    /// Requested game
+    pub game: Option<String>,
+}
+
+impl PlayerProfileRequest {
+    /// Create a [`PlayerProfileRequest`] from the `profile_id` in the path and
+    /// the `query` parameters
+    ///
+    /// # Errors
+    /// Returns a [`RequestValidationError`] with every invalid field
+    pub fn with_query(profile_id: String,
+                      query: PlayerProfileQuery)
+                      -> Result<Self, RequestValidationError> {
+        let mut invalid_fields = Vec::new();
+
+        if let Err(err) = validate_profile_id(&profile_id) {
+            invalid_fields.push(err);
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        if invalid_fields.is_empty() {
+            Ok(Self { profile_id,
+                      game: query.game })
+        }
+        else {
+            Err(RequestValidationError { invalid_fields })
+        }
+    }
+}
+
+/// Result of the `player/{profile_id}` endpoint, a player card that merges
+/// the `aoc-reference-data` with the ratings on aoe2.net
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Name of the player, the known name from the `aoc-reference-data` if
+    /// there is one
+    pub name: String,
+    /// Country of the player, if known
+    pub country: Option<String>,
+    /// Team of the player, if known
+    pub team: Option<PlayerTeam>,
+    /// Link to a twitch channel
+    pub twitch: Option<String>,
+    /// Link to a youtube channel
+    pub youtube: Option<String>,
+    /// Name of the player on liquipedia
+    pub liquipedia: Option<String>,
+    /// ID of the player on esportsearnings.com
+    pub esportsearnings: Option<i64>,
+    /// Current ratings of the player on each leaderboard
+    pub ratings: PlayerRatings,
+    /// Errors that happened while looking up the ratings
+    #[serde(default)]
+    pub error_message: Vec<ErrorMessageToFrontend>,
+}
+
+/// Team of a player from `teams.json`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerTeam {
+    /// Long form of the name
+    pub name: String,
+    /// (Optional) short form of the name
+    pub abbreviation: Option<String>,
+}
+
+impl From<&Team> for PlayerTeam {
+    fn from(team: &Team) -> Self {
+        Self { name: team.name.clone(),
+               abbreviation: team.abbreviation.clone() }
+    }
+}
+
+/// Current [`Rating`] of a player for each leaderboard, `None` if the player
+/// isn't ranked on it
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerRatings {
+    /// `1v1 Random Map`
+    pub rm_1v1: Option<Rating>,
+    /// `Team Random Map`
+    pub rm_team: Option<Rating>,
+    /// `1v1 Empire Wars`
+    pub ew_1v1: Option<Rating>,
+    /// `1v1 Deathmatch`
+    pub dm_1v1: Option<Rating>,
+}
+
+#[test]
+fn profile_id_must_be_numeric() {
+    let err = PlayerProfileRequest::with_query(
+        "TheViper".to_string(),
+        PlayerProfileQuery { game: Some("aoe1".to_string()) },
+    )
+    .unwrap_err();
+
+    assert_eq!(err.invalid_fields
+                  .iter()
+                  .map(|field| field.name.as_ref())
+                  .collect::<Vec<_>>(),
+               vec!["profile_id", "game"]);
+
+    assert!(PlayerProfileRequest::with_query("196240".to_string(),
+                                             PlayerProfileQuery::default())
+            .is_ok());
+}
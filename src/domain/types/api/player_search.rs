@@ -0,0 +1,162 @@
+//! Datastructures of the `players/search` endpoint
+
+use std::convert::TryFrom;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::validate_game;
+use crate::domain::types::{
+    aoc_ref::{
+        players::Player,
+        teams::Team,
+    },
+    error::{
+        InvalidField,
+        RequestValidationError,
+    },
+};
+
+/// Datastructure for an incoming `request` on the `players/search` endpoint
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerSearchRequest {
+    /// Prefix or (misspelled) name of the player
+    pub q: String,
+    /// Requested game (Optional), Standard value is "aoe2de"
+    pub game: Option<String>,
+}
+
+/// Unvalidated query parameters of a [`PlayerSearchRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct PlayerSearchQuery {
+    /// Prefix or (misspelled) name of the player
+    pub q: Option<String>,
+    /// Requested game
+    pub game: Option<String>,
+}
+
+impl TryFrom<PlayerSearchQuery> for PlayerSearchRequest {
+    type Error = RequestValidationError;
+
+    fn try_from(query: PlayerSearchQuery) -> Result<Self, Self::Error> {
+        let mut invalid_fields = Vec::new();
+
+        match query.q.as_deref().map(str::trim) {
+            None => invalid_fields.push(InvalidField::missing("q")),
+            Some("") => {
+                invalid_fields.push(InvalidField::new("q",
+                                                      "must not be empty"));
+            },
+            Some(_) => {},
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        match query.q {
+            Some(q) if invalid_fields.is_empty() => {
+                Ok(Self { q: q.trim().to_string(),
+                          game: query.game })
+            },
+            _ => Err(RequestValidationError { invalid_fields }),
+        }
+    }
+}
+
+/// Result of the `players/search` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerSearchResponse {
+    /// Found players, best matches first
+    pub players: Vec<PlayerSearchResult>,
+}
+
+/// Where a [`PlayerSearchResult`] was found
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerSearchSource {
+    /// `players.yaml` of the `aoc-reference-data`
+    ReferenceData,
+    /// Leaderboard of aoe2.net
+    Leaderboard,
+}
+
+/// A player found by the `players/search` endpoint
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerSearchResult {
+    /// Name of the player
+    pub name: String,
+    /// `profile_id`s of the player, the main account first
+    pub profile_ids: Vec<String>,
+    /// Country of the player, if known
+    pub country: Option<String>,
+    /// Name of the team of the player, if known
+    pub team: Option<String>,
+    /// Known streaming channels of the player
+    pub streams: StreamingLinks,
+    /// Where the player was found
+    pub source: PlayerSearchSource,
+}
+
+/// Streaming channels of a player from the `aoc-reference-data`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamingLinks {
+    /// Link to a channel on twitch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub twitch: Option<String>,
+    /// Link to a channel on youtube
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub youtube: Option<String>,
+    /// Link to a channel on douyu
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub douyu: Option<String>,
+    /// Link to a channel on mixer (outdated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mixer: Option<String>,
+}
+
+impl PlayerSearchResult {
+    /// Create a [`PlayerSearchResult`] for a `player` of the
+    /// `aoc-reference-data` with its `profile_ids` of the `game` and the
+    /// `team` it is part of
+    #[must_use]
+    pub fn with_reference_data(player: &Player,
+                               game: &str,
+                               team: Option<&Team>)
+                               -> Self {
+        Self { name: player.name.clone(),
+               profile_ids: player.platforms.profile_ids(game).to_vec(),
+               country: Some(player.country.clone()),
+               team: team.map(|team| team.name.clone()),
+               streams: StreamingLinks { twitch: player.twitch.clone(),
+                                         youtube: player.youtube.clone(),
+                                         douyu: player.douyu.clone(),
+                                         mixer: player.mixer.clone() },
+               source: PlayerSearchSource::ReferenceData }
+    }
+}
+
+#[test]
+fn search_query_is_validated() {
+    let query = |q: Option<&str>, game: Option<&str>| PlayerSearchQuery {
+        q: q.map(str::to_string),
+        game: game.map(str::to_string),
+    };
+
+    let request = PlayerSearchRequest::try_from(query(Some(" vip "), None));
+    assert_eq!(request.unwrap().q, "vip");
+
+    let err = PlayerSearchRequest::try_from(query(Some("  "), Some("aoe1")))
+        .unwrap_err();
+    assert_eq!(err.invalid_fields
+                  .iter()
+                  .map(|field| field.name.as_ref())
+                  .collect::<Vec<_>>(),
+               vec!["q", "game"]);
+
+    assert!(PlayerSearchRequest::try_from(query(None, None)).is_err());
+}
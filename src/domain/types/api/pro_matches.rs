@@ -0,0 +1,94 @@
+//! Datastructures of the `live/pro_matches` endpoint
+
+use std::convert::TryFrom;
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    validate_language,
+    Server,
+};
+use crate::domain::types::error::RequestValidationError;
+
+/// Datastructure for an incoming `request` on the `live/pro_matches` endpoint
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ProMatchesRequest {
+    /// Requested language (Optional), Standard value is "en"
+    pub language: Option<String>,
+}
+
+/// Unvalidated query parameters of a [`ProMatchesRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct ProMatchesQuery {
+    /// Requested language
+    pub language: Option<String>,
+}
+
+impl TryFrom<ProMatchesQuery> for ProMatchesRequest {
+    type Error = RequestValidationError;
+
+    fn try_from(query: ProMatchesQuery) -> Result<Self, Self::Error> {
+        let mut invalid_fields = Vec::new();
+
+        if let Some(language) = &query.language {
+            if let Err(err) = validate_language(language) {
+                invalid_fields.push(err);
+            }
+        }
+
+        if invalid_fields.is_empty() {
+            Ok(Self { language: query.language })
+        }
+        else {
+            Err(RequestValidationError { invalid_fields })
+        }
+    }
+}
+
+/// Result of the `live/pro_matches` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProMatchesResponse {
+    /// Ongoing matches with a player of the `aoc-reference-data`, most
+    /// recently started first
+    pub matches: Vec<ProMatch>,
+}
+
+/// An ongoing match with at least one player of the `aoc-reference-data`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProMatch {
+    /// aoe2.net ID of the match
+    pub match_id: String,
+    /// Translated name of the map
+    pub map: String,
+    /// Translated name of the rating type
+    pub rating_type: String,
+    /// Server of the match
+    pub server: Server,
+    /// Time the match started as ISO 8601 timestamp in UTC
+    pub started: Option<String>,
+    /// Players of the match
+    pub players: Vec<ProMatchPlayer>,
+}
+
+/// A player in a [`ProMatch`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProMatchPlayer {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Current in-game name of the player
+    pub name: String,
+    /// Known name of the player from the `aoc-reference-data`, if there is
+    /// one
+    pub alias: Option<String>,
+    /// Country of the player, if known
+    pub country: Option<String>,
+    /// Translated name of the civilisation
+    pub civ: String,
+    /// Team of the player, if known
+    pub team: Option<i64>,
+    /// Rating of the player, if known
+    pub rating: Option<i64>,
+}
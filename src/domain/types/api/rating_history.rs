@@ -0,0 +1,184 @@
+//! Datastructures of the `player/{profile_id}/rating_history` endpoint
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    parse_in_range,
+    validate_game,
+    validate_profile_id,
+};
+use crate::{
+    domain::{
+        types::error::RequestValidationError,
+        util,
+    },
+    STANDARD,
+};
+
+/// Kind of leaderboard of the rating history if none is requested
+const DEFAULT_LEADERBOARD: &str = "rm_1v1";
+/// Amount of games in the rating history if none is requested
+const DEFAULT_COUNT: i32 = 100;
+/// Maximum amount of games aoe2.net returns for a rating history
+const MAX_COUNT: i32 = 10000;
+
+/// Datastructure for an incoming `request` on the
+/// `player/{profile_id}/rating_history` endpoint
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct RatingHistoryRequest {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Requested game (Optional), Standard value is "aoe2de"
+    pub game: Option<String>,
+    /// Leaderboard of the rating history
+    pub leaderboard_id: i32,
+    /// Amount of most recent games in the rating history
+    pub count: i32,
+    /// Amount of points the time series is downsampled to (Optional)
+    pub points: Option<usize>,
+}
+
+/// Unvalidated query parameters of a [`RatingHistoryRequest`]
+#[derive(Debug, Default, Deserialize, Clone, PartialEq, Eq)]
+pub struct RatingHistoryQuery {
+    /// Requested game
+    pub game: Option<String>,
+    /// Leaderboard of the rating history
+    pub leaderboard_id: Option<String>,
+    /// Amount of most recent games
+    pub count: Option<String>,
+    /// Amount of points to downsample to
+    pub points: Option<String>,
+}
+
+impl RatingHistoryRequest {
+    /// Create a [`RatingHistoryRequest`] from the `profile_id` in the path and
+    /// the `query` parameters
+    ///
+    /// # Errors
+    /// Returns a [`RequestValidationError`] with every invalid field
+    ///
+    /// # Panics
+    /// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
+    /// returning None
+    pub fn with_query(profile_id: String,
+                      query: RatingHistoryQuery)
+                      -> Result<Self, RequestValidationError> {
+        let mut invalid_fields = Vec::new();
+
+        if let Err(err) = validate_profile_id(&profile_id) {
+            invalid_fields.push(err);
+        }
+
+        if let Some(game) = &query.game {
+            if let Err(err) = validate_game(game) {
+                invalid_fields.push(err);
+            }
+        }
+
+        let game = query.game
+                        .as_deref()
+                        .unwrap_or(*STANDARD.get(&"game").unwrap());
+
+        let leaderboard_id = parse_in_range(&mut invalid_fields,
+                                            "leaderboard_id",
+                                            query.leaderboard_id.as_deref(),
+                                            0..=i32::MAX)
+            .or_else(|| util::leaderboard_id(game, DEFAULT_LEADERBOARD))
+            .unwrap_or_default();
+
+        let count = parse_in_range(&mut invalid_fields,
+                                   "count",
+                                   query.count.as_deref(),
+                                   1..=MAX_COUNT)
+            .unwrap_or(DEFAULT_COUNT);
+
+        // A time series needs at least its first and last point
+        let points = parse_in_range(&mut invalid_fields,
+                                    "points",
+                                    query.points.as_deref(),
+                                    2..=usize::MAX);
+
+        if invalid_fields.is_empty() {
+            Ok(Self { profile_id,
+                      game: query.game,
+                      leaderboard_id,
+                      count,
+                      points })
+        }
+        else {
+            Err(RequestValidationError { invalid_fields })
+        }
+    }
+}
+
+/// Result of the `player/{profile_id}/rating_history` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatingHistoryResponse {
+    /// aoe2.net profile ID of the player
+    pub profile_id: String,
+    /// Leaderboard of the rating history
+    pub leaderboard_id: i32,
+    /// Rating after each game, oldest first, possibly downsampled
+    pub points: Vec<RatingPoint>,
+    /// Highest rating within the requested games
+    pub peak: Option<RatingPoint>,
+    /// Lowest rating within the requested games
+    pub trough: Option<RatingPoint>,
+    /// Current streak, positive for wins and negative for losses
+    pub current_streak: i64,
+}
+
+/// Rating of a player after a game
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatingPoint {
+    /// Time of the game as ISO 8601 timestamp in UTC
+    pub timestamp: String,
+    /// Rating after the game
+    pub rating: i64,
+    /// Change of the rating through the game, `0` for the oldest game
+    pub delta: i64,
+    /// Wins on the leaderboard after the game
+    pub num_wins: i64,
+    /// Losses on the leaderboard after the game
+    pub num_losses: i64,
+}
+
+#[test]
+fn rating_history_query_is_validated() {
+    let query = RatingHistoryQuery { game: None,
+                                     leaderboard_id: Some("4".to_string()),
+                                     count: None,
+                                     points: Some("50".to_string()) };
+
+    let request =
+        RatingHistoryRequest::with_query("196240".to_string(), query).unwrap();
+    assert_eq!((request.leaderboard_id, request.count, request.points),
+               (4, DEFAULT_COUNT, Some(50)));
+
+    // The default leaderboard depends on the game
+    let query = RatingHistoryQuery { game: Some("aoe3de".to_string()),
+                                     leaderboard_id: None,
+                                     count: None,
+                                     points: None };
+
+    let request =
+        RatingHistoryRequest::with_query("196240".to_string(), query).unwrap();
+    assert_eq!(request.leaderboard_id, 1);
+
+    let query = RatingHistoryQuery { game: None,
+                                     leaderboard_id: Some("rm".to_string()),
+                                     count: Some("0".to_string()),
+                                     points: Some("1".to_string()) };
+
+    let err = RatingHistoryRequest::with_query("196240".to_string(), query)
+        .unwrap_err();
+    assert_eq!(err.invalid_fields
+                  .iter()
+                  .map(|field| field.name.as_ref())
+                  .collect::<Vec<_>>(),
+               vec!["leaderboard_id", "count", "points"]);
+}
@@ -4,6 +4,11 @@ use std::{
     num::ParseIntError,
 };
 
+use api_client::error::{
+    ApiRequestGetError,
+    ClientRequestError,
+};
+use http::StatusCode;
 use serde::{
     Deserialize,
     Serialize,
@@ -11,26 +16,128 @@ use serde::{
 use thiserror::Error;
 
 use crate::domain::types::{
+    api::PlayerCandidate,
     ApiRequest,
     GithubFileRequest,
 };
 
-#[derive(Error,
-           displaydoc::Display,
-           Debug,
-           Serialize,
-           Clone,
-           PartialEq,
-           Deserialize)]
-pub enum ErrorMessageToFrontend {
-    /// Generic error from the Responder: {0}
-    GenericResponderError(Cow<'static, str>),
-    /// Matchinfo processing failed: {0}
-    HardFail(Cow<'static, str>),
-    /// Matchinfo processing failed: {0}
-    SoftFail(Cow<'static, str>),
-    /// Rocover: {0}
-    Recover(Cow<'static, str>),
+/// Severity of an [`ErrorMessageToFrontend`]
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ErrorSeverity {
+    /// Processing failed, there is no data to show
+    HardFail,
+    /// Processing succeeded, but parts of the data are missing
+    SoftFail,
+    /// Data has been recovered, but might be outdated
+    Recover,
+}
+
+/// Structured error message that is handed to the frontend within a
+/// `MatchInfoResult`
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Deserialize)]
+pub struct ErrorMessageToFrontend {
+    /// Machine-readable identifier, e.g. `leaderboard_not_found`
+    pub code: Cow<'static, str>,
+    /// How severe the error is for the returned data
+    pub severity: ErrorSeverity,
+    /// Human-readable description of the error
+    pub message: Cow<'static, str>,
+    /// HTTP status code our API responds with for a `HardFail`, only used
+    /// internally
+    #[serde(skip)]
+    pub status: Option<StatusCode>,
+}
+
+impl ErrorMessageToFrontend {
+    /// Create a new [`ErrorMessageToFrontend`]
+    pub fn new(code: impl Into<Cow<'static, str>>,
+               severity: ErrorSeverity,
+               message: impl Into<Cow<'static, str>>)
+               -> Self {
+        Self { code: code.into(),
+               severity,
+               message: message.into(),
+               status: None }
+    }
+
+    /// Set the HTTP status code our API responds with
+    #[must_use]
+    pub fn with_status(mut self,
+                       status: StatusCode)
+                       -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Processing failed, there is no data to show
+    pub fn hard_fail(code: impl Into<Cow<'static, str>>,
+                     message: impl Into<Cow<'static, str>>)
+                     -> Self {
+        Self::new(code, ErrorSeverity::HardFail, message)
+    }
+
+    /// Processing succeeded, but parts of the data are missing
+    pub fn soft_fail(code: impl Into<Cow<'static, str>>,
+                     message: impl Into<Cow<'static, str>>)
+                     -> Self {
+        Self::new(code, ErrorSeverity::SoftFail, message)
+    }
+
+    /// Data has been recovered, but might be outdated
+    pub fn recover(code: impl Into<Cow<'static, str>>,
+                   message: impl Into<Cow<'static, str>>)
+                   -> Self {
+        Self::new(code, ErrorSeverity::Recover, message)
+    }
+}
+
+/// A single invalid field of a request to our API
+#[derive(Error, Debug, Serialize, Clone, PartialEq, Eq)]
+#[error("{name}: {reason}")]
+pub struct InvalidField {
+    /// Name of the field
+    pub name: Cow<'static, str>,
+    /// Why the value of the field is invalid
+    pub reason: Cow<'static, str>,
+}
+
+impl InvalidField {
+    /// Create a new [`InvalidField`]
+    pub fn new(name: impl Into<Cow<'static, str>>,
+               reason: impl Into<Cow<'static, str>>)
+               -> Self {
+        Self { name: name.into(),
+               reason: reason.into() }
+    }
+
+    /// A required field is missing
+    pub fn missing(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::new(name, "is missing")
+    }
+}
+
+/// Validation of a request to our API failed, contains every invalid field
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub struct RequestValidationError {
+    /// All fields of the request that are invalid
+    pub invalid_fields: Vec<InvalidField>,
+}
+
+impl std::fmt::Display for RequestValidationError {
+    fn fmt(&self,
+           f: &mut std::fmt::Formatter<'_>)
+           -> std::fmt::Result {
+        write!(f, "Invalid request parameters: ")?;
+
+        for (i, field) in self.invalid_fields.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{field}")?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Error type for the `MatchInfoProcessor`
@@ -56,6 +163,39 @@ pub enum ProcessingError {
     TranslationError(String, usize),
     /// Player id {0} is not ranked on that leaderboard.
     NotRankedLeaderboard(u64),
+    /// Haven't found an alias for player id: {0}
+    AliasNotFound(u64),
+}
+
+impl ProcessingError {
+    /// Machine-readable identifier of the error for the frontend
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProcessingError::CivilisationError => "civilisation_not_found",
+            ProcessingError::AssemblyError => "assembly_failed",
+            ProcessingError::ResponderMalfunction(err) => err.code(),
+            ProcessingError::ProcessIntParsingError(_) |
+            ProcessingError::SerdeStringConversionError(_) => "parsing_failed",
+            ProcessingError::DividingByZeroError => "dividing_by_zero",
+            ProcessingError::LookupRatingNotFound(_) => "rating_not_found",
+            ProcessingError::LeaderboardNotFound(_) => "leaderboard_not_found",
+            ProcessingError::TranslationError(..) => "translation_not_found",
+            ProcessingError::NotRankedLeaderboard(_) => {
+                "not_ranked_leaderboard"
+            },
+            ProcessingError::AliasNotFound(_) => "alias_not_found",
+        }
+    }
+
+    /// HTTP status code our API responds with for this error
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ProcessingError::ResponderMalfunction(err) => err.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
 }
 
 /// Error type for the `MatchInfoResponder`
@@ -101,6 +241,70 @@ pub enum ResponderError {
     // },
     /// UUID parsing failed: {0}
     ParsingError(#[from] uuid::Error),
+    /// No player found with the name {0:?}.
+    PlayerNotFound(String),
+    /// Player name is ambiguous, it matches {0:?}.
+    AmbiguousPlayerName(Vec<PlayerCandidate>),
+}
+
+impl ResponderError {
+    /// Machine-readable identifier of the error for the frontend
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            ResponderError::RequestNotMatching { .. } => "request_not_matching",
+            ResponderError::NotFound(_) => "data_not_found",
+            ResponderError::HttpClient(_) |
+            ResponderError::OtherApiRequestError(_) => {
+                match self.status_code() {
+                    StatusCode::GATEWAY_TIMEOUT => "upstream_timeout",
+                    StatusCode::BAD_GATEWAY => "upstream_error",
+                    _ => "api_request_failed",
+                }
+            },
+            ResponderError::RespondIntParsingFailed(_) |
+            ResponderError::SerdeStringConversion(_) => "parsing_failed",
+            ResponderError::TranslationFailed(..) |
+            ResponderError::TranslationPosError(..) |
+            ResponderError::TranslationHasBeenMoved => "translation_failed",
+            ResponderError::LastMatchNotFound => "last_match_not_found",
+            ResponderError::InvalidIdType(_) => "invalid_id_type",
+            ResponderError::InvalidReqType(_) => "invalid_request_type",
+            ResponderError::ParsingError(_) => "invalid_uuid",
+            ResponderError::PlayerNotFound(_) => "player_not_found",
+            ResponderError::AmbiguousPlayerName(_) => "ambiguous_player_name",
+        }
+    }
+
+    /// HTTP status code our API responds with for this error
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ResponderError::LastMatchNotFound |
+            ResponderError::PlayerNotFound(_) => StatusCode::NOT_FOUND,
+            ResponderError::AmbiguousPlayerName(_) => {
+                StatusCode::MULTIPLE_CHOICES
+            },
+            ResponderError::InvalidIdType(_) |
+            ResponderError::ParsingError(_) => StatusCode::BAD_REQUEST,
+            ResponderError::HttpClient(err) => upstream_status_code(err),
+            ResponderError::OtherApiRequestError(err) => err.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Returns `true` if the upstream API responded with `404 Not Found`
+    #[must_use]
+    pub fn is_upstream_not_found(&self) -> bool {
+        matches!(self,
+                 ResponderError::OtherApiRequestError(
+                     ApiRequestError::ApiClientError(
+                         ClientRequestError::ApiRequestGetError(
+                             ApiRequestGetError::Error { status, .. },
+                         ),
+                     ),
+                 ) if *status == StatusCode::NOT_FOUND)
+    }
 }
 
 /// Error type for a `FileRequest`
@@ -148,6 +352,47 @@ pub enum ApiRequestError {
     },
 }
 
+impl ApiRequestError {
+    /// HTTP status code our API responds with for this error, failures of
+    /// the upstream API are mapped to `502 Bad Gateway` and `504 Gateway
+    /// Timeout`
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ApiRequestError::HttpClientError(err) |
+            ApiRequestError::ApiClientError(ClientRequestError::RequestError(
+                err,
+            )) => upstream_status_code(err),
+            ApiRequestError::ApiClientError(
+                ClientRequestError::ApiRequestGetError(
+                    ApiRequestGetError::Error { status, .. },
+                ),
+            ) |
+            ApiRequestError::HttpClientErrorWithStatusCode(status)
+                if *status == StatusCode::GATEWAY_TIMEOUT =>
+            {
+                StatusCode::GATEWAY_TIMEOUT
+            },
+            ApiRequestError::RequestNotMatching { .. } => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            },
+            ApiRequestError::NotFoundResponse { .. } => StatusCode::NOT_FOUND,
+            _ => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+/// Timeouts of our HTTP client are mapped to `504 Gateway Timeout`, all other
+/// errors to `502 Bad Gateway`
+fn upstream_status_code(err: &reqwest::Error) -> StatusCode {
+    if err.is_timeout() {
+        StatusCode::GATEWAY_TIMEOUT
+    }
+    else {
+        StatusCode::BAD_GATEWAY
+    }
+}
+
 /// Error type for an [`ApiRequest`]
 #[derive(Error, displaydoc::Display, Debug)]
 pub enum TestCaseError {
@@ -1,24 +1,132 @@
-//! Wrapper structs for usages within the in-memory DB
+//! Provider-neutral match data and the wrapper struct for usages within the
+//! processing stage
+//!
+//! Every [`MatchDataProvider`](crate::domain::provider::MatchDataProvider)
+//! converts the responses of its upstream API into these types, so the
+//! processing stage doesn't need to know where the data came from.
 
-use aoe2net::types::api::Player as aoe2net_Player;
-use dashmap::DashMap;
-use serde::Serialize;
-use serde_json::Value as JsonValue;
+use std::collections::{
+    BTreeMap,
+    HashMap,
+};
 
-use super::InMemoryDb;
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
+use super::aoc_ref::RefDataLists;
+
+/// Everything gathered from a provider and the `aoc-reference-data` that is
+/// needed to process a `matchinfo` request
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct MatchDataResponses {
-    pub aoe2net: Aoe2NetResponses,
-    pub db: InMemoryDb,
+    /// Game of aoe2net the match was played in, e.g. `aoe2de`
+    pub game: String,
+    /// The match that is processed
+    pub match_data: MatchData,
+    /// `profile_id` of the player the request was made for, `None` if a
+    /// match was requested by its ID
+    pub requested_profile_id: Option<String>,
+    /// Ratings of the players on the leaderboard of the match, keyed by their
+    /// `profile_id`
+    pub ratings: HashMap<String, PlayerRating>,
+    /// Translations in the requested language
+    pub translations: Translations,
+    /// Players (Aliases), Platforms and Teams of `aoc-reference-data`
+    pub ref_data: RefDataLists,
 }
 
-#[derive(Debug, Clone, Default, Serialize)]
-pub struct Aoe2NetResponses {
-    pub leaderboard_id: Option<String>,
-    pub player_last_match: Option<JsonValue>,
-    pub leaderboard: DashMap<String, JsonValue>,
-    pub rating_history: DashMap<String, JsonValue>,
-    pub players_temp: Vec<aoe2net_Player>,
-    pub match_id: Option<JsonValue>,
+/// A match as delivered by a provider
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchData {
+    /// ID of the match at the provider
+    pub match_id: Option<String>,
+    /// ID of the leaderboard the match is rated on
+    pub leaderboard_id: Option<i32>,
+    /// ID of the rating type, see [`Translations`]
+    pub rating_type: Option<usize>,
+    /// ID of the map type, see [`Translations`]
+    pub map_type: Option<usize>,
+    /// ID of the game type, see [`Translations`]
+    pub game_type: Option<usize>,
+    /// Location of the server the match is played on
+    pub server: Option<String>,
+    /// Unix timestamp of the start of the match
+    pub started: Option<i64>,
+    /// Unix timestamp of the end of the match, `None` while it is running
+    pub finished: Option<i64>,
+    /// Players of the match
+    pub players: Vec<MatchPlayerData>,
+}
+
+/// A player of a [`MatchData`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchPlayerData {
+    /// ID of the player at the provider
+    pub profile_id: String,
+    /// Current in-game name of the player
+    pub name: String,
+    /// ID of the civilisation, see [`Translations`]
+    pub civ: Option<usize>,
+    /// Color of the player
+    pub color: i64,
+    /// Slot of the player in the lobby
+    pub slot: i64,
+    /// Team of the player, `-1` for a free for all
+    pub team: i64,
+}
+
+/// The last match of a player
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerLastMatch {
+    /// `profile_id` of the player
+    pub profile_id: Option<String>,
+    /// The last match the player played or is playing
+    pub last_match: MatchData,
+}
+
+/// Rating of a player on a leaderboard
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerRating {
+    /// Current rating
+    pub rating: u32,
+    /// Amount of won matches
+    pub wins: u64,
+    /// Amount of lost matches
+    pub losses: u64,
+    /// Current streak, negative for a losing streak
+    pub streak: i32,
+    /// Standing on the leaderboard, `None` if the player isn't ranked on it
+    pub standing: Option<LeaderboardStanding>,
+}
+
+/// Standing of a player on a leaderboard
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardStanding {
+    /// Rank on the leaderboard
+    pub rank: u64,
+    /// Highest rating the player ever had on the leaderboard
+    pub highest_rating: u32,
+    /// Country of the player as lowercase ISO 3166-1 alpha-2 code
+    pub country: Option<String>,
+}
+
+/// Translations of the IDs of a category, e.g. `civ` or `map_type`, into
+/// their names
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Translations(pub BTreeMap<String, BTreeMap<usize, String>>);
+
+impl Translations {
+    /// Returns the name of `id` in the translation `category`
+    #[must_use]
+    pub fn lookup(&self,
+                  category: &str,
+                  id: usize)
+                  -> Option<&str> {
+        self.0
+            .get(category)
+            .and_then(|names| names.get(&id))
+            .map(String::as_str)
+    }
 }
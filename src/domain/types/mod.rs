@@ -8,6 +8,9 @@ pub mod match_data;
 pub mod requests;
 pub mod testing;
 
+use std::sync::Arc;
+
+use aoe2net::types::api::Match;
 use dashmap::DashMap;
 pub use match_data::MatchDataResponses;
 pub use requests::*;
@@ -15,22 +18,95 @@ use serde::Serialize;
 use serde_json::Value as JsonValue;
 use tracing::trace;
 
-use self::aoc_ref::RefDataLists;
-use crate::STANDARD;
+use self::{
+    aoc_ref::RefDataLists,
+    api::{
+        HeadToHead,
+        MatchInfoRequest,
+        MatchInfoResult,
+    },
+};
+use crate::{
+    persistence::in_memory_db::ttl_cache::TtlCache,
+    STANDARD,
+};
 
 /// The "Database" we use, which is in-memory for lookup of
 /// player names and other "more" static content
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct InMemoryDb {
-    /// Translations for aoe2net
-    pub aoe2net_languages: DashMap<String, JsonValue>,
+    /// Translations for aoe2net, keyed by the game and then by the language
+    pub aoe2net_languages: DashMap<String, DashMap<String, JsonValue>>,
     /// Containing the Players (Aliases), Platforms and Teams of
     /// aoc-reference-data
     pub github_file_content: RefDataLists,
+    /// Last successfully processed [`MatchInfoResult`] for each
+    /// [`MatchInfoRequest`], used as a fallback while the upstream is not
+    /// available. Expires after [`static@crate::LAST_GOOD_MATCH_INFO_TTL`]
+    #[serde(skip)]
+    pub last_good_match_infos: TtlCache<MatchInfoRequest, MatchInfoResult>,
+    /// Cached [`HeadToHead`] records of a player pair. Expire after
+    /// [`static@crate::HEAD_TO_HEAD_CACHE_TTL`]
+    #[serde(skip)]
+    pub head_to_heads: TtlCache<HeadToHeadKey, HeadToHead>,
+    /// Matches of aoe2.net with a player of the `aoc-reference-data` found
+    /// by the pro match discovery, keyed by their `match_id`
+    #[serde(skip)]
+    pub pro_matches: Arc<DashMap<String, Match>>,
+    /// Raw responses of aoestats.io, keyed by the full request `URI`. Expire
+    /// after [`static@crate::AOESTATS_CACHE_TTL`]
+    #[serde(skip)]
+    pub aoestats_responses: TtlCache<String, JsonValue>,
+}
+
+/// Identifies a cached [`HeadToHead`] record, the `profile_ids` are sorted so
+/// both orders of a player pair share an entry
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HeadToHeadKey {
+    /// Game of the matches
+    pub game: String,
+    /// Language the maps are translated to
+    pub language: String,
+    /// aoe2.net profile IDs of the players
+    pub profile_ids: (String, String),
+}
+
+impl HeadToHeadKey {
+    /// Create a [`HeadToHeadKey`] for a player pair in any order
+    #[must_use]
+    pub fn new(game: String,
+               language: String,
+               first: String,
+               second: String)
+               -> Self {
+        let profile_ids = if first <= second {
+            (first, second)
+        }
+        else {
+            (second, first)
+        };
+
+        Self { game,
+               language,
+               profile_ids }
+    }
 }
 
 impl InMemoryDb {
-    /// Return the [`InMemoryDb`] with only the language needed
+    /// Returns the preloaded translations of aoe2net for a `game` in a
+    /// `language`
+    #[must_use]
+    pub fn language_strings(&self,
+                            game: &str,
+                            language: &str)
+                            -> Option<JsonValue> {
+        self.aoe2net_languages
+            .get(game)?
+            .get(language)
+            .map(|strings| strings.value().clone())
+    }
+
+    /// Return the [`InMemoryDb`] with only the language needed for each game
     ///
     /// # Panics
     /// Could panic if the [`dashmap::DashMap`] in [`static@crate::STANDARD`] is
@@ -38,24 +114,31 @@ impl InMemoryDb {
     pub fn retain_only_requested_language(&mut self,
                                           language: &str)
                                           -> Self {
-        trace!("Checking DashMap for language: {:?}", language);
-        if self.aoe2net_languages.contains_key(language) {
-            trace!("Cleaning DashMap of other languages than language: {:?} ...",
+        // Set standard language value to `English`
+        // if wrong language is set in `Query`
+        let std_language = *(STANDARD.get(&"language").unwrap());
+
+        for languages in &self.aoe2net_languages {
+            trace!("Checking DashMap of game {:?} for language: {:?}",
+                   languages.key(),
                    language);
-            self.aoe2net_languages.retain(|lang, _| lang == language);
-        }
-        else {
-            // Set standard language value to `English`
-            // if wrong language is set in `Query`
-            let std_language = *(STANDARD.get(&"language").unwrap());
-            trace!("Cleaning DashMap of other languages than language: {:?} ...",
-                   std_language);
+            let retained_language = if languages.contains_key(language) {
+                language
+            }
+            else {
+                std_language
+            };
 
-            self.aoe2net_languages
-                .retain(|lang, _| lang == std_language);
+            trace!("Cleaning DashMap of other languages than language: {:?} ...",
+                   retained_language);
+            languages.retain(|lang, _| lang == retained_language);
         }
 
         Self { aoe2net_languages: self.aoe2net_languages.clone(),
-               github_file_content: self.github_file_content.clone() }
+               github_file_content: self.github_file_content.clone(),
+               last_good_match_infos: self.last_good_match_infos.clone(),
+               head_to_heads: self.head_to_heads.clone(),
+               pro_matches: self.pro_matches.clone(),
+               aoestats_responses: self.aoestats_responses.clone() }
     }
 }
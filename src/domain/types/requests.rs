@@ -7,6 +7,8 @@ use strum::AsRefStr;
 use typed_builder::TypedBuilder;
 use url::Url;
 
+use crate::domain::api_handler::retry::RetryPolicy;
+
 /// Datastructure storing different our `ApiClients`
 #[derive(Getters, Debug, Clone)]
 pub struct ApiClient {
@@ -61,6 +63,9 @@ pub struct ApiRequest {
     endpoint: String,
     /// A Vector of a tuple of query strings
     query: Vec<(String, String)>,
+    /// Policy for retrying the request if it failed
+    #[builder(default)]
+    retry_policy: RetryPolicy,
 }
 
 /// Datastructure to deal with `FileRequests` towards Github
@@ -12,6 +12,7 @@ use serde::{
 use serde_json::Value as JsonValue;
 
 use crate::domain::types::{
+    aoc_ref::RefDataLists,
     api::{
         MatchInfoRequest,
         MatchInfoResult,
@@ -101,3 +102,20 @@ impl TestCase {
         })
     }
 }
+
+/// Create indexed [`RefDataLists`] from the `players` and `teams` in the
+/// format of `players.yaml` and `teams.json`
+///
+/// # Panics
+/// Panics if the lists can't be parsed or indexed
+#[must_use]
+pub fn ref_data_lists(players: JsonValue,
+                      teams: JsonValue)
+                      -> RefDataLists {
+    let mut ref_data = RefDataLists::new();
+    ref_data.players = serde_json::from_value(players).unwrap();
+    ref_data.teams = serde_json::from_value(teams).unwrap();
+    ref_data.index().unwrap();
+
+    ref_data
+}
@@ -1,6 +1,7 @@
 //! Additional utility functions that are useful in different modules
 
 use std::{
+    convert::TryFrom,
     error::Error,
     fs,
     io::BufWriter,
@@ -10,13 +11,21 @@ use std::{
     },
 };
 
+use chrono::{
+    SecondsFormat,
+    TimeZone,
+    Utc,
+};
 use serde_json::Value as JsonValue;
 use url::Url;
 
-use crate::domain::types::{
-    requests::FileFormat,
-    File,
-    GithubFileRequest,
+use crate::{
+    domain::types::{
+        requests::FileFormat,
+        File,
+        GithubFileRequest,
+    },
+    LEADERBOARD_IDS,
 };
 
 /// Assembles a request for a file in a Github repository
@@ -103,6 +112,54 @@ pub fn remove_escaping(string: String) -> String {
     edit
 }
 
+/// Format a unix `timestamp` as ISO 8601 in UTC, e.g.
+/// `2021-02-22T13:20:00Z`
+#[must_use]
+pub fn iso_timestamp(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+       .single()
+       .map_or_else(String::new, |time| {
+           time.to_rfc3339_opts(SecondsFormat::Secs, true)
+       })
+}
+
+/// Look up the translated `String` for an `id` in the `first` list (e.g.
+/// `civ` or `map_type`) of the aoe2net `language` strings
+#[must_use]
+pub fn lookup_translation(language: &JsonValue,
+                          first: &str,
+                          id: usize)
+                          -> Option<String> {
+    language[first].as_array()?
+                   .iter()
+                   .find(|obj| obj["id"].as_u64() == Some(id as u64))
+                   .and_then(|obj| obj["string"].as_str())
+                   .map(str::to_string)
+}
+
+/// Translate the `id` in the `first` list of the aoe2net `language` strings,
+/// falls back to the `id` itself if there is no translation for it
+#[must_use]
+pub fn translate_id(language: &JsonValue,
+                    first: &str,
+                    id: Option<i64>)
+                    -> String {
+    id.and_then(|id| usize::try_from(id).ok())
+      .and_then(|id| lookup_translation(language, first, id))
+      .or_else(|| id.map(|id| id.to_string()))
+      .unwrap_or_default()
+}
+
+/// Look up the leaderboard ID of aoe2net for the kind of `leaderboard`, e.g.
+/// `rm_1v1`, of a `game`, see [`static@crate::LEADERBOARD_IDS`]
+#[must_use]
+pub fn leaderboard_id(game: &str,
+                      leaderboard: &str)
+                      -> Option<i32> {
+    LEADERBOARD_IDS.get(game)
+                   .and_then(|ids| ids.get(leaderboard).map(|id| *id))
+}
+
 #[allow(unused_macros)]
 macro_rules! enum_with_str_representation {
     (enum $enum_name:ident {
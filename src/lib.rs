@@ -39,9 +39,26 @@ pub static ref LANGUAGE_STRINGS: [&'static str; 18] = [
 ];
 
 /// `Game strings` used for preloading and other request towards the AoE2.net
-/// API.
-/// Can be used later also for adding `AoE3DE` and/or `AoE4` support
-pub static ref GAME_STRINGS: [&'static str; 1] = ["aoe2de"];
+/// API
+pub static ref GAME_STRINGS: [&'static str; 3] = ["aoe2de", "aoe3de", "aoe4"];
+
+/// Leaderboard IDs of the AoE2.net API for each of the `GAME_STRINGS`, keyed
+/// by the kind of the leaderboard as used for the `ratings` of the `player`
+/// endpoint. A game without a leaderboard of that kind has no entry for it,
+/// e.g. `AoE4` has a separate leaderboard for each team size.
+pub static ref LEADERBOARD_IDS: DashMap<&'static str, DashMap<&'static str, i32>> = {
+        let ids = DashMap::new();
+        ids.insert("aoe2de",
+                   [("rm_1v1", 3), ("rm_team", 4), ("ew_1v1", 13), ("dm_1v1", 1)]
+                       .iter()
+                       .copied()
+                       .collect());
+        ids.insert("aoe3de",
+                   [("rm_1v1", 1), ("rm_team", 2)].iter().copied().collect());
+        ids.insert("aoe4", [("rm_1v1", 17)].iter().copied().collect());
+
+        ids
+    };
 
 /// Our app name as USERAGENT for the clients
 pub static ref APP_USER_AGENT: &'static str =
@@ -52,5 +69,81 @@ pub static ref CLIENT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 /// Timeout for http-connections
 pub static ref CLIENT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// `Time-to-live` of cached responses for each endpoint of the AoE2.net API.
+/// Responses of endpoints that are not listed here are not cached.
+pub static ref AOE2NET_CACHE_TTLS: DashMap<&'static str, Duration> = {
+        let ttls = DashMap::new();
+        // Below the `SUBSCRIPTION_POLLING_INTERVAL`, so every poll of a
+        // subscription gets a fresh response
+        ttls.insert("player/lastmatch", Duration::from_secs(5));
+        ttls.insert("match", Duration::from_secs(30));
+        ttls.insert("leaderboard", Duration::from_secs(60));
+        ttls.insert("lobbies", Duration::from_secs(10));
+        ttls.insert("player/rating", Duration::from_secs(600));
+        ttls.insert("player/ratinghistory", Duration::from_secs(600));
+
+        ttls
+    };
+
+/// Maximum amount of responses in the response cache of a client
+pub static ref RESPONSE_CACHE_CAPACITY: usize = 10_000;
+
+/// Amount of requests towards a single API root that can be made in a burst
+/// before our rate limiter starts throttling
+pub static ref RATE_LIMIT_BURST: u32 = 10;
+/// Interval in which our rate limiter allows another request towards a single
+/// API root
+pub static ref RATE_LIMIT_REFILL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Maximum amount of retries for a failed idempotent request
+pub static ref RETRY_MAX_RETRIES: u32 = 3;
+/// Delay before the first retry of a failed request, doubles with each retry
+pub static ref RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+/// Upper bound for the delay between two retries
+pub static ref RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// `Time-to-live` of the last successfully processed `MatchInfoResult` of a
+/// request, which is served while the circuit breaker for aoe2.net is open
+pub static ref LAST_GOOD_MATCH_INFO_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Amount of consecutive failures towards an API root after which its circuit
+/// breaker opens
+pub static ref CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
+/// Duration a circuit breaker stays open before requests are let through
+/// again
+pub static ref CIRCUIT_BREAKER_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// Kind of leaderboard that is searched for a player name that is not part of
+/// the `aoc-reference-data`, see `LEADERBOARD_IDS`
+pub static ref NAME_SEARCH_LEADERBOARD: &'static str = "rm_1v1";
+/// Maximum amount of players returned when searching the leaderboard for a
+/// player name
+pub static ref NAME_SEARCH_COUNT: i32 = 10;
+
+/// Maximum amount of players returned by the `players/search` endpoint
+pub static ref PLAYER_SEARCH_LIMIT: usize = 10;
+
+/// Amount of most recent matches of each player that are searched for shared
+/// matches when building a head-to-head record
+pub static ref HEAD_TO_HEAD_MATCH_COUNT: i32 = 1000;
+/// `Time-to-live` of a cached head-to-head record of a player pair
+pub static ref HEAD_TO_HEAD_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// `Time-to-live` of cached responses of the aoestats.io API, which only
+/// recalculates its stats about once a day and rate limits its users
+pub static ref AOESTATS_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Interval in which `subscriptions` poll the `last_match` endpoint for
+/// changes
+pub static ref SUBSCRIPTION_POLLING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Interval in which the pro match discovery polls the `matches` endpoint for
+/// newly started matches
+pub static ref PRO_MATCHES_POLLING_INTERVAL: Duration = Duration::from_secs(30);
+/// Maximum amount of matches requested from the `matches` endpoint per poll
+pub static ref PRO_MATCHES_POLL_COUNT: i32 = 1000;
+/// Age after which a discovered pro match that is not reported as finished
+/// is no longer considered to be ongoing
+pub static ref PRO_MATCHES_MAX_AGE: Duration = Duration::from_secs(2 * 60 * 60);
 
 }
@@ -6,7 +6,10 @@ use std::{
     time::Duration,
 };
 
-use aoe2net::endpoints::strings::GetApiStringsRequest;
+use aoe2net::endpoints::{
+    strings::GetApiStringsRequest,
+    Raw,
+};
 use api_client::error::ClientRequestError;
 use dashmap::DashMap;
 use serde_json::Value as JsonValue;
@@ -194,6 +197,8 @@ pub async fn preload_data(api_client: Option<reqwest::Client>,
 /// Index the `player_ids` of Players in the `players.yaml` file of
 /// aoc-reference-data repository in a [`dashmap::DashMap`] to make them be
 /// easily looked-up during the processing stage
+///
+/// The search index for the `players/search` endpoint is rebuilt as well.
 // TODO: Handle Result better for indexing errors
 #[allow(unused_must_use)]
 async fn index_aoc_ref_data(in_memory_db: Arc<Mutex<InMemoryDb>>) {
@@ -212,8 +217,13 @@ async fn index_aoc_ref_data(in_memory_db: Arc<Mutex<InMemoryDb>>) {
 
 /// Preload data from `aoe2net`
 ///
+/// A `language string` that can't be requested is skipped with a warning, so
+/// a missing translation of one game doesn't prevent the other games from
+/// being preloaded.
+///
 /// # Errors
-// TODO
+/// Returns the last error if none of the `language strings` could be
+/// requested
 /// # Panics
 // TODO
 pub async fn preload_aoe2_net_data(api_client: reqwest::Client,
@@ -221,9 +231,10 @@ pub async fn preload_aoe2_net_data(api_client: reqwest::Client,
                                    root: Url,
                                    export_path: Option<PathBuf>)
                                    -> Result<(), ApiRequestError> {
-    let language_requests = build_language_requests(&root);
+    let language_requests = build_language_requests();
 
     let responses = assemble_languages_to_dashmap(api_client,
+                                                  root,
                                                   language_requests,
                                                   export_path).await?;
 
@@ -235,46 +246,69 @@ pub async fn preload_aoe2_net_data(api_client: reqwest::Client,
     Ok(())
 }
 
-/// Pull responses for `language strings` into a [`dashmap::DashMap`] for being
-/// easily looked-up later on
+/// Pull responses for `language strings` of each game into a
+/// [`dashmap::DashMap`] for being easily looked-up later on
 ///
 /// # Errors
-// TODO
+/// Returns the last error if none of the `language_requests` succeeded
 async fn assemble_languages_to_dashmap(
     api_client: reqwest::Client,
-    language_requests: Vec<(String, GetApiStringsRequest<'_>)>,
+    root: Url,
+    language_requests: Vec<(String, String, GetApiStringsRequest<'_>)>,
     export_path: Option<PathBuf>)
-    -> Result<DashMap<String, JsonValue>, ClientRequestError<reqwest::Error>> {
-    let responses: DashMap<String, JsonValue> =
-        DashMap::with_capacity(LANGUAGE_STRINGS.len());
-
-    let client = A2NClient::with_client(api_client);
-
-    for (req_name, req) in language_requests {
-        let response = client.req_get(req).await?;
-        let data = response.data.unwrap_or_default();
-
-        responses.insert(req_name.to_string(), data.clone());
+    -> Result<DashMap<String, DashMap<String, JsonValue>>,
+              ClientRequestError<reqwest::Error>> {
+    let responses: DashMap<String, DashMap<String, JsonValue>> =
+        DashMap::with_capacity(GAME_STRINGS.len());
+
+    let client = A2NClient::with_client(api_client).with_root(root);
+
+    let mut last_error = None;
+
+    for (game, language, req) in language_requests {
+        let data = match client.req_get(Raw(req)).await {
+            Ok(response) => response.data.unwrap_or_default(),
+            Err(err) => {
+                warn!("Preloading language {:?} of game {:?} failed: {}",
+                      language, game, err);
+                last_error = Some(err);
+                continue;
+            },
+        };
 
-        if export_path.is_some() {
-            util::export_to_json(&File { name: req_name.to_string(),
+        if let Some(mut path) = export_path.clone() {
+            path.push(&game);
+            util::export_to_json(&File { name: language.clone(),
                                          ext: FileFormat::Json },
-                                 &export_path.clone().unwrap(),
-                                 &data)
+                                 &path,
+                                 &data);
         }
+
+        responses.entry(game)
+                 .or_insert_with(|| {
+                     DashMap::with_capacity(LANGUAGE_STRINGS.len())
+                 })
+                 .insert(language, data);
     }
 
-    Ok(responses)
+    match last_error {
+        Some(err) if responses.is_empty() => Err(err),
+        _ => Ok(responses),
+    }
 }
 
-/// Builds all requests for the `LANGUAGE_STRINGS`
-fn build_language_requests(_root: &Url) -> Vec<(String, GetApiStringsRequest)> {
-    let mut language_requests = Vec::with_capacity(LANGUAGE_STRINGS.len());
+/// Builds all requests for the `LANGUAGE_STRINGS` of each of the
+/// `GAME_STRINGS`
+fn build_language_requests(
+    ) -> Vec<(String, String, GetApiStringsRequest<'static>)> {
+    let mut language_requests =
+        Vec::with_capacity(GAME_STRINGS.len() * LANGUAGE_STRINGS.len());
 
     // Build requests for each `GAME_STRING` with each `LANGUAGE_STRING`
     for game in &(*GAME_STRINGS) {
         for language in &(*LANGUAGE_STRINGS) {
             language_requests.push((
+                (*game).to_string(),
                 (*language).to_string(),
                 GetApiStringsRequest::builder()
                     .game(*game)
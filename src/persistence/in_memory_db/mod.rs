@@ -1,2 +1,3 @@
 //! In-memory DB implemented by `Arc<Mutex<T>>`
 pub mod data_preloading;
+pub mod ttl_cache;
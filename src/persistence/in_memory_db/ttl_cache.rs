@@ -0,0 +1,231 @@
+//! A concurrent cache with a `time-to-live` for each entry
+//!
+//! Clones of a [`TtlCache`] share the same storage and counters, so it can be
+//! handed to every request handler.
+//!
+//! Expired entries are purged when the cache is full, if that doesn't free up
+//! any space, the entry that expires first gets evicted.
+
+use std::{
+    hash::Hash,
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use dashmap::DashMap;
+use serde::Serialize;
+
+/// Maximum amount of entries of a [`TtlCache`] created with
+/// [`TtlCache::new`]
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// A single entry of the [`TtlCache`]
+#[derive(Clone, Debug)]
+struct CacheEntry<V> {
+    /// The cached value
+    value: V,
+    /// Point in time after which the entry is stale
+    expires_at: Instant,
+}
+
+/// Snapshot of the hit/miss counters of a [`TtlCache`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Lookups that were answered from the cache
+    pub hits: u64,
+    /// Lookups that were not in the cache or already expired
+    pub misses: u64,
+}
+
+/// Concurrent cache where each entry expires after its own `time-to-live`
+#[derive(Clone, Debug)]
+pub struct TtlCache<K, V>
+    where K: Eq + Hash,
+{
+    /// Storage of the cache
+    entries: Arc<DashMap<K, CacheEntry<V>>>,
+    /// Counter for lookups answered from the cache
+    hits: Arc<AtomicU64>,
+    /// Counter for lookups not answered from the cache
+    misses: Arc<AtomicU64>,
+    /// Maximum amount of entries
+    capacity: usize,
+}
+
+impl<K, V> Default for TtlCache<K, V> where K: Eq + Hash,
+{
+    fn default() -> Self {
+        Self { entries: Arc::new(DashMap::new()),
+               hits: Arc::new(AtomicU64::new(0)),
+               misses: Arc::new(AtomicU64::new(0)),
+               capacity: DEFAULT_CAPACITY }
+    }
+}
+
+impl<K, V> TtlCache<K, V>
+    where K: Eq + Hash + Clone,
+          V: Clone,
+{
+    /// Create a new and empty [`TtlCache`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new and empty [`TtlCache`] that holds at most `capacity`
+    /// entries
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "TtlCache needs a capacity of at least 1");
+
+        Self { capacity,
+               ..Self::default() }
+    }
+
+    /// Returns a clone of the value for `key` if it exists and is not expired
+    /// yet, expired entries get removed on lookup
+    pub fn get(&self,
+               key: &K)
+               -> Option<V> {
+        let cached = self.entries
+                         .get(key)
+                         .filter(|entry| entry.expires_at > Instant::now())
+                         .map(|entry| entry.value.clone());
+
+        if cached.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        else {
+            self.entries
+                .remove_if(key, |_, entry| entry.expires_at <= Instant::now());
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        cached
+    }
+
+    /// Insert `value` for `key`, which will be valid for `ttl`
+    ///
+    /// If the cache is full, the expired entries are purged first. If there
+    /// are none, the entry that expires first gets evicted.
+    pub fn insert(&self,
+                  key: K,
+                  value: V,
+                  ttl: Duration) {
+        if self.entries.len() >= self.capacity &&
+           !self.entries.contains_key(&key)
+        {
+            self.purge_expired();
+            self.evict_to(self.capacity - 1);
+        }
+
+        self.entries
+            .insert(key, CacheEntry { value,
+                                      expires_at: Instant::now() + ttl });
+    }
+
+    /// Remove all expired entries
+    pub fn purge_expired(&self) {
+        let now = Instant::now();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+    }
+
+    /// Evict the entries that expire first, until at most `len` are left
+    fn evict_to(&self,
+                len: usize) {
+        while self.entries.len() > len {
+            let first_to_expire = self.entries
+                                      .iter()
+                                      .min_by_key(|entry| entry.expires_at)
+                                      .map(|entry| entry.key().clone());
+
+            match first_to_expire {
+                Some(key) => {
+                    self.entries.remove(&key);
+                },
+                None => break,
+            }
+        }
+    }
+
+    /// Number of entries, including the expired ones that have not been
+    /// purged yet
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the cache contains no entries
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the current hit/miss counters
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        CacheStats { hits: self.hits.load(Ordering::Relaxed),
+                     misses: self.misses.load(Ordering::Relaxed) }
+    }
+}
+
+#[test]
+fn cache_counts_hits_and_misses() {
+    let cache = TtlCache::new();
+
+    assert_eq!(cache.get(&"lastmatch"), None);
+    cache.insert("lastmatch", 1, Duration::from_secs(30));
+    assert_eq!(cache.get(&"lastmatch"), Some(1));
+
+    assert_eq!(cache.stats(), CacheStats { hits: 1,
+                                           misses: 1 });
+}
+
+#[test]
+fn cache_entries_expire() {
+    let cache = TtlCache::new();
+
+    cache.insert("lastmatch", 1, Duration::from_secs(0));
+
+    assert_eq!(cache.get(&"lastmatch"), None);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn full_cache_purges_expired_entries() {
+    let cache = TtlCache::with_capacity(2);
+
+    cache.insert("lastmatch", 1, Duration::from_secs(0));
+    cache.insert("leaderboard", 2, Duration::from_secs(60));
+    cache.insert("lobbies", 3, Duration::from_secs(10));
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"leaderboard"), Some(2));
+    assert_eq!(cache.get(&"lobbies"), Some(3));
+}
+
+#[test]
+fn full_cache_evicts_the_entry_expiring_first() {
+    let cache = TtlCache::with_capacity(2);
+
+    cache.insert("leaderboard", 1, Duration::from_secs(60));
+    cache.insert("lobbies", 2, Duration::from_secs(10));
+    cache.insert("lobbies", 3, Duration::from_secs(10));
+    cache.insert("player/rating", 4, Duration::from_secs(600));
+
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"lobbies"), None);
+    assert_eq!(cache.get(&"player/rating"), Some(4));
+}
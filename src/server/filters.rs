@@ -1,6 +1,9 @@
 //! API endpoints of the backend
 
-use std::sync::Arc;
+use std::{
+    convert::TryFrom,
+    sync::Arc,
+};
 
 use tokio::sync::Mutex;
 use warp::Filter;
@@ -9,24 +12,73 @@ use crate::{
     domain::{
         api_handler::client::A2NClient,
         types::{
-            api::MatchInfoRequest,
+            api::{
+                IdType,
+                LeaderboardQuery,
+                LeaderboardRequest,
+                LobbiesQuery,
+                LobbiesRequest,
+                MatchHistoryQuery,
+                MatchHistoryRequest,
+                MatchInfoQuery,
+                MatchInfoRequest,
+                PlayerProfileQuery,
+                PlayerProfileRequest,
+                PlayerSearchQuery,
+                PlayerSearchRequest,
+                ProMatchesQuery,
+                ProMatchesRequest,
+                RatingHistoryQuery,
+                RatingHistoryRequest,
+            },
+            error::{
+                InvalidField,
+                RequestValidationError,
+            },
             InMemoryDb,
         },
     },
-    server::handlers::{
-        return_health_check_to_client,
-        return_matchinfo_to_client,
+    server::{
+        handlers::{
+            return_health_check_to_client,
+            return_leaderboard_to_client,
+            return_lobbies_to_client,
+            return_match_history_to_client,
+            return_matchinfo_to_client,
+            return_player_profile_to_client,
+            return_player_search_to_client,
+            return_pro_matches_to_client,
+            return_rating_history_to_client,
+            return_subscription_to_client,
+        },
+        problem::handle_rejection,
     },
 };
 
 /// A general warp-filter that is basically our API
+///
+/// Rejections, e.g. for invalid query parameters, are returned as
+/// `application/problem+json`
 #[must_use]
 pub fn transparencies(
     aoe_net_client: A2NClient<'static, reqwest::Client>,
     in_memory_db: Arc<Mutex<InMemoryDb>>)
     -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
 {
-    health_check().or(matchinfo(aoe_net_client, in_memory_db))
+    health_check().or(matchinfo(aoe_net_client.clone(), in_memory_db.clone()))
+                  .or(subscribe(aoe_net_client.clone(), in_memory_db.clone()))
+                  .or(players_search(aoe_net_client.clone(),
+                                     in_memory_db.clone()))
+                  .or(player_profile(aoe_net_client.clone(),
+                                     in_memory_db.clone()))
+                  .or(rating_history(aoe_net_client.clone()))
+                  .or(match_history(aoe_net_client.clone(),
+                                    in_memory_db.clone()))
+                  .or(leaderboard(aoe_net_client.clone(),
+                                  in_memory_db.clone()))
+                  .or(lobbies(aoe_net_client, in_memory_db.clone()))
+                  .or(pro_matches(in_memory_db))
+                  .recover(handle_rejection)
 }
 
 /// GET `/health_check`
@@ -40,7 +92,9 @@ pub fn health_check(
 }
 
 /// GET  `/matchinfo?id_type=profile_id&id_number=459658`
-/// Our matchinfo endpoint
+/// Our matchinfo endpoint, `h2h=true` adds the head-to-head record of the
+/// players of a 1v1, `civ_stats=true` adds the win rates of the civilizations
+/// from aoestats.io, `sort` and `team_sort` order the players and teams
 pub fn matchinfo(
     aoe_net_client: A2NClient<'static, reqwest::Client>,
     in_memory_db: Arc<Mutex<InMemoryDb>>)
@@ -57,8 +111,246 @@ pub fn matchinfo(
     let ref_data_filter = warp::any().map(move || in_memory_db.clone());
 
     warp::path!("matchinfo").and(warp::get())
-                            .and(warp::query::<MatchInfoRequest>())
+                            .and(match_info_request())
                             .and(aoe_net_client_filter)
                             .and(ref_data_filter)
                             .and_then(return_matchinfo_to_client)
 }
+
+/// GET  `/subscribe?id_type=profile_id&id_number=459658`
+/// Our subscription endpoint, upgrades the connection to a `WebSocket` and
+/// pushes a new `MatchInfoResult` whenever the match of the player changes
+pub fn subscribe(
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("subscribe").and(subscription_request())
+                            .and(warp::ws())
+                            .and(aoe_net_client_filter)
+                            .and(ref_data_filter)
+                            .and_then(return_subscription_to_client)
+}
+
+/// GET  `/players/search?q=vip`
+/// Our player search endpoint, searches the `aoc-reference-data` and the
+/// leaderboard for players by (parts of) their name
+pub fn players_search(
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("players" / "search").and(warp::get())
+                                     .and(player_search_request())
+                                     .and(aoe_net_client_filter)
+                                     .and(ref_data_filter)
+                                     .and_then(return_player_search_to_client)
+}
+
+/// GET  `/player/196240`
+/// Our player profile endpoint, merges the `aoc-reference-data` of a player
+/// with the current ratings on aoe2.net
+pub fn player_profile(
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("player" / String)
+        .and(warp::get())
+        .and(warp::query::<PlayerProfileQuery>())
+        .and_then(|profile_id, query| async move {
+            PlayerProfileRequest::with_query(profile_id, query)
+                .map_err(warp::reject::custom)
+        })
+        .and(aoe_net_client_filter)
+        .and(ref_data_filter)
+        .and_then(return_player_profile_to_client)
+}
+
+/// GET  `/player/196240/rating_history?leaderboard_id=3&count=100&points=50`
+/// Our rating history endpoint, returns the rating of a player after each
+/// game as a time series
+pub fn rating_history(
+    aoe_net_client: A2NClient<'static, reqwest::Client>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    warp::path!("player" / String / "rating_history")
+        .and(warp::get())
+        .and(warp::query::<RatingHistoryQuery>())
+        .and_then(|profile_id, query| async move {
+            RatingHistoryRequest::with_query(profile_id, query)
+                .map_err(warp::reject::custom)
+        })
+        .and(aoe_net_client_filter)
+        .and_then(return_rating_history_to_client)
+}
+
+/// GET  `/player/196240/matches?count=50`
+/// Our match history endpoint, returns the translated recent matches of a
+/// player with the performance per civilisation, map and against the current
+/// opponent
+pub fn match_history(
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("player" / String / "matches")
+        .and(warp::get())
+        .and(warp::query::<MatchHistoryQuery>())
+        .and_then(|profile_id, query| async move {
+            MatchHistoryRequest::with_query(profile_id, query)
+                .map_err(warp::reject::custom)
+        })
+        .and(aoe_net_client_filter)
+        .and(ref_data_filter)
+        .and_then(return_match_history_to_client)
+}
+
+/// GET  `/leaderboard/3?start=1&count=10`
+/// Our leaderboard endpoint, returns a page of a leaderboard enriched with the
+/// `aoc-reference-data`
+pub fn leaderboard(
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("leaderboard" / String)
+        .and(warp::get())
+        .and(warp::query::<LeaderboardQuery>())
+        .and_then(|leaderboard_id: String, query| async move {
+            LeaderboardRequest::with_query(&leaderboard_id, query)
+                .map_err(warp::reject::custom)
+        })
+        .and(aoe_net_client_filter)
+        .and(ref_data_filter)
+        .and_then(return_leaderboard_to_client)
+}
+
+/// GET  `/lobbies?map=arabia&min_rating=1800&known_players=true`
+/// Our lobbies endpoint, returns the translated open lobbies that pass the
+/// requested filters
+pub fn lobbies(
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let aoe_net_client_filter = warp::any().map(move || aoe_net_client.clone());
+
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("lobbies").and(warp::get())
+                          .and(lobbies_request())
+                          .and(aoe_net_client_filter)
+                          .and(ref_data_filter)
+                          .and_then(return_lobbies_to_client)
+}
+
+/// GET  `/live/pro_matches?language=en`
+/// Our pro matches endpoint, returns the ongoing matches of players of the
+/// `aoc-reference-data` found by the background discovery
+pub fn pro_matches(
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone
+{
+    let ref_data_filter = warp::any().map(move || in_memory_db.clone());
+
+    warp::path!("live" / "pro_matches")
+        .and(warp::get())
+        .and(warp::query::<ProMatchesQuery>())
+        .and_then(|query| async move {
+            ProMatchesRequest::try_from(query).map_err(warp::reject::custom)
+        })
+        .and(ref_data_filter)
+        .and_then(return_pro_matches_to_client)
+}
+
+/// Query parameters of a [`LobbiesRequest`]
+/// Rejects with a `RequestValidationError` that contains every invalid field
+#[must_use]
+pub fn lobbies_request(
+    )
+    -> impl Filter<Extract = (LobbiesRequest,), Error = warp::Rejection> + Clone
+{
+    warp::query::<LobbiesQuery>().and_then(|query| async move {
+                                     LobbiesRequest::try_from(query)
+                                         .map_err(warp::reject::custom)
+                                 })
+}
+
+/// Query parameters of a [`PlayerSearchRequest`]
+/// Rejects with a `RequestValidationError` that contains every invalid field
+#[must_use]
+pub fn player_search_request(
+    )
+    -> impl Filter<Extract = (PlayerSearchRequest,), Error = warp::Rejection> + Clone
+{
+    warp::query::<PlayerSearchQuery>().and_then(|query| async move {
+                                          PlayerSearchRequest::try_from(query)
+                                              .map_err(warp::reject::custom)
+                                      })
+}
+
+/// Query parameters of a [`MatchInfoRequest`]
+/// Rejects with a `RequestValidationError` that contains every invalid field
+#[must_use]
+pub fn match_info_request(
+    )
+    -> impl Filter<Extract = (MatchInfoRequest,), Error = warp::Rejection> + Clone
+{
+    warp::query::<MatchInfoQuery>().and_then(|query| async move {
+                                       MatchInfoRequest::try_from(query)
+                                           .map_err(warp::reject::custom)
+                                   })
+}
+
+/// Query parameters of a [`MatchInfoRequest`] for a subscription
+/// Rejects with a `RequestValidationError` as well, if the `id_type` is
+/// `match_id` or `match_uuid`, as a subscription follows the last match of a
+/// player
+#[must_use]
+pub fn subscription_request(
+    )
+    -> impl Filter<Extract = (MatchInfoRequest,), Error = warp::Rejection> + Clone
+{
+    match_info_request().and_then(|par: MatchInfoRequest| async move {
+                            match par.id_type {
+                                IdType::MatchId | IdType::MatchUuid => {
+                                    Err(warp::reject::custom(
+                                        RequestValidationError {
+                                            invalid_fields: vec![
+                                                InvalidField::new(
+                                                    "id_type",
+                                                    "a subscription follows \
+                                                     the last match of a \
+                                                     player",
+                                                ),
+                                            ],
+                                        },
+                                    ))
+                                },
+                                _ => Ok(par),
+                            }
+                        })
+}
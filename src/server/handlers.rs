@@ -7,14 +7,37 @@ use std::{
 
 use tokio::sync::Mutex;
 use url::Url;
+use warp::Reply;
 
-use crate::domain::{
-    api_handler::client::A2NClient,
-    data_processing::build_result,
-    types::{
-        api::MatchInfoRequest,
-        InMemoryDb,
+use crate::{
+    domain::{
+        api_handler::client::A2NClient,
+        data_processing::{
+            build_result_coalesced,
+            leaderboard::build_leaderboard,
+            lobbies::build_lobbies,
+            match_history::build_match_history,
+            player_profile::build_player_profile,
+            player_search::search_players,
+            pro_matches::build_pro_matches,
+            rating_history::build_rating_history,
+            subscription::serve_subscription,
+        },
+        types::{
+            api::{
+                LeaderboardRequest,
+                LobbiesRequest,
+                MatchHistoryRequest,
+                MatchInfoRequest,
+                PlayerProfileRequest,
+                PlayerSearchRequest,
+                ProMatchesRequest,
+                RatingHistoryRequest,
+            },
+            InMemoryDb,
+        },
     },
+    server::problem::Problem,
 };
 
 /// Small `health_check` function to return 200 on `health_check` endpoint
@@ -31,6 +54,10 @@ pub async fn return_health_check_to_client(
 /// Handler function to return data from the `match_info` processing serialized
 /// as JSON to `/matchinfo` endpoint
 ///
+/// If processing failed, a [`Problem`] is returned instead with a status code
+/// corresponding to the error, e.g. `404` for an unknown player or `502` if
+/// aoe2.net is not reachable
+///
 /// GET Endpoint
 /// Possible test url: <http://127.0.0.1:8000/matchinfo?id_type=profile_id&id_number=196240&game=aoe2de>
 ///
@@ -53,11 +80,251 @@ pub async fn return_matchinfo_to_client(
     // API root for aoe2net
     let root = Url::parse("https://aoe2.net/api").unwrap();
 
-    let processed_match_info = build_result(opts.clone(),
-                                            aoe_net_client,
-                                            root,
-                                            in_memory_db.clone(),
-                                            None).await;
+    let processed_match_info = build_result_coalesced(opts.clone(),
+                                                      aoe_net_client,
+                                                      root,
+                                                      in_memory_db.clone()).await;
+
+    Ok(Problem::with_match_info_result(&processed_match_info).map_or_else(
+        || warp::reply::json(&processed_match_info).into_response(),
+        Reply::into_response,
+    ))
+}
+
+/// Handler function to upgrade a connection on the `/subscribe` endpoint to a
+/// `WebSocket` and serve the subscription on it
+///
+/// Possible test url: <ws://127.0.0.1:8000/subscribe?id_type=profile_id&id_number=196240&game=aoe2de>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `ws`: the `WebSocket` upgrade of the connection
+/// - `aoe_net_client`: Our reusable aoe.net Client
+/// - `in_memory_db`: Our in-memory DB
+///
+/// # Errors
+// TODO
+pub async fn return_subscription_to_client(
+    opts: MatchInfoRequest,
+    ws: warp::ws::Ws,
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    Ok(ws.on_upgrade(move |socket| {
+             serve_subscription(socket, opts, aoe_net_client, in_memory_db)
+         }))
+}
+
+/// Handler function to return the players found for a search serialized as
+/// JSON to `/players/search` endpoint
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/players/search?q=vip>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `aoe_net_client`: Our reusable aoe.net Client
+/// - `in_memory_db`: Our in-memory DB that contains the search index
+///
+/// # Errors
+// TODO
+/// # Panics
+// TODO
+pub async fn return_player_search_to_client(
+    opts: PlayerSearchRequest,
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    let players =
+        search_players(opts, aoe_net_client, root, in_memory_db).await;
+
+    Ok(warp::reply::json(&players))
+}
+
+/// Handler function to return the player card from the `player_profile`
+/// processing serialized as JSON to `/player/{profile_id}` endpoint
+///
+/// If the player is unknown or no rating could be looked up, a [`Problem`] is
+/// returned instead with a status code corresponding to the error
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/player/196240>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `aoe_net_client`: Our reusable aoe.net Client
+/// - `in_memory_db`: Our in-memory DB that contains the `aoc-reference-data`
+///
+/// # Errors
+// TODO
+/// # Panics
+// TODO
+pub async fn return_player_profile_to_client(
+    opts: PlayerProfileRequest,
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    let profile =
+        build_player_profile(opts, aoe_net_client, root, in_memory_db).await;
+
+    Ok(profile.map_or_else(
+        |err| Problem::with_responder_error(&err).into_response(),
+        |profile| warp::reply::json(&profile).into_response(),
+    ))
+}
+
+/// Handler function to return the rating history of a player as a time
+/// series serialized as JSON to `/player/{profile_id}/rating_history` endpoint
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/player/196240/rating_history?leaderboard_id=3&points=50>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `aoe_net_client`: Our reusable aoe.net Client
+///
+/// # Errors
+// TODO
+/// # Panics
+// TODO
+pub async fn return_rating_history_to_client(
+    opts: RatingHistoryRequest,
+    aoe_net_client: A2NClient<'static, reqwest::Client>)
+    -> Result<impl warp::Reply, Infallible> {
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    let rating_history = build_rating_history(opts, aoe_net_client, root).await;
+
+    Ok(rating_history.map_or_else(
+        |err| Problem::with_responder_error(&err).into_response(),
+        |rating_history| warp::reply::json(&rating_history).into_response(),
+    ))
+}
+
+/// Handler function to return the match history of a player with its
+/// aggregated performance serialized as JSON to `/player/{profile_id}/matches`
+/// endpoint
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/player/196240/matches?count=50>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `aoe_net_client`: Our reusable aoe.net Client
+/// - `in_memory_db`: Our in-memory DB that contains the translations
+///
+/// # Errors
+// TODO
+/// # Panics
+// TODO
+pub async fn return_match_history_to_client(
+    opts: MatchHistoryRequest,
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    let match_history =
+        build_match_history(opts, aoe_net_client, root, in_memory_db).await;
+
+    Ok(match_history.map_or_else(
+        |err| Problem::with_responder_error(&err).into_response(),
+        |match_history| warp::reply::json(&match_history).into_response(),
+    ))
+}
+
+/// Handler function to return a page of a leaderboard enriched with the
+/// `aoc-reference-data` serialized as JSON to `/leaderboard/{leaderboard_id}`
+/// endpoint
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/leaderboard/3?start=1&count=10>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `aoe_net_client`: Our reusable aoe.net Client
+/// - `in_memory_db`: Our in-memory DB that contains the reference data
+///
+/// # Errors
+// TODO
+/// # Panics
+// TODO
+pub async fn return_leaderboard_to_client(
+    opts: LeaderboardRequest,
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    let leaderboard =
+        build_leaderboard(opts, aoe_net_client, root, in_memory_db).await;
+
+    Ok(leaderboard.map_or_else(
+        |err| Problem::with_responder_error(&err).into_response(),
+        |leaderboard| warp::reply::json(&leaderboard).into_response(),
+    ))
+}
+
+/// Handler function to return the translated open lobbies that pass the
+/// requested filters serialized as JSON to `/lobbies` endpoint
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/lobbies?known_players=true>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `aoe_net_client`: Our reusable aoe.net Client
+/// - `in_memory_db`: Our in-memory DB that contains the translations and the
+///   reference data
+///
+/// # Errors
+// TODO
+/// # Panics
+// TODO
+pub async fn return_lobbies_to_client(
+    opts: LobbiesRequest,
+    aoe_net_client: A2NClient<'static, reqwest::Client>,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    // API root for aoe2net
+    let root = Url::parse("https://aoe2.net/api").unwrap();
+
+    let lobbies =
+        build_lobbies(opts, aoe_net_client, root, in_memory_db).await;
+
+    Ok(lobbies.map_or_else(
+        |err| Problem::with_responder_error(&err).into_response(),
+        |lobbies| warp::reply::json(&lobbies).into_response(),
+    ))
+}
+
+/// Handler function to return the ongoing matches of players of the
+/// `aoc-reference-data` serialized as JSON to `/live/pro_matches` endpoint
+///
+/// GET Endpoint
+/// Possible test url: <http://127.0.0.1:8000/live/pro_matches?language=en>
+///
+/// - `opts`: options struct that contains the parameters that the client gave
+///   us
+/// - `in_memory_db`: Our in-memory DB that contains the discovered matches,
+///   the translations and the reference data
+///
+/// # Errors
+// TODO
+pub async fn return_pro_matches_to_client(
+    opts: ProMatchesRequest,
+    in_memory_db: Arc<Mutex<InMemoryDb>>)
+    -> Result<impl warp::Reply, Infallible> {
+    let pro_matches = build_pro_matches(opts, in_memory_db).await;
 
-    Ok(warp::reply::json(&processed_match_info))
+    Ok(warp::reply::json(&pro_matches))
 }
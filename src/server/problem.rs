@@ -0,0 +1,182 @@
+//! Error responses of our API as `application/problem+json` (RFC 7807)
+
+use std::borrow::Cow;
+
+use http::StatusCode;
+use serde::Serialize;
+use warp::{
+    reject::{
+        InvalidQuery,
+        MethodNotAllowed,
+        Reject,
+    },
+    Rejection,
+    Reply,
+};
+
+use crate::domain::types::{
+    api::{
+        MatchInfoResult,
+        PlayerCandidate,
+    },
+    error::{
+        ErrorSeverity,
+        InvalidField,
+        RequestValidationError,
+        ResponderError,
+    },
+};
+
+/// Media type of a [`Problem`]
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Problem details for HTTP APIs (RFC 7807)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Problem {
+    /// URI reference that identifies the problem type, `about:blank` means
+    /// that the problem has no additional semantics beyond the status code
+    #[serde(rename = "type")]
+    pub problem_type: Cow<'static, str>,
+    /// Short summary of the problem type
+    pub title: Cow<'static, str>,
+    /// HTTP status code of the response
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Cow<'static, str>>,
+    /// Stable machine-readable identifier of the problem
+    pub code: Cow<'static, str>,
+    /// Every invalid parameter of the request
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_params: Vec<InvalidField>,
+    /// Players an ambiguous name could belong to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<PlayerCandidate>,
+}
+
+impl Problem {
+    /// Create a new [`Problem`] for a `status` with its canonical reason as
+    /// the title
+    #[must_use]
+    pub fn new(status: StatusCode,
+               code: impl Into<Cow<'static, str>>)
+               -> Self {
+        Self { problem_type: Cow::Borrowed("about:blank"),
+               title: Cow::Borrowed(status.canonical_reason()
+                                          .unwrap_or("Unknown Error")),
+               status: status.as_u16(),
+               detail: None,
+               code: code.into(),
+               invalid_params: Vec::new(),
+               candidates: Vec::new() }
+    }
+
+    /// Add an explanation specific to this occurrence of the problem
+    #[must_use]
+    pub fn with_detail(mut self,
+                       detail: impl Into<Cow<'static, str>>)
+                       -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Create a [`Problem`] from the first `HardFail` of a
+    /// [`MatchInfoResult`], returns `None` if processing didn't fail
+    #[must_use]
+    pub fn with_match_info_result(result: &MatchInfoResult) -> Option<Self> {
+        result.error_message
+              .iter()
+              .find(|err| err.severity == ErrorSeverity::HardFail)
+              .map(|err| {
+                  Self { candidates: result.candidates.clone(),
+                         ..Self::new(err.status.unwrap_or(
+                             StatusCode::INTERNAL_SERVER_ERROR,
+                         ),
+                                     err.code.clone())
+                             .with_detail(err.message.clone()) }
+              })
+    }
+
+    /// Create a [`Problem`] from a [`ResponderError`] with a status code
+    /// corresponding to the error
+    #[must_use]
+    pub fn with_responder_error(err: &ResponderError) -> Self {
+        let candidates =
+            if let ResponderError::AmbiguousPlayerName(candidates) = err {
+                candidates.clone()
+            }
+            else {
+                Vec::new()
+            };
+
+        Self { candidates,
+               ..Self::new(err.status_code(), err.code())
+                   .with_detail(err.to_string()) }
+    }
+}
+
+impl Reply for Problem {
+    fn into_response(self) -> warp::reply::Response {
+        let status = StatusCode::from_u16(self.status)
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        warp::reply::with_status(warp::reply::with_header(
+                                     warp::reply::json(&self),
+                                     http::header::CONTENT_TYPE,
+                                     PROBLEM_JSON,
+                                 ),
+                                 status).into_response()
+    }
+}
+
+impl Reject for RequestValidationError {}
+
+/// Turn rejections of our filters into a [`Problem`], e.g. when the query of
+/// a request could not be parsed into a `MatchInfoRequest`
+///
+/// Rejections we don't know about are passed on to `warp` unchanged.
+///
+/// # Errors
+/// Returns the `Rejection` again if we can't handle it
+pub async fn handle_rejection(err: Rejection) -> Result<Problem, Rejection> {
+    if err.is_not_found() {
+        Ok(Problem::new(StatusCode::NOT_FOUND, "not_found"))
+    }
+    else if let Some(invalid_request) = err.find::<RequestValidationError>() {
+        Ok(Problem { invalid_params: invalid_request.invalid_fields.clone(),
+                     ..Problem::new(StatusCode::BAD_REQUEST, "invalid_request")
+                         .with_detail(invalid_request.to_string()) })
+    }
+    else if let Some(invalid_query) = err.find::<InvalidQuery>() {
+        Ok(Problem::new(StatusCode::BAD_REQUEST, "invalid_query")
+           .with_detail(invalid_query.to_string()))
+    }
+    else if err.find::<MethodNotAllowed>().is_some() {
+        Ok(Problem::new(StatusCode::METHOD_NOT_ALLOWED, "method_not_allowed"))
+    }
+    else {
+        Err(err)
+    }
+}
+
+#[test]
+fn hard_fail_is_turned_into_problem() {
+    use crate::domain::types::error::ErrorMessageToFrontend;
+
+    let result = MatchInfoResult::builder()
+        .error_message(vec![ErrorMessageToFrontend::hard_fail(
+            "last_match_not_found",
+            "Data for LastMatch not found, possible unrecorded player detected.",
+        )
+        .with_status(StatusCode::NOT_FOUND)])
+        .build();
+
+    let problem = Problem::with_match_info_result(&result).unwrap();
+
+    assert_eq!(problem.status, 404);
+    assert_eq!(problem.code, "last_match_not_found");
+    assert_eq!(serde_json::to_value(&problem).unwrap()["type"],
+               "about:blank");
+
+    assert_eq!(Problem::with_match_info_result(&MatchInfoResult::new()), None);
+}
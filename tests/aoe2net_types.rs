@@ -0,0 +1,87 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use aoe2net::types::api::{
+    ApiStrings,
+    LastMatch,
+    Leaderboard,
+    RatingHistory,
+};
+use pretty_assertions::assert_eq;
+use serde::de::DeserializeOwned;
+
+/// Fixtures of the `standard` test case of the `matchinfo` integration test
+const FIXTURES: &str = "tests/matchinfo-integration/standard";
+
+/// Deserialize every JSON file in the `dir` of the fixtures into `T`
+fn parse_fixtures<T>(dir: &str) -> Vec<T>
+    where T: DeserializeOwned,
+{
+    let mut files = fs::read_dir(Path::new(FIXTURES).join(dir))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    files.iter()
+         .map(|file| {
+             serde_json::from_str(&fs::read_to_string(file).unwrap())
+                 .unwrap_or_else(|err| panic!("{:?}: {}", file, err))
+         })
+         .collect()
+}
+
+#[test]
+fn last_match_fixture_is_deserialized() {
+    let last_match: LastMatch = serde_json::from_str(
+        &fs::read_to_string(Path::new(FIXTURES).join("aoe2net/last_match.json"))
+            .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(last_match.profile_id, Some(196240));
+    assert_eq!(last_match.name.as_deref(), Some("GL.TheViper"));
+
+    let last_match = last_match.last_match.unwrap();
+    assert_eq!(last_match.match_id.as_deref(), Some("75984064"));
+    assert_eq!(last_match.map_type, Some(9));
+    assert_eq!(last_match.server, None);
+    assert_eq!(last_match.players.len(), 2);
+    assert_eq!(last_match.players[0].profile_id, Some(196240));
+    assert_eq!(last_match.players[0].name, None);
+    assert_eq!(last_match.players[1].won, Some(true));
+}
+
+#[test]
+fn leaderboard_fixtures_are_deserialized() {
+    let leaderboards = parse_fixtures::<Leaderboard>("aoe2net/leaderboard");
+
+    assert_eq!(leaderboards.len(), 3);
+    assert!(leaderboards.iter()
+                        .all(|leaderboard| leaderboard.leaderboard.len() == 1));
+    assert_eq!(leaderboards[0].leaderboard[0].name.as_deref(),
+               Some("GL.TheViper"));
+    assert_eq!(leaderboards[0].leaderboard[0].rating, Some(2408));
+}
+
+#[test]
+fn rating_history_fixtures_are_deserialized() {
+    let histories =
+        parse_fixtures::<Vec<RatingHistory>>("aoe2net/rating_history");
+
+    assert_eq!(histories.len(), 3);
+    assert_eq!(histories[0][0].rating, 2415);
+    assert_eq!(histories[0][0].timestamp, 1_615_409_542);
+}
+
+#[test]
+fn strings_fixtures_are_deserialized() {
+    let languages = parse_fixtures::<ApiStrings>("languages/aoe2de");
+
+    assert!(!languages.is_empty());
+    assert!(languages.iter()
+                     .all(|strings| !strings.civ.is_empty() &&
+                                    !strings.map_type.is_empty()));
+}
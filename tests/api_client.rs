@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use aoe2net::endpoints::{
+    last_match::GetLastMatchRequest,
+    Raw,
+};
+use pretty_assertions::assert_eq;
+use serde_json::{
+    json,
+    Value as JsonValue,
+};
+use transparencies_backend_rs::{
+    domain::{
+        api_handler::{
+            client::A2NClient,
+            retry::RetryPolicy,
+        },
+        types::requests::ApiRequest,
+    },
+    persistence::in_memory_db::ttl_cache::CacheStats,
+};
+use url::Url;
+use wiremock::{
+    matchers::{
+        method,
+        path,
+        query_param,
+    },
+    Mock,
+    MockServer,
+    ResponseTemplate,
+};
+
+#[tokio::test]
+async fn identical_requests_hit_upstream_once() {
+    let mock_server = MockServer::start().await;
+
+    let last_match = json!({"profile_id": 196240, "last_match": {"match_id": "1"}});
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .and(query_param("game", "aoe2de"))
+        .and(query_param("profile_id", "196240"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&last_match))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let client = A2NClient::with_client(reqwest::Client::default()).with_root(root);
+
+    for _ in 0..2 {
+        let req = GetLastMatchRequest::builder().profile_id("196240").build();
+        let response = client.req_get(Raw(req)).await.unwrap();
+
+        assert_eq!(response.data, Some(last_match.clone()));
+    }
+
+    assert_eq!(client.cache_stats(), CacheStats { hits: 1, misses: 1 });
+
+    // Clones share the same cache
+    let cloned_client = client.clone();
+    let req = GetLastMatchRequest::builder().profile_id("196240").build();
+    cloned_client.req_get(Raw(req)).await.unwrap();
+
+    assert_eq!(client.cache_stats(), CacheStats { hits: 2, misses: 1 });
+}
+
+fn fast_retry_policy() -> RetryPolicy {
+    RetryPolicy { max_retries: 2,
+                  base_delay: Duration::from_millis(10),
+                  max_delay: Duration::from_millis(50) }
+}
+
+async fn mount_unavailable_once(mock_server: &MockServer,
+                                endpoint: &str) {
+    Mock::given(method("GET"))
+        .and(path(endpoint))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn req_get_retries_after_service_unavailable() {
+    let mock_server = MockServer::start().await;
+
+    let last_match = json!({"profile_id": 196240, "last_match": {"match_id": "1"}});
+
+    mount_unavailable_once(&mock_server, "/api/player/lastmatch").await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&last_match))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let client = A2NClient::with_client(reqwest::Client::default())
+        .with_root(root)
+        .with_retry_policy(fast_retry_policy());
+
+    let req = GetLastMatchRequest::builder().profile_id("196240").build();
+    let response = client.req_get(Raw(req)).await.unwrap();
+
+    assert_eq!(response.data, Some(last_match));
+}
+
+#[tokio::test]
+async fn req_get_gives_up_on_long_retry_after() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "60"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let client = A2NClient::with_client(reqwest::Client::default())
+        .with_root(root)
+        .with_retry_policy(fast_retry_policy());
+
+    let req = GetLastMatchRequest::builder().profile_id("196240").build();
+
+    assert!(client.req_get(Raw(req)).await.is_err());
+}
+
+#[tokio::test]
+async fn api_request_retries_after_service_unavailable() {
+    let mock_server = MockServer::start().await;
+
+    let strings = json!({"language": "en"});
+
+    mount_unavailable_once(&mock_server, "/api/strings").await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/strings"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&strings))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let req = ApiRequest::builder()
+        .client(reqwest::Client::default())
+        .root(Url::parse(&format!("{}/api", &mock_server.uri())).unwrap())
+        .endpoint("strings")
+        .query(vec![("language".to_string(), "en".to_string())])
+        .retry_policy(fast_retry_policy())
+        .build();
+
+    let response: JsonValue = req.execute().await.unwrap();
+
+    assert_eq!(response, strings);
+}
+
+#[tokio::test]
+async fn failures_surface_after_retries_are_exhausted() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let client = A2NClient::with_client(reqwest::Client::default())
+        .with_root(root)
+        .with_retry_policy(fast_retry_policy());
+
+    let req = GetLastMatchRequest::builder().profile_id("196240").build();
+
+    assert!(client.req_get(Raw(req)).await.is_err());
+}
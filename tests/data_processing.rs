@@ -0,0 +1,1056 @@
+use std::{
+    sync::Arc,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use http::StatusCode;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tokio::sync::Mutex;
+use transparencies_backend_rs::domain::{
+    api_handler::{
+        circuit_breaker::CircuitBreaker,
+        client::A2NClient,
+        retry::RetryPolicy,
+    },
+    data_processing::{
+        build_result_coalesced,
+        leaderboard::build_leaderboard,
+        lobbies::build_lobbies,
+        match_history::build_match_history,
+        player_profile::build_player_profile,
+        player_search::search_players,
+        pro_matches::{
+            build_pro_matches,
+            poll_pro_matches,
+        },
+        match_info_processor::MatchInfoProcessor,
+        rating_history::build_rating_history,
+    },
+    provider::FixtureProvider,
+    types::{
+        api::{
+            IdType,
+            LeaderboardRequest,
+            LobbiesRequest,
+            MatchHistoryRequest,
+            MatchInfo,
+            MatchInfoRequest,
+            MatchInfoResult,
+            PlayerProfileRequest,
+            PlayerSearchRequest,
+            PlayerSearchSource,
+            ProMatchesRequest,
+            RatingHistoryRequest,
+        },
+        error::{
+            ErrorSeverity,
+            ResponderError,
+        },
+        match_data::{
+            LeaderboardStanding,
+            MatchData,
+            MatchDataResponses,
+            MatchPlayerData,
+            PlayerLastMatch,
+            PlayerRating,
+            Translations,
+        },
+        testing::ref_data_lists,
+        InMemoryDb,
+    },
+};
+use url::Url;
+use wiremock::{
+    matchers::{
+        method,
+        path,
+        query_param,
+    },
+    Mock,
+    MockServer,
+    ResponseTemplate,
+};
+
+/// Read a response of aoe2.net from the fixtures of the standard `matchinfo`
+/// integration test
+fn standard_fixture(file: &str) -> String {
+    let fixtures = std::path::Path::new("tests/matchinfo-integration/standard/aoe2net");
+    std::fs::read_to_string(fixtures.join(file)).unwrap()
+}
+
+/// Mount the rating histories and leaderboards of both players of the standard
+/// `matchinfo` integration test
+async fn mount_standard_ratings(mock_server: &MockServer) {
+    for profile_id in &["196240", "4240386"] {
+        for (endpoint, fixture_dir) in &[("/api/player/ratinghistory", "rating_history"),
+                                         ("/api/leaderboard", "leaderboard")]
+        {
+            Mock::given(method("GET"))
+                .and(path(*endpoint))
+                .and(query_param("profile_id", *profile_id))
+                .respond_with(ResponseTemplate::new(200).set_body_string(standard_fixture(&format!("{}/{}.json",
+                                                                                                   fixture_dir,
+                                                                                                   profile_id))))
+                .mount(mock_server)
+                .await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn concurrent_identical_requests_are_coalesced() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"profile_id": 196240})))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    let par = MatchInfoRequest { id_type: IdType::ProfileId,
+                                 id_number: "196240".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    // Different clients, so the response cache can't be the reason for a
+    // single upstream request
+    let (first, second) =
+        tokio::join!(build_result_coalesced(par.clone(),
+                                            A2NClient::with_client(reqwest::Client::default()),
+                                            root.clone(),
+                                            in_memory_db.clone()),
+                     build_result_coalesced(par.clone(),
+                                            A2NClient::with_client(reqwest::Client::default()),
+                                            root.clone(),
+                                            in_memory_db.clone()));
+
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn cancelled_request_does_not_stay_in_flight() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"profile_id": 196241}))
+                                                .set_delay(Duration::from_millis(200)))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    let par = MatchInfoRequest { id_type: IdType::ProfileId,
+                                 id_number: "196241".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    // The first request gets dropped while it is waiting for aoe2.net
+    let cancelled = tokio::time::timeout(Duration::from_millis(50),
+                                         build_result_coalesced(par.clone(),
+                                                                A2NClient::with_client(reqwest::Client::default()),
+                                                                root.clone(),
+                                                                in_memory_db.clone())).await;
+    assert!(cancelled.is_err());
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // The cancelled request has been finished and removed in the meantime, so
+    // this one is processed again instead of joining a stale result
+    build_result_coalesced(par,
+                           A2NClient::with_client(reqwest::Client::default()),
+                           root,
+                           in_memory_db).await;
+}
+
+#[tokio::test]
+async fn stale_result_is_served_while_circuit_is_open() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    let par = MatchInfoRequest { id_type: IdType::ProfileId,
+                                 id_number: "196242".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    let last_good = MatchInfoResult::builder().match_info(MatchInfo::default())
+                                              .build();
+    in_memory_db.lock()
+                .await
+                .last_good_match_infos
+                .insert(par.clone(), last_good.clone(), Duration::from_secs(60));
+
+    let client = A2NClient::with_client(reqwest::Client::default())
+        .with_retry_policy(RetryPolicy::disabled())
+        .with_circuit_breaker(CircuitBreaker::new(1, Duration::from_secs(30)));
+
+    // The first request fails and opens the circuit, the second one doesn't
+    // reach the upstream at all
+    for _ in 0..2 {
+        let result = build_result_coalesced(par.clone(),
+                                            client.clone(),
+                                            root.clone(),
+                                            in_memory_db.clone()).await;
+
+        assert_eq!(result.match_info, last_good.match_info);
+        assert_eq!(result.error_message.len(), 1);
+        assert_eq!(result.error_message[0].severity, ErrorSeverity::Recover);
+    }
+}
+
+#[tokio::test]
+async fn stale_result_is_served_while_circuit_is_half_open() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(503).set_delay(Duration::from_millis(300)))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    // Different requests, so they can't join each other's in-flight result
+    let pars = ["196243", "196244"].iter()
+                                   .map(|id_number| MatchInfoRequest { id_type: IdType::ProfileId,
+                                                                       id_number: id_number.to_string(),
+                                                                       ..MatchInfoRequest::default() })
+                                   .collect::<Vec<_>>();
+
+    let last_good = MatchInfoResult::builder().match_info(MatchInfo::default())
+                                              .build();
+    for par in &pars {
+        in_memory_db.lock()
+                    .await
+                    .last_good_match_infos
+                    .insert(par.clone(), last_good.clone(), Duration::from_secs(60));
+    }
+
+    let client = A2NClient::with_client(reqwest::Client::default())
+        .with_retry_policy(RetryPolicy::disabled())
+        .with_circuit_breaker(CircuitBreaker::new(1, Duration::from_millis(200)));
+
+    // Opens the circuit
+    build_result_coalesced(pars[0].clone(), client.clone(), root.clone(), in_memory_db.clone()).await;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    // The first request is let through as the probe, the second one arrives
+    // while the probe is in flight
+    let (_, result) = tokio::join!(build_result_coalesced(pars[0].clone(),
+                                                          client.clone(),
+                                                          root.clone(),
+                                                          in_memory_db.clone()),
+                                   async {
+                                       tokio::time::sleep(Duration::from_millis(50)).await;
+                                       build_result_coalesced(pars[1].clone(),
+                                                              client.clone(),
+                                                              root.clone(),
+                                                              in_memory_db.clone()).await
+                                   });
+
+    assert_eq!(result.match_info, last_good.match_info);
+    assert_eq!(result.error_message.len(), 1);
+    assert_eq!(result.error_message[0].severity, ErrorSeverity::Recover);
+}
+
+#[tokio::test]
+async fn unknown_player_is_not_found() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({"error": "Not Found",
+                                                                      "status": 404,
+                                                                      "message": "Not Found"})))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let par = MatchInfoRequest { id_type: IdType::ProfileId,
+                                 id_number: "1".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    let result = build_result_coalesced(par,
+                                        A2NClient::with_client(reqwest::Client::default()),
+                                        root,
+                                        Arc::new(Mutex::new(InMemoryDb::default()))).await;
+
+    assert_eq!(result.match_info, None);
+    assert_eq!(result.error_message[0].code, "last_match_not_found");
+    assert_eq!(result.error_message[0].status, Some(StatusCode::NOT_FOUND));
+}
+
+#[tokio::test]
+async fn steam_id_is_used_for_last_match() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .and(query_param("steam_id", "76561197984749679"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(standard_fixture("last_match.json")))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    mount_standard_ratings(&mock_server).await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let par = MatchInfoRequest { id_type: IdType::SteamId,
+                                 id_number: "76561197984749679".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    let result = build_result_coalesced(par,
+                                        A2NClient::with_client(reqwest::Client::default()),
+                                        root,
+                                        Arc::new(Mutex::new(InMemoryDb::default()))).await;
+
+    assert!(result.error_message
+                  .iter()
+                  .all(|error| error.severity != ErrorSeverity::HardFail));
+
+    // The player with the requested steam_id is marked as requested
+    let result = serde_json::to_value(&result).unwrap();
+    let requested = result["match_info"]["teams"].as_array()
+                                                 .unwrap()
+                                                 .iter()
+                                                 .flat_map(|team| team["players"].as_array().unwrap())
+                                                 .filter(|player| player["requested"] == true)
+                                                 .collect::<Vec<_>>();
+
+    assert_eq!(requested.len(), 1);
+    assert_eq!(requested[0]["rating"]["mmr"], 2415);
+    assert_eq!(requested[0]["country"], "no");
+}
+
+#[tokio::test]
+async fn head_to_head_is_cached_per_player_pair() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/lastmatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(standard_fixture("last_match.json")))
+        .mount(&mock_server)
+        .await;
+
+    mount_standard_ratings(&mock_server).await;
+
+    for profile_id in &["196240", "4240386"] {
+        // Only the first request reaches the match histories
+        Mock::given(method("GET"))
+            .and(path("/api/player/matches"))
+            .and(query_param("profile_id", *profile_id))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "match_id": "1",
+                "map_type": 9,
+                "started": 1_614_000_000,
+                "players": [
+                    {"profile_id": 196240, "civ": 29, "team": 1, "won": true},
+                    {"profile_id": 4240386, "civ": 24, "team": 2, "won": false}
+                ]
+            }])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    let par = MatchInfoRequest { id_type: IdType::ProfileId,
+                                 id_number: "196240".to_string(),
+                                 h2h: true,
+                                 ..MatchInfoRequest::default() };
+
+    // Different clients, so the response cache can't be the reason for a
+    // single upstream request
+    for _ in 0..2 {
+        let result = build_result_coalesced(par.clone(),
+                                            A2NClient::with_client(reqwest::Client::default()),
+                                            root.clone(),
+                                            in_memory_db.clone()).await;
+
+        let result = serde_json::to_value(&result).unwrap();
+        assert_eq!(result["match_info"]["head_to_head"]["games"], 1);
+    }
+}
+
+#[tokio::test]
+async fn ambiguous_name_returns_candidates() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/leaderboard"))
+        .and(query_param("search", "Viper"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "leaderboard": [
+                {"profile_id": 196240, "name": "TheViper", "country": "NO"},
+                {"profile_id": 1, "name": "ViperFan", "country": null}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let par = MatchInfoRequest { id_type: IdType::Name,
+                                 id_number: "Viper".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    let result = build_result_coalesced(par,
+                                        A2NClient::with_client(reqwest::Client::default()),
+                                        root,
+                                        Arc::new(Mutex::new(InMemoryDb::default()))).await;
+
+    assert_eq!(result.error_message[0].code, "ambiguous_player_name");
+    assert_eq!(result.error_message[0].status, Some(StatusCode::MULTIPLE_CHOICES));
+    assert_eq!(result.candidates
+                     .iter()
+                     .map(|candidate| candidate.profile_id.as_str())
+                     .collect::<Vec<_>>(),
+               vec!["196240", "1"]);
+}
+
+#[tokio::test]
+async fn player_search_falls_back_to_reference_data() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/leaderboard"))
+        .and(query_param("search", "vip"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let in_memory_db = InMemoryDb {
+        github_file_content: ref_data_lists(
+            json!([
+                {"name": "TheViper", "aka": ["Viper"], "country": "no", "platforms": {"de": ["196240"]}},
+                {"name": "Hera", "country": "ca", "platforms": {"de": ["199325"]}}
+            ]),
+            json!([]),
+        ),
+        ..InMemoryDb::default()
+    };
+
+    let par = PlayerSearchRequest { q: "vip".to_string(),
+                                    game: None };
+
+    let client = A2NClient::with_client(reqwest::Client::default())
+        .with_retry_policy(RetryPolicy::disabled());
+
+    let response = search_players(par, client, root, Arc::new(Mutex::new(in_memory_db))).await;
+
+    assert_eq!(response.players.len(), 1);
+    assert_eq!(response.players[0].name, "TheViper");
+    assert_eq!(response.players[0].profile_ids, vec!["196240"]);
+    assert_eq!(response.players[0].source, PlayerSearchSource::ReferenceData);
+}
+
+async fn mount_unranked_leaderboards(mock_server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/api/leaderboard"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"count": 0, "leaderboard": []})))
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn player_profile_merges_reference_data_and_ratings() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/leaderboard"))
+        .and(query_param("leaderboard_id", "3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "count": 1,
+            "leaderboard": [{"profile_id": 196240, "name": "GL.TheViper", "country": "NO", "rank": 2, "highest_rating": 2600}]
+        })))
+        .with_priority(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/ratinghistory"))
+        .and(query_param("leaderboard_id", "3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {"rating": 2550, "num_wins": 30, "num_losses": 10, "streak": 3, "drops": 0, "timestamp": 1_614_000_000}
+        ])))
+        .mount(&mock_server)
+        .await;
+
+    mount_unranked_leaderboards(&mock_server).await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let in_memory_db = InMemoryDb {
+        github_file_content: ref_data_lists(
+            json!([
+                {"name": "TheViper", "country": "no", "liquipedia": "TheViper", "platforms": {"de": ["196240"]}}
+            ]),
+            json!([
+                {"name": "GamerLegion", "abbreviation": "GL", "players": ["TheViper"]}
+            ]),
+        ),
+        ..InMemoryDb::default()
+    };
+
+    let par = PlayerProfileRequest { profile_id: "196240".to_string(),
+                                     game: None };
+
+    let profile = build_player_profile(par,
+                                       A2NClient::with_client(reqwest::Client::default()),
+                                       root,
+                                       Arc::new(Mutex::new(in_memory_db))).await
+                                                                          .unwrap();
+
+    assert_eq!(profile.name, "TheViper");
+    assert_eq!(profile.team.unwrap().name, "GamerLegion");
+    assert_eq!(profile.liquipedia, Some("TheViper".to_string()));
+
+    let rating = serde_json::to_value(profile.ratings.rm_1v1.unwrap()).unwrap();
+    assert_eq!(rating["mmr"], 2550);
+    assert_eq!(rating["rank"], 2);
+    assert_eq!(rating["win_rate"], 75.0);
+
+    assert_eq!(profile.ratings.rm_team, None);
+    assert!(profile.error_message.is_empty());
+}
+
+#[tokio::test]
+async fn unranked_unknown_player_profile_is_not_found() {
+    let mock_server = MockServer::start().await;
+
+    mount_unranked_leaderboards(&mock_server).await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let par = PlayerProfileRequest { profile_id: "1".to_string(),
+                                     game: None };
+
+    let profile = build_player_profile(par,
+                                       A2NClient::with_client(reqwest::Client::default()),
+                                       root,
+                                       Arc::new(Mutex::new(InMemoryDb::default()))).await;
+
+    assert!(matches!(profile, Err(ResponderError::PlayerNotFound(_))));
+}
+
+#[tokio::test]
+async fn rating_history_is_downsampled_time_series() {
+    let mock_server = MockServer::start().await;
+
+    let history = (0..10).rev()
+                         .map(|game| json!({
+                             "rating": 2000 + game * 10,
+                             "num_wins": 10 + game,
+                             "num_losses": 10,
+                             "streak": game,
+                             "drops": 0,
+                             "timestamp": 1_614_000_000 + game * 3600
+                         }))
+                         .collect::<Vec<_>>();
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/ratinghistory"))
+        .and(query_param("profile_id", "196240"))
+        .and(query_param("leaderboard_id", "4"))
+        .and(query_param("start", "0"))
+        .and(query_param("count", "10"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!(history)))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let par = RatingHistoryRequest { profile_id: "196240".to_string(),
+                                     game: None,
+                                     leaderboard_id: 4,
+                                     count: 10,
+                                     points: Some(4) };
+
+    let response = build_rating_history(par,
+                                        A2NClient::with_client(reqwest::Client::default()),
+                                        root).await
+                                             .unwrap();
+
+    assert_eq!(response.points
+                       .iter()
+                       .map(|point| point.rating)
+                       .collect::<Vec<_>>(),
+               vec![2000, 2030, 2060, 2090]);
+    assert_eq!(response.points[1].delta, 10);
+    assert_eq!(response.points[3].timestamp, "2021-02-22T22:20:00Z");
+    assert_eq!(response.peak.unwrap().rating, 2090);
+    assert_eq!(response.trough.unwrap().rating, 2000);
+    assert_eq!(response.current_streak, 9);
+}
+
+#[tokio::test]
+async fn match_history_is_translated_and_aggregated() {
+    let mock_server = MockServer::start().await;
+
+    let match_data = |match_id: &str, map_type: u64, won: bool| {
+        json!({
+            "match_id": match_id,
+            "map_type": map_type,
+            "started": 1_614_000_000,
+            "players": [
+                {"profile_id": 196240, "name": "GL.TheViper", "civ": 29,
+                 "team": 1, "won": won, "rating": 2500, "rating_change": 12},
+                {"profile_id": 199325, "name": "Hera", "civ": 24,
+                 "team": 2, "won": !won}
+            ]
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/player/matches"))
+        .and(query_param("profile_id", "196240"))
+        .and(query_param("start", "0"))
+        .and(query_param("count", "3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            match_data("3", 9, true),
+            match_data("2", 29, false),
+            match_data("1", 9, true)
+        ])))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+    in_memory_db.lock().await.aoe2net_languages.insert(
+        "aoe2de".to_string(),
+        vec![("en".to_string(),
+              json!({
+                  "civ": [{"id": 24, "string": "Mayans"}, {"id": 29, "string": "Vikings"}],
+                  "map_type": [{"id": 9, "string": "Arabia"}, {"id": 29, "string": "Arena"}]
+              }))].into_iter()
+                  .collect(),
+    );
+
+    let par = MatchHistoryRequest { profile_id: "196240".to_string(),
+                                    language: None,
+                                    game: None,
+                                    count: 3,
+                                    opponent_id: None };
+
+    let response = build_match_history(par,
+                                       A2NClient::with_client(reqwest::Client::default()),
+                                       root,
+                                       in_memory_db).await
+                                                    .unwrap();
+
+    assert_eq!(response.matches.len(), 3);
+    assert_eq!((response.matches[0].map.as_str(),
+                response.matches[0].civ.as_str(),
+                response.matches[0].rating_change),
+               ("Arabia", "Vikings", Some(12)));
+    assert_eq!(response.matches[0].opponents[0].civ, "Mayans");
+    assert_eq!((response.per_map[0].name.as_str(), response.per_map[0].wins),
+               ("Arabia", 2));
+    assert_eq!(response.per_civ[0].win_rate.map(f32::round), Some(67.0));
+
+    let versus = response.versus_opponent.unwrap();
+    assert_eq!((versus.profile_id.as_str(),
+                versus.stats.name.as_str(),
+                versus.stats.wins,
+                versus.stats.losses),
+               ("199325", "Hera", 2, 1));
+}
+
+#[tokio::test]
+async fn leaderboard_page_is_cached() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/leaderboard"))
+        .and(query_param("leaderboard_id", "3"))
+        .and(query_param("start", "1"))
+        .and(query_param("count", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "total": 40000,
+            "leaderboard_id": 3,
+            "start": 1,
+            "count": 2,
+            "leaderboard": [
+                {"profile_id": 196240, "rank": 1, "name": "GL.TheViper",
+                 "country": "NO", "rating": 2600, "highest_rating": 2700,
+                 "streak": 3, "wins": 30, "losses": 10},
+                {"profile_id": 199325, "rank": 2, "name": "Hera",
+                 "country": "CA", "rating": 2550, "highest_rating": 2650,
+                 "streak": -1, "wins": 20, "losses": 20}
+            ]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+    let par = LeaderboardRequest { leaderboard_id: 3,
+                                   language: None,
+                                   game: None,
+                                   start: 1,
+                                   count: 2 };
+
+    // Clones of a client share the response cache
+    let client = A2NClient::with_client(reqwest::Client::default());
+
+    for _ in 0..2 {
+        let leaderboard = build_leaderboard(par.clone(),
+                                            client.clone(),
+                                            root.clone(),
+                                            in_memory_db.clone()).await
+                                                                 .unwrap();
+
+        assert_eq!(leaderboard.total, Some(40000));
+        assert_eq!(leaderboard.entries
+                              .iter()
+                              .map(|entry| (entry.rank, entry.name.as_str()))
+                              .collect::<Vec<_>>(),
+                   vec![(1, "GL.TheViper"), (2, "Hera")]);
+        assert_eq!(leaderboard.entries[1].country.as_deref(), Some("ca"));
+        assert_eq!(leaderboard.entries[1].win_rate, Some(50.0));
+    }
+}
+
+#[tokio::test]
+async fn lobbies_are_filtered_for_known_players() {
+    let mock_server = MockServer::start().await;
+
+    let lobby = |match_id: &str, average_rating: i64, profile_id: i64| {
+        json!({
+            "match_id": match_id,
+            "name": "1v1",
+            "map_type": 9,
+            "game_type": 0,
+            "rating_type": 2,
+            "server": "westeurope",
+            "average_rating": average_rating,
+            "num_players": 1,
+            "num_slots": 2,
+            "opened": 1_614_000_000,
+            "players": [
+                {"profile_id": profile_id, "name": "Player", "country": "DE",
+                 "rating": average_rating},
+                {"profile_id": null, "name": null}
+            ]
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/lobbies"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            lobby("1", 2400, 199325),
+            lobby("2", 2400, 1),
+            lobby("3", 1000, 199325)
+        ])))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+    let in_memory_db = InMemoryDb {
+        github_file_content: ref_data_lists(
+            json!([
+                {"name": "Hera", "country": "ca", "platforms": {"de": ["199325"]}}
+            ]),
+            json!([]),
+        ),
+        ..InMemoryDb::default()
+    };
+    in_memory_db.aoe2net_languages.insert(
+        "aoe2de".to_string(),
+        vec![("en".to_string(),
+              json!({"map_type": [{"id": 9, "string": "Arabia"}]}))].into_iter()
+                                                                     .collect(),
+    );
+
+    let par = LobbiesRequest { map: Some("arabia".to_string()),
+                               min_rating: Some(2000),
+                               known_players: true,
+                               ..LobbiesRequest::default() };
+
+    let response = build_lobbies(par,
+                                 A2NClient::with_client(reqwest::Client::default()),
+                                 root,
+                                 Arc::new(Mutex::new(in_memory_db))).await
+                                                                    .unwrap();
+
+    assert_eq!(response.lobbies
+                       .iter()
+                       .map(|lobby| lobby.match_id.as_str())
+                       .collect::<Vec<_>>(),
+               vec!["1"]);
+    assert_eq!(response.lobbies[0].players[0].alias.as_deref(), Some("Hera"));
+    assert_eq!(response.lobbies[0].players[0].country.as_deref(), Some("ca"));
+}
+
+#[tokio::test]
+async fn pro_matches_are_discovered_incrementally() {
+    let mock_server = MockServer::start().await;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+                               .unwrap()
+                               .as_secs() as i64;
+
+    let match_data = |match_id: &str, started: i64, profile_id: i64| {
+        json!({
+            "match_id": match_id,
+            "map_type": 9,
+            "rating_type": 2,
+            "server": "westeurope",
+            "started": started,
+            "finished": null,
+            "players": [
+                {"profile_id": profile_id, "name": "Player", "country": "DE",
+                 "civ": 1, "team": 1, "rating": 2400},
+                {"profile_id": 2, "name": "Other", "country": "DE",
+                 "civ": 1, "team": 2, "rating": 2400}
+            ]
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/matches"))
+        .and(query_param("since", (now - 120).to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            match_data("1", now - 60, 199325),
+            match_data("2", now - 30, 1),
+            match_data("3", now, 199325)
+        ])))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let client =
+        A2NClient::with_client(reqwest::Client::default()).with_root(root);
+
+    let in_memory_db = InMemoryDb {
+        github_file_content: ref_data_lists(
+            json!([
+                {"name": "Hera", "country": "ca", "platforms": {"de": ["199325"]}}
+            ]),
+            json!([]),
+        ),
+        ..InMemoryDb::default()
+    };
+    let in_memory_db = Arc::new(Mutex::new(in_memory_db));
+
+    let cursor =
+        poll_pro_matches(&client, "aoe2de", Some(now - 120), &in_memory_db)
+            .await
+            .unwrap();
+
+    assert_eq!(cursor, Some(now));
+
+    let response =
+        build_pro_matches(ProMatchesRequest::default(), in_memory_db).await;
+
+    assert_eq!(response.matches
+                       .iter()
+                       .map(|pro_match| pro_match.match_id.as_str())
+                       .collect::<Vec<_>>(),
+               vec!["3", "1"]);
+    assert_eq!(response.matches[0].players[0].alias.as_deref(), Some("Hera"));
+}
+
+#[tokio::test]
+async fn finished_pro_match_is_dropped() {
+    let mock_server = MockServer::start().await;
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+                               .unwrap()
+                               .as_secs() as i64;
+
+    let match_data = |match_id: &str, finished: Option<i64>| {
+        json!({
+            "match_id": match_id,
+            "started": now - 600,
+            "finished": finished,
+            "players": [{"profile_id": 199325, "name": "Player"}]
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/api/matches"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    for (match_id, finished) in &[("1", Some(now - 60)), ("2", None)] {
+        Mock::given(method("GET"))
+            .and(path("/api/match"))
+            .and(query_param("match_id", *match_id))
+            .respond_with(ResponseTemplate::new(200).set_body_json(match_data(match_id, *finished)))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let root = Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    let client =
+        A2NClient::with_client(reqwest::Client::default()).with_root(root);
+
+    let in_memory_db = InMemoryDb {
+        github_file_content: ref_data_lists(
+            json!([
+                {"name": "Hera", "country": "ca", "platforms": {"de": ["199325"]}}
+            ]),
+            json!([]),
+        ),
+        ..InMemoryDb::default()
+    };
+    // Both matches were ongoing when they were discovered
+    for match_id in &["1", "2"] {
+        in_memory_db.pro_matches.insert(
+            match_id.to_string(),
+            serde_json::from_value(match_data(match_id, None)).unwrap(),
+        );
+    }
+    let in_memory_db = Arc::new(Mutex::new(in_memory_db));
+
+    poll_pro_matches(&client, "aoe2de", Some(now - 120), &in_memory_db)
+        .await
+        .unwrap();
+
+    let response =
+        build_pro_matches(ProMatchesRequest::default(), in_memory_db).await;
+
+    assert_eq!(response.matches
+                       .iter()
+                       .map(|pro_match| pro_match.match_id.as_str())
+                       .collect::<Vec<_>>(),
+               vec!["2"]);
+}
+
+#[tokio::test]
+async fn match_info_is_processed_from_fixture_provider() {
+    let player = |profile_id: &str, name: &str, civ, color, team| {
+        MatchPlayerData { profile_id: profile_id.to_string(),
+                          name: name.to_string(),
+                          civ: Some(civ),
+                          color,
+                          slot: color,
+                          team }
+    };
+
+    let mut provider = FixtureProvider::default();
+    provider.last_matches.insert(
+        "196240".to_string(),
+        PlayerLastMatch {
+            profile_id: Some("196240".to_string()),
+            last_match: MatchData { match_id: Some("75984064".to_string()),
+                                    leaderboard_id: Some(3),
+                                    rating_type: Some(2),
+                                    map_type: Some(9),
+                                    game_type: Some(0),
+                                    server: Some("ukwest".to_string()),
+                                    started: Some(1_611_180_000),
+                                    finished: None,
+                                    players: vec![player("196240",
+                                                         "GL.TheViper",
+                                                         23,
+                                                         1,
+                                                         1),
+                                                  player("197964", "Hera", 9, 2, 2)] },
+        },
+    );
+    provider.ratings.insert(
+        "196240".to_string(),
+        PlayerRating { rating: 2415,
+                       wins: 3,
+                       losses: 1,
+                       streak: 2,
+                       standing: Some(LeaderboardStanding { rank: 18,
+                                                            highest_rating: 2510,
+                                                            country: Some("no".to_string()) }) },
+    );
+    provider.translations.insert(
+        "en".to_string(),
+        Translations(
+            vec![("civ", vec![(23, "Sicilians"), (9, "Franks")]),
+                 ("game_type", vec![(0, "Random Map")]),
+                 ("map_type", vec![(9, "Arabia")]),
+                 ("rating_type", vec![(2, "1v1 Random Map")])]
+                .into_iter()
+                .map(|(category, names)| {
+                    (category.to_string(),
+                     names.into_iter()
+                          .map(|(id, name)| (id, name.to_string()))
+                          .collect())
+                })
+                .collect(),
+        ),
+    );
+
+    let par = MatchInfoRequest { id_type: IdType::ProfileId,
+                                 id_number: "196240".to_string(),
+                                 ..MatchInfoRequest::default() };
+
+    let responses =
+        MatchDataResponses::with_match_data(par,
+                                            &provider,
+                                            Arc::new(Mutex::new(InMemoryDb::default())))
+            .await
+            .unwrap();
+
+    let result = MatchInfoProcessor::with_response(responses).process()
+                                                             .unwrap()
+                                                             .assemble()
+                                                             .unwrap();
+    let result = serde_json::to_value(&result).unwrap();
+
+    let match_info = &result["match_info"];
+    assert_eq!(match_info["map_name"], "Arabia");
+    assert_eq!(match_info["rating_type"], "1v1 Random Map");
+    assert_eq!(match_info["match_status"], "Running");
+
+    // Teams are ordered descending, the requested player is last
+    let viper = &match_info["teams"][1]["players"][0];
+    assert_eq!(viper["civilisation"], "Sicilians");
+    assert_eq!(viper["country"], "no");
+    assert_eq!(viper["requested"], true);
+    assert_eq!(viper["rating"]["mmr"], 2415);
+    assert_eq!(viper["rating"]["win_rate"], 75.0);
+
+    // Hera has no rating at the provider
+    let hera = &match_info["teams"][0]["players"][0];
+    assert_eq!(hera["civilisation"], "Franks");
+    assert_eq!(hera["rating"]["mmr"], 0);
+    assert!(result["error_message"].as_array()
+                                   .unwrap()
+                                   .iter()
+                                   .any(|err| err["code"] == "rating_not_found"));
+}
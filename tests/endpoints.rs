@@ -1,8 +1,25 @@
+use std::sync::Arc;
+
 use pretty_assertions::assert_eq;
-use transparencies_backend_rs::server::filters;
+use serde_json::Value as JsonValue;
+use tokio::sync::Mutex;
+use transparencies_backend_rs::{
+    domain::{
+        api_handler::client::A2NClient,
+        types::InMemoryDb,
+    },
+    server::{
+        filters,
+        problem::{
+            handle_rejection,
+            PROBLEM_JSON,
+        },
+    },
+};
 use warp::{
     http::StatusCode,
     test::request,
+    Filter,
 };
 
 #[tokio::test]
@@ -16,3 +33,83 @@ async fn health_check_works() {
 
     assert_eq!(resp.status(), StatusCode::OK);
 }
+
+#[tokio::test]
+async fn invalid_matchinfo_query_is_problem_json() {
+    let api = filters::transparencies(A2NClient::with_client(reqwest::Client::default()),
+                                      Arc::new(Mutex::new(InMemoryDb::default())));
+
+    let resp = request().method("GET")
+                        .path("/matchinfo?id_type=profile_id")
+                        .reply(&api)
+                        .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.headers()["content-type"], PROBLEM_JSON);
+
+    let body: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+
+    assert_eq!(body["status"], 400);
+    assert_eq!(body["code"], "invalid_request");
+    assert_eq!(body["invalid_params"][0]["name"], "id_number");
+}
+
+#[tokio::test]
+async fn every_invalid_matchinfo_parameter_is_listed() {
+    let api = filters::matchinfo(A2NClient::with_client(reqwest::Client::default()),
+                                 Arc::new(Mutex::new(InMemoryDb::default())))
+        .recover(handle_rejection);
+
+    let resp = request().method("GET")
+                        .path("/matchinfo?id_type=nickname&id_number=abc&language=xx&game=aoe2de&h2h=maybe")
+                        .reply(&api)
+                        .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let body: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+
+    let invalid_params = body["invalid_params"].as_array()
+                                               .unwrap()
+                                               .iter()
+                                               .map(|param| param["name"].as_str().unwrap())
+                                               .collect::<Vec<_>>();
+
+    assert_eq!(invalid_params, vec!["id_type", "language", "h2h"]);
+}
+
+#[tokio::test]
+async fn player_search_without_query_is_problem_json() {
+    let api = filters::transparencies(A2NClient::with_client(reqwest::Client::default()),
+                                      Arc::new(Mutex::new(InMemoryDb::default())));
+
+    let resp = request().method("GET")
+                        .path("/players/search?q=%20")
+                        .reply(&api)
+                        .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let body: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+
+    assert_eq!(body["invalid_params"][0]["name"], "q");
+}
+
+#[tokio::test]
+async fn subscription_for_a_match_is_problem_json() {
+    let api = filters::transparencies(A2NClient::with_client(reqwest::Client::default()),
+                                      Arc::new(Mutex::new(InMemoryDb::default())));
+
+    let resp = request().method("GET")
+                        .path("/subscribe?id_type=match_id&id_number=74208654")
+                        .reply(&api)
+                        .await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(resp.headers()["content-type"], PROBLEM_JSON);
+
+    let body: JsonValue = serde_json::from_slice(resp.body()).unwrap();
+
+    assert_eq!(body["code"], "invalid_request");
+    assert_eq!(body["invalid_params"][0]["name"], "id_type");
+}
@@ -57,69 +57,47 @@ init_subscriber(subscriber);
 
 #[tokio::test]
 async fn matchinfo_pipeline_works() {
-    let current_dir = std::env::current_dir().unwrap();
+    mock_test_match_info_result(test_cases_for("standard")).await
+}
 
-    let test_cases =
-        TestCases::default().add_case([&format!("{}", current_dir.display()),
-                                       "tests",
-                                       "matchinfo-integration",
-                                       "standard"].iter()
-                                                  .collect())
-                            .unwrap();
+#[tokio::test]
+async fn matchinfo_pipeline_works_for_aoe3de() {
+    mock_test_match_info_result(test_cases_for("aoe3de")).await
+}
 
-    mock_test_match_info_result(test_cases).await
+#[tokio::test]
+async fn matchinfo_pipeline_works_for_aoe4() {
+    mock_test_match_info_result(test_cases_for("aoe4")).await
 }
 
 // #[tokio::test]
 // async fn last_match_404() {
-//     let current_dir = std::env::current_dir().unwrap();
-
-//     let test_cases = TestCases::default()
-//         .add_case(
-//             [
-//                 &format!("{}", current_dir.display()),
-//                 "tests",
-//                 "matchinfo-integration",
-//                 "last_match_404",
-//             ]
-//             .iter()
-//             .collect(),
-//         )
-//         .unwrap();
-
-//     mock_test_match_info_result(test_cases).await
+//     mock_test_match_info_result(test_cases_for("last_match_404")).await
 // }
 
+/// Parse the test case in the folder `name` of `matchinfo-integration`
+fn test_cases_for(name: &str) -> TestCases {
+    let current_dir = std::env::current_dir().unwrap();
+
+    TestCases::default().add_case([&format!("{}", current_dir.display()),
+                                   "tests",
+                                   "matchinfo-integration",
+                                   name].iter()
+                                        .collect())
+                        .unwrap()
+}
+
 async fn mock_test_match_info_result(test_cases: TestCases) {
     // The first time `initialize` is invoked the code in `TRACING` is executed.
     // All other invocations will instead skip execution.
     lazy_static::initialize(&TRACING);
 
-    // Start a background HTTP server on a random local port
-    let mock_server = MockServer::start().await;
-
     let aoe2net_api_roots: Vec<&str> = vec!["/api/strings",
                                             "/api/player/lastmatch",
                                             "/api/leaderboard",
                                             "/api/player/ratinghistory",
                                             "/SiegeEngineers/aoc-reference-data/master/data/",];
 
-    // Preloaded data
-    let language_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>> =
-        Arc::new(Mutex::new(DashMap::with_capacity(18)));
-    let language_mock_responses_clone = language_mock_responses.clone();
-
-    let github_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>> =
-        Arc::new(Mutex::new(DashMap::with_capacity(3)));
-    let github_mock_responses_clone = github_mock_responses.clone();
-
-    let aoe2net_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>> =
-        Arc::new(Mutex::new(DashMap::with_capacity(16)));
-    let aoe2net_mock_responses_clone = aoe2net_mock_responses.clone();
-
-    let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
-    let in_memory_db_clone = in_memory_db.clone();
-
     let mock_client =
         reqwest::Client::builder().user_agent(*APP_USER_AGENT)
                                   .timeout(*CLIENT_REQUEST_TIMEOUT)
@@ -131,55 +109,72 @@ async fn mock_test_match_info_result(test_cases: TestCases) {
 
     let a2n_client = A2NClient::with_client(mock_client.clone());
 
-    let github_root = Url::parse(&format!("{}", &mock_server.uri())).unwrap();
-    let aoe2_net_root =
-        Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+    for mut test_case in test_cases.0 {
+        // Each test case gets its own mock server and in-memory DB, so the
+        // preloaded data of one game can't leak into another one
 
-    // URL
-    let missing_link_url =
-        Url::parse(&format!("{}/missing", &mock_server.uri())).unwrap();
+        // Start a background HTTP server on a random local port
+        let mock_server = MockServer::start().await;
 
-    // If the request doesn't match any `Mock` mounted on our `MockServer` a 404
-    // is returned.
-    let status = get(missing_link_url).await.unwrap().status();
-    assert_eq!(status.as_u16(), 404);
+        // Preloaded data, languages are keyed by game and language
+        let language_mock_responses: Arc<Mutex<DashMap<(String, String),
+                                                      JsonValue>>> =
+            Arc::new(Mutex::new(DashMap::with_capacity(18)));
 
-    let mut ran_once: bool = false;
+        let github_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>> =
+            Arc::new(Mutex::new(DashMap::with_capacity(3)));
+
+        let aoe2net_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>> =
+            Arc::new(Mutex::new(DashMap::with_capacity(16)));
+
+        let in_memory_db = Arc::new(Mutex::new(InMemoryDb::default()));
+
+        let github_root = Url::parse(&mock_server.uri()).unwrap();
+        let aoe2_net_root =
+            Url::parse(&format!("{}/api", &mock_server.uri())).unwrap();
+
+        // URL
+        let missing_link_url =
+            Url::parse(&format!("{}/missing", &mock_server.uri())).unwrap();
+
+        // If the request doesn't match any `Mock` mounted on our `MockServer` a
+        // 404 is returned.
+        let status = get(missing_link_url).await.unwrap().status();
+        assert_eq!(status.as_u16(), 404);
 
-    for mut test_case in test_cases.0 {
-        // each test_case could be as well run in
-        // a thread from here on
         load_responses_from_fs(&mut test_case,
-                               aoe2net_mock_responses_clone.clone(),
-                               language_mock_responses_clone.clone(),
-                               github_mock_responses_clone.clone()).await
-                                                                   .unwrap();
+                               aoe2net_mock_responses.clone(),
+                               language_mock_responses.clone(),
+                               github_mock_responses.clone()).await
+                                                             .unwrap();
+
+        let game = test_case.parsed_request
+                            .game
+                            .clone()
+                            .unwrap_or_else(|| "aoe2de".to_string());
 
         mount_mocks(&aoe2net_api_roots,
+                    &game,
                     test_case.profile_ids.clone(),
                     test_case.last_match(),
                     &mock_server,
-                    aoe2net_mock_responses_clone.clone(),
-                    language_mock_responses_clone.clone(),
-                    github_mock_responses_clone.clone()).await;
-
-        if ran_once == false {
-            preload_data(Some(mock_client.clone()),
-                         Some(mock_client.clone()),
-                         in_memory_db_clone.clone(),
-                         github_root.clone(),
-                         aoe2_net_root.clone(),
-                         None,
-                         true).await
-                              .expect("Preloading data failed.");
-
-            ran_once = true;
-        }
+                    aoe2net_mock_responses.clone(),
+                    language_mock_responses.clone(),
+                    github_mock_responses.clone()).await;
+
+        preload_data(Some(mock_client.clone()),
+                     Some(mock_client.clone()),
+                     in_memory_db.clone(),
+                     github_root.clone(),
+                     aoe2_net_root.clone(),
+                     None,
+                     true).await
+                          .expect("Preloading data failed.");
 
         let result = build_result(test_case.parsed_request,
                                   a2n_client.clone(),
                                   aoe2_net_root.to_owned(),
-                                  in_memory_db_clone.clone(),
+                                  in_memory_db.clone(),
                                   None).await;
 
         assert_eq!(test_case.parsed_result, result);
@@ -188,7 +183,7 @@ async fn mock_test_match_info_result(test_cases: TestCases) {
 
 async fn load_responses_from_fs(test_case: &mut TestCase,
                                 aoe2net_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>>,
-                                language_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>>,
+                                language_mock_responses: Arc<Mutex<DashMap<(String, String), JsonValue>>>,
                                 github_mock_responses: Arc<Mutex<DashMap<String, JsonValue>>>)
                                 -> Result<(), TestCaseError> {
     for entry in fs::read_dir(test_case.resource_dir()).unwrap() {
@@ -251,19 +246,28 @@ async fn load_responses_from_fs(test_case: &mut TestCase,
                 }
             },
             "languages" => {
-                path.clone().push("/languages/");
-                for new_entry in fs::read_dir(path).unwrap() {
-                    let new_entry = new_entry.unwrap();
-                    let new_path = new_entry.path();
-                    let file_name = util::extract_filename(&new_path);
-
-                    let val: JsonValue = serde_json::from_reader(
-                        BufReader::new(fs::File::open(new_path).unwrap()),
-                    )
-                    .unwrap();
-                    {
-                        let guard = language_mock_responses.lock().await;
-                        guard.insert(file_name, val);
+                // One folder for each game, e.g. `languages/aoe2de/en.json`
+                for game_entry in fs::read_dir(path).unwrap() {
+                    let game_path = game_entry.unwrap().path();
+                    let game = game_path.file_name()
+                                        .unwrap()
+                                        .to_str()
+                                        .unwrap()
+                                        .to_string();
+
+                    for new_entry in fs::read_dir(game_path).unwrap() {
+                        let new_entry = new_entry.unwrap();
+                        let new_path = new_entry.path();
+                        let file_name = util::extract_filename(&new_path);
+
+                        let val: JsonValue = serde_json::from_reader(
+                            BufReader::new(fs::File::open(new_path).unwrap()),
+                        )
+                        .unwrap();
+                        {
+                            let guard = language_mock_responses.lock().await;
+                            guard.insert((game.clone(), file_name), val);
+                        }
                     }
                 }
             },
@@ -294,12 +298,14 @@ async fn load_responses_from_fs(test_case: &mut TestCase,
 }
 
 async fn mount_mocks(aoe2net_api_roots: &Vec<&str>,
+                     game: &str,
                      profile_ids: Vec<String>,
                      last_match: &JsonValue,
                      mock_server: &MockServer,
                      aoe2net_mock_responses: Arc<Mutex<DashMap<String,
                                        JsonValue>>>,
-                     language_mock_responses: Arc<Mutex<DashMap<String,
+                     language_mock_responses: Arc<Mutex<DashMap<(String,
+                                                                  String),
                                        JsonValue>>>,
                      github_mock_responses: Arc<Mutex<DashMap<String,
                                        JsonValue>>>) {
@@ -311,7 +317,7 @@ async fn mount_mocks(aoe2net_api_roots: &Vec<&str>,
                 for profile_id in &profile_ids {
                     Mock::given(method("GET"))
                         .and(wiremock::matchers::path(url_string.to_string()))
-                        .and(wiremock::matchers::query_param("game", "aoe2de"))
+                        .and(wiremock::matchers::query_param("game", game))
                         .and(wiremock::matchers::query_param(
                             "profile_id",
                             profile_id,
@@ -340,7 +346,7 @@ async fn mount_mocks(aoe2net_api_roots: &Vec<&str>,
                     }
                     Mock::given(method("GET"))
                         .and(wiremock::matchers::path(url_string.to_string()))
-                        .and(wiremock::matchers::query_param("game", "aoe2de"))
+                        .and(wiremock::matchers::query_param("game", game))
                         .and(wiremock::matchers::query_param(
                             "profile_id",
                             profile_id,
@@ -374,7 +380,7 @@ async fn mount_mocks(aoe2net_api_roots: &Vec<&str>,
 
                     Mock::given(method("GET"))
                         .and(wiremock::matchers::path(url_string.to_string()))
-                        .and(wiremock::matchers::query_param("game", "aoe2de"))
+                        .and(wiremock::matchers::query_param("game", game))
                         .and(wiremock::matchers::query_param(
                             "profile_id",
                             profile_id,
@@ -399,7 +405,7 @@ async fn mount_mocks(aoe2net_api_roots: &Vec<&str>,
                 // Language mocking
                 #[allow(unused_assignments)]
                 let mut clone_language_mock_responses: DashMap<
-                    std::string::String,
+                    (std::string::String, std::string::String),
                     JsonValue,
                 > = DashMap::with_capacity(18);
 
@@ -409,11 +415,12 @@ async fn mount_mocks(aoe2net_api_roots: &Vec<&str>,
                 }
 
                 for multiref in clone_language_mock_responses.iter() {
-                    let (lang_short, json) = (multiref.key(), multiref.value());
+                    let ((game, lang_short), json) =
+                        (multiref.key(), multiref.value());
                     let url_string = &format!("{}", root.clone());
                     Mock::given(method("GET"))
                         .and(wiremock::matchers::path(url_string.to_string()))
-                        .and(wiremock::matchers::query_param("game", "aoe2de"))
+                        .and(wiremock::matchers::query_param("game", game))
                         .and(wiremock::matchers::query_param(
                             "language", lang_short,
                         ))